@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+import "./IDLogVerifier.sol";
+
+/// Verifier of `DLogProof<Secp256k1, Keccak256>`, see src/cryptology_primitives/proofs/sigma_dlog_evm.rs
+///
+/// The challenge c is recomputed from the same Keccak256 transcript the prover used, then
+/// z * G + c * pk = R is checked with ecrecover:
+///   ecrecover(h, v, r, s) = r^-1 * (s * Q - h * G) with Q the point of x coordinate r and parity v
+///   r = pk.x, v = 27 + parity(pk.y), s = c * pk.x, h = -z * pk.x  gives  c * pk + z * G
+/// which is compared to the address of R.
+///
+/// This is the reference source of the verifier. The deployed code, DLogVerifier.bin, is assembled
+/// with etk from DLogVerifier.etk, which implements the same checks instruction by instruction:
+/// both revert on exactly the same calldata and otherwise return the same result.
+contract DLogVerifier is IDLogVerifier {
+    uint256 private constant P = 0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f;
+    uint256 private constant N = 0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141;
+    /// compressed generator
+    bytes private constant G = hex"0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    function verify(
+        uint256[2] calldata pk,
+        uint256[2] calldata pkTRandCommitment,
+        uint256 challengeResponse,
+        bytes calldata ctx
+    ) external view override returns (bool) {
+        // only the canonical encoding, ctx right after the head
+        uint256 ctxOffset;
+        assembly {
+            ctxOffset := ctx.offset
+        }
+        if (ctxOffset != 0xe4) {
+            revert();
+        }
+        if (!onCurve(pk) || !onCurve(pkTRandCommitment) || pk[0] >= N || challengeResponse >= N) {
+            return false;
+        }
+        uint256 c = challenge(pk, pkTRandCommitment, ctx);
+
+        uint256 s = mulmod(c, pk[0], N);
+        if (s == 0) {
+            return false;
+        }
+        uint256 h = (N - mulmod(challengeResponse, pk[0], N)) % N;
+        address recovered = recover(h, 27 + (pk[1] & 1), pk[0], s);
+        return recovered != address(0) && recovered == pointAddress(pkTRandCommitment);
+    }
+
+    /// ecrecover(h, v, r, s), address(0) also if the precompile call fails, where the ecrecover
+    /// builtin would revert
+    function recover(uint256 h, uint256 v, uint256 r, uint256 s) private view returns (address recovered) {
+        assembly {
+            let input := mload(0x40)
+            mstore(input, h)
+            mstore(add(input, 0x20), v)
+            mstore(add(input, 0x40), r)
+            mstore(add(input, 0x60), s)
+            mstore(0, 0)
+            if staticcall(gas(), 1, input, 0x80, 0, 0x20) {
+                recovered := mload(0)
+            }
+        }
+    }
+
+    /// y^2 = x^3 + 7 with both coordinates reduced
+    function onCurve(uint256[2] calldata point) private pure returns (bool) {
+        uint256 x = point[0];
+        uint256 y = point[1];
+        return x < P && y < P && mulmod(y, y, P) == addmod(mulmod(mulmod(x, x, P), x, P), 7, P);
+    }
+
+    function compressed(uint256[2] calldata point) private pure returns (bytes memory) {
+        return abi.encodePacked(uint8(2 + (point[1] & 1)), point[0]);
+    }
+
+    /// The address ecrecover returns for the point
+    function pointAddress(uint256[2] calldata point) private pure returns (address) {
+        return address(uint160(uint256(keccak256(abi.encodePacked(point[0], point[1])))));
+    }
+
+    /// Every message is length prefixed with a big endian u64, like Transcript::append_bytes.
+    /// challenge_scalar draws 48 bytes, the digests of the transcript followed by block 0 and 1,
+    /// and reduces them modulo n.
+    function challenge(
+        uint256[2] calldata pk,
+        uint256[2] calldata pkTRandCommitment,
+        bytes calldata ctx
+    ) private pure returns (uint256) {
+        bytes memory transcript = abi.encodePacked(
+            abi.encodePacked(uint64(7), "dom-sep", uint64(10), "sigma_dlog"),
+            abi.encodePacked(uint64(3), "ctx", uint64(ctx.length), ctx),
+            abi.encodePacked(uint64(4), "base", uint64(33), G),
+            abi.encodePacked(uint64(2), "pk", uint64(33), compressed(pk)),
+            abi.encodePacked(uint64(20), "pk_t_rand_commitment", uint64(33), compressed(pkTRandCommitment)),
+            abi.encodePacked(uint64(9), "challenge", uint64(8), uint64(48))
+        );
+        uint256 d0 = uint256(keccak256(abi.encodePacked(transcript, uint64(0))));
+        uint256 d1 = uint256(keccak256(abi.encodePacked(transcript, uint64(1))));
+        return addmod(mulmod(d0, 1 << 128, N), d1 >> 128, N);
+    }
+}
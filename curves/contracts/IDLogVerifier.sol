@@ -0,0 +1,17 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// Verifier of secp256k1 Schnorr proofs of knowledge of a discrete log, `DLogProof<Secp256k1, Keccak256>`
+/// from the curves crate. DLogVerifier.sol implements it, DLogVerifier.bin is the same verifier
+/// assembled from DLogVerifier.etk. Deploy DLogVerifier.bin and call it through this interface.
+interface IDLogVerifier {
+    /// Whether the proof shows knowledge of sk with pk = sk * G. Points are affine (x, y),
+    /// `ctx` is the context the proof was made with, empty for `DLogProof::prove`.
+    /// Reverts only on malformed calldata.
+    function verify(
+        uint256[2] calldata pk,
+        uint256[2] calldata pkTRandCommitment,
+        uint256 challengeResponse,
+        bytes calldata ctx
+    ) external view returns (bool);
+}
@@ -1,11 +1,16 @@
-use std::ops;
-
-use num_bigint::{BigInt, Sign};
-use num_traits::{Num, One, Zero};
+use std::{fmt, ops};
+
+use curv::elliptic::curves::{Curve, Scalar};
+use num_bigint::{BigInt, RandBigInt, Sign};
+use num_integer::Integer;
+use num_traits::{Num, One, Signed, Zero};
+use rand::{CryptoRng, RngCore};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use zeroize::Zeroize;
 
 use crate::arithmetic::errors::{ParseBigIntError, ParseBigIntErrorReason};
-use crate::arithmetic::traits::{Converter, InnerAccess, Wrap, WrappedBigInt};
+use crate::arithmetic::primes;
+use crate::arithmetic::traits::{BitManipulation, Converter, EGCD, InnerAccess, Modulo, Primes, Samplable, Wrap, WrappedBigInt};
 
 #[derive(PartialOrd, PartialEq, Ord, Eq, Clone)]
 pub struct NativeBigInt {
@@ -57,7 +62,7 @@ impl Converter for NativeBigInt {
     }
 
     fn from_bytes(bytes: &[u8]) -> Self {
-        BigInt::from_bytes_le(Sign::Plus, bytes).wrap()
+        BigInt::from_bytes_be(Sign::Plus, bytes).wrap()
     }
 
     fn to_str_radix(&self, radix: u8) -> String {
@@ -122,11 +127,175 @@ impl One for NativeBigInt {
 
 impl WrappedBigInt for NativeBigInt {}
 
+impl Modulo for NativeBigInt {
+    fn mod_pow(base: &Self, exponent: &Self, modulus: &Self) -> Self {
+        base.inner.modpow(&exponent.inner, &modulus.inner).wrap()
+    }
+
+    fn mod_mul(a: &Self, b: &Self, modulus: &Self) -> Self {
+        (a.inner.mod_floor(&modulus.inner) * b.inner.mod_floor(&modulus.inner))
+            .mod_floor(&modulus.inner)
+            .wrap()
+    }
+
+    fn mod_sub(a: &Self, b: &Self, modulus: &Self) -> Self {
+        (a.inner.mod_floor(&modulus.inner) - b.inner.mod_floor(&modulus.inner))
+            .mod_floor(&modulus.inner)
+            .wrap()
+    }
+
+    fn mod_add(a: &Self, b: &Self, modulus: &Self) -> Self {
+        (a.inner.mod_floor(&modulus.inner) + b.inner.mod_floor(&modulus.inner))
+            .mod_floor(&modulus.inner)
+            .wrap()
+    }
+
+    fn mod_inv(a: &Self, modulus: &Self) -> Option<Self> {
+        let (gcd, x, _) = Self::egcd(&a.modulus(modulus), modulus);
+        if gcd.is_one() {
+            Some(x.modulus(modulus))
+        } else {
+            None
+        }
+    }
+
+    fn modulus(&self, modulus: &Self) -> Self {
+        self.inner.mod_floor(&modulus.inner).wrap()
+    }
+}
+
+impl EGCD for NativeBigInt {
+    fn egcd(a: &Self, b: &Self) -> (Self, Self, Self) {
+        let gcd = a.inner.extended_gcd(&b.inner);
+        (gcd.gcd.wrap(), gcd.x.wrap(), gcd.y.wrap())
+    }
+}
+
+impl Samplable for NativeBigInt {
+    fn sample_below(upper: &Self) -> Self {
+        Self::sample_below_with_rng(upper, &mut rand::thread_rng())
+    }
+
+    fn sample_range(lower: &Self, upper: &Self) -> Self {
+        Self::sample_range_with_rng(lower, upper, &mut rand::thread_rng())
+    }
+
+    fn sample(bit_size: usize) -> Self {
+        Self::sample_with_rng(bit_size, &mut rand::thread_rng())
+    }
+
+    fn strict_sample(bit_size: usize) -> Self {
+        Self::strict_sample_with_rng(bit_size, &mut rand::thread_rng())
+    }
+
+    fn sample_below_with_rng(upper: &Self, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        assert!(upper.inner.is_positive(), "upper bound must be positive");
+        rng.gen_bigint_range(&BigInt::zero(), &upper.inner).wrap()
+    }
+
+    fn sample_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        BigInt::from_biguint(Sign::Plus, rng.gen_biguint(bit_size as u64)).wrap()
+    }
+
+    fn sample_range_with_rng(lower: &Self, upper: &Self, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        assert!(lower < upper, "lower bound must be less than upper bound");
+        rng.gen_bigint_range(&lower.inner, &upper.inner).wrap()
+    }
+
+    fn strict_sample_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        let mut n = Self::sample_with_rng(bit_size, rng);
+        if bit_size > 0 {
+            n.set_bit(bit_size - 1, true);
+        }
+        n
+    }
+}
+
+impl BitManipulation for NativeBigInt {
+    fn set_bit(&mut self, bit: usize, bit_val: bool) {
+        self.inner.set_bit(bit as u64, bit_val)
+    }
+
+    fn test_bit(&self, bit: usize) -> bool {
+        self.inner.bit(bit as u64)
+    }
+
+    fn bit_length(&self) -> usize {
+        self.inner.bits() as usize
+    }
+}
+
+impl Primes for NativeBigInt {
+    fn next_prime(&self) -> Self {
+        let two = NativeBigInt::from(2u32);
+        if self < &two {
+            return two;
+        }
+        let mut candidate = self + NativeBigInt::one();
+        candidate.set_bit(0, true);
+        while !candidate.is_probable_prime(primes::MILLER_RABIN_ROUNDS) {
+            candidate = candidate + &two;
+        }
+        candidate
+    }
+
+    fn is_probable_prime(&self, n: u32) -> bool {
+        primes::probably_prime(self, n)
+    }
+}
+
+/// Scalars of a curve as integers, e.g. to encrypt them with Paillier
+impl NativeBigInt {
+    /// The canonical representative in [0, q)
+    pub fn from_scalar<E: Curve>(scalar: &Scalar<E>) -> NativeBigInt {
+        NativeBigInt::from_bytes(&scalar.to_bytes())
+    }
+
+    /// The order q of the curve's prime order group
+    pub fn group_order<E: Curve>() -> NativeBigInt {
+        NativeBigInt::from_bytes(&curv::arithmetic::Converter::to_bytes(Scalar::<E>::group_order()))
+    }
+
+    /// Reduced modulo the group order q, negative values included
+    pub fn to_scalar<E: Curve>(&self) -> Scalar<E> {
+        let reduced = self.modulus(&NativeBigInt::group_order::<E>());
+        Scalar::from_bigint(&curv::arithmetic::Converter::from_bytes(&reduced.to_bytes()))
+    }
+}
+
+/// Serialized as a hex string, same as curv's BigInt
+impl Serialize for NativeBigInt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for NativeBigInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        NativeBigInt::from_hex(&hex).map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Debug for NativeBigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl fmt::Display for NativeBigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
 
 #[cfg(test)]
 mod test {
+    use curv::elliptic::curves::{Scalar, Secp256k1};
+
     use crate::arithmetic::big_native::NativeBigInt;
-    use crate::arithmetic::traits::InnerAccess;
+    use crate::arithmetic::traits::{Converter, InnerAccess, Modulo, Samplable};
 
     #[test]
     fn test_impl_from_macro() {
@@ -171,4 +340,54 @@ mod test {
         let owner_rem = NativeBigInt::from(15) % NativeBigInt::from(7);
         println!("owner_rem:{:?}", owner_rem.inner_ref());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let bn = NativeBigInt::from(0x0102_0304u32);
+        assert_eq!(bn.to_bytes(), vec![1, 2, 3, 4]);
+        assert_eq!(NativeBigInt::from_bytes(&bn.to_bytes()), bn);
+
+        // big-endian, like curv's BigInt; leading zero bytes don't change the value
+        let bytes = hex::decode("00ff0000000000000001").unwrap();
+        let bn = NativeBigInt::from_bytes(&bytes);
+        assert_eq!(bn, NativeBigInt::from_hex("ff0000000000000001").unwrap());
+        assert_eq!(bn.to_bytes(), bytes[1..].to_vec());
+        assert_eq!(NativeBigInt::from_bytes(&[]), NativeBigInt::from(0u32));
+    }
+
+    #[test]
+    fn test_scalar_conversion() {
+        let scalar = Scalar::<Secp256k1>::random();
+        let bn = NativeBigInt::from_scalar(&scalar);
+        assert_eq!(bn.to_scalar::<Secp256k1>(), scalar);
+
+        let order = NativeBigInt::group_order::<Secp256k1>();
+        assert_eq!(NativeBigInt::from_scalar(&-Scalar::<Secp256k1>::from(1)) + NativeBigInt::from(1u32), order);
+        assert_eq!((&bn + &order).to_scalar::<Secp256k1>(), scalar);
+        assert_eq!(NativeBigInt::from(-1i32).to_scalar::<Secp256k1>(), -Scalar::from(1));
+    }
+
+    #[test]
+    fn test_serde() {
+        let bn = NativeBigInt::from(-255i32);
+        let json = serde_json::to_string(&bn).unwrap();
+        assert_eq!(json, "\"-ff\"");
+        assert_eq!(serde_json::from_str::<NativeBigInt>(&json).unwrap(), bn);
+        assert!(serde_json::from_str::<NativeBigInt>("\"xyz\"").is_err());
+    }
+
+    #[test]
+    fn test_modulo() {
+        let modulus = NativeBigInt::from(97u32);
+        let a = NativeBigInt::from(-5i32);
+        assert_eq!(a.modulus(&modulus), NativeBigInt::from(92u32));
+        assert_eq!(NativeBigInt::mod_sub(&NativeBigInt::from(3u32), &NativeBigInt::from(5u32), &modulus), NativeBigInt::from(95u32));
+
+        let inv = NativeBigInt::mod_inv(&NativeBigInt::from(5u32), &modulus).unwrap();
+        assert_eq!(NativeBigInt::mod_mul(&inv, &NativeBigInt::from(5u32), &modulus), NativeBigInt::from(1u32));
+        assert!(NativeBigInt::mod_inv(&NativeBigInt::from(6u32), &NativeBigInt::from(9u32)).is_none());
+
+        let sample = NativeBigInt::sample_below(&modulus);
+        assert!(sample < modulus);
+    }
+}
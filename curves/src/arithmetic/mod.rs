@@ -1,4 +1,5 @@
 pub mod big_native;
 pub mod traits;
 pub mod errors;
-mod macros;
\ No newline at end of file
+pub mod primes;
+mod macros;
@@ -0,0 +1,191 @@
+//! Probabilistic prime testing and prime generation over [NativeBigInt].
+//!
+//! Candidates are first sieved by trial division with small primes, then checked with
+//! Miller-Rabin using random bases.
+
+use curv::cryptographic_primitives::hashing::Digest;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::traits::{BitManipulation, Converter, Modulo, Primes, Samplable};
+
+/// Miller-Rabin rounds, error probability of a composite passing is at most 4^-40
+pub const MILLER_RABIN_ROUNDS: u32 = 40;
+
+const SMALL_PRIMES: [u32; 54] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
+    97, 101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191,
+    193, 197, 199, 211, 223, 227, 229, 233, 239, 241, 251,
+];
+
+/// Miller-Rabin test, `n` must be positive
+pub(crate) fn probably_prime(n: &NativeBigInt, rounds: u32) -> bool {
+    probably_prime_with_rng(n, rounds, &mut rand::thread_rng())
+}
+
+/// Like [probably_prime], the random bases are drawn from `rng`
+pub(crate) fn probably_prime_with_rng(n: &NativeBigInt, rounds: u32, rng: &mut (impl RngCore + CryptoRng)) -> bool {
+    let two = NativeBigInt::from(2u32);
+    if n < &two {
+        return false;
+    }
+
+    for p in SMALL_PRIMES {
+        let p = NativeBigInt::from(p);
+        if n == &p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
+        }
+    }
+
+    // n - 1 = d * 2^s with d odd
+    let n_minus_one = n - NativeBigInt::one();
+    let mut d = n_minus_one.clone();
+    let mut s = 0usize;
+    while !d.test_bit(0) {
+        d = d / &two;
+        s += 1;
+    }
+
+    'witness: for _ in 0..rounds {
+        let base = NativeBigInt::sample_range_with_rng(&two, &n_minus_one, rng);
+        let mut x = NativeBigInt::mod_pow(&base, &d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = NativeBigInt::mod_mul(&x, &x, n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Generates a random prime of exactly `bit_size` bits
+pub fn generate_prime(bit_size: usize) -> NativeBigInt {
+    generate_prime_with_rng(bit_size, &mut rand::thread_rng())
+}
+
+pub fn generate_prime_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> NativeBigInt {
+    assert!(bit_size >= 2, "a prime has at least 2 bits");
+    loop {
+        let mut candidate = NativeBigInt::strict_sample_with_rng(bit_size, rng);
+        candidate.set_bit(0, true);
+        if probably_prime_with_rng(&candidate, MILLER_RABIN_ROUNDS, rng) {
+            return candidate;
+        }
+    }
+}
+
+/// Generates a random safe prime `p = 2q + 1` of exactly `bit_size` bits, where `q` is prime too
+pub fn generate_safe_prime(bit_size: usize) -> NativeBigInt {
+    generate_safe_prime_with_rng(bit_size, &mut rand::thread_rng())
+}
+
+pub fn generate_safe_prime_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> NativeBigInt {
+    assert!(bit_size >= 3, "a safe prime has at least 3 bits");
+    let two = NativeBigInt::from(2u32);
+    loop {
+        let mut q = NativeBigInt::strict_sample_with_rng(bit_size - 1, rng);
+        q.set_bit(0, true);
+        let p = &q * &two + NativeBigInt::one();
+        // cheap single-round filters before the full tests
+        if !probably_prime_with_rng(&q, 1, rng) || !probably_prime_with_rng(&p, 1, rng) {
+            continue;
+        }
+        if probably_prime_with_rng(&q, MILLER_RABIN_ROUNDS, rng) && probably_prime_with_rng(&p, MILLER_RABIN_ROUNDS, rng) {
+            return p;
+        }
+    }
+}
+
+/// Deterministically maps `input` to a prime of the digest's bit size.
+///
+/// Hashes `counter || input` for an increasing counter until the digest, with its top and
+/// lowest bits set, is a probable prime.
+pub fn hash_to_prime<H: Digest + Clone>(input: &[u8]) -> NativeBigInt {
+    let bit_size = H::output_size() * 8;
+    let mut counter = 0u32;
+    loop {
+        let digest = H::new()
+            .chain(counter.to_be_bytes())
+            .chain(input)
+            .finalize();
+        let mut candidate = NativeBigInt::from_bytes(&digest);
+        candidate.set_bit(bit_size - 1, true);
+        candidate.set_bit(0, true);
+        if candidate.is_probable_prime(MILLER_RABIN_ROUNDS) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::arithmetic::primes::{generate_prime, generate_prime_with_rng, generate_safe_prime, generate_safe_prime_with_rng, hash_to_prime, MILLER_RABIN_ROUNDS};
+    use crate::arithmetic::traits::{BitManipulation, Primes};
+
+    #[test]
+    fn test_is_probable_prime() {
+        let primes = [2u64, 3, 97, 65537, 2147483647, 18446744073709551557];
+        for p in primes {
+            assert!(NativeBigInt::from(p).is_probable_prime(MILLER_RABIN_ROUNDS), "{} is prime", p);
+        }
+
+        // 561 is a carmichael number
+        let composites = [0u64, 1, 4, 561, 65535, 2147483649, 18446744073709551559];
+        for c in composites {
+            assert!(!NativeBigInt::from(c).is_probable_prime(MILLER_RABIN_ROUNDS), "{} is composite", c);
+        }
+    }
+
+    #[test]
+    fn test_generate_prime() {
+        let p = generate_prime(256);
+        assert_eq!(p.bit_length(), 256);
+        assert!(p.is_probable_prime(MILLER_RABIN_ROUNDS));
+    }
+
+    #[test]
+    fn test_generate_prime_with_rng() {
+        let p = generate_prime_with_rng(256, &mut ChaCha20Rng::seed_from_u64(7));
+        assert_eq!(p, generate_prime_with_rng(256, &mut ChaCha20Rng::seed_from_u64(7)));
+        assert_ne!(p, generate_prime_with_rng(256, &mut ChaCha20Rng::seed_from_u64(8)));
+        assert_eq!(p.bit_length(), 256);
+        assert!(p.is_probable_prime(MILLER_RABIN_ROUNDS));
+
+        let p = generate_safe_prime_with_rng(128, &mut ChaCha20Rng::seed_from_u64(7));
+        assert_eq!(p, generate_safe_prime_with_rng(128, &mut ChaCha20Rng::seed_from_u64(7)));
+    }
+
+    #[test]
+    fn test_generate_safe_prime() {
+        let p = generate_safe_prime(128);
+        assert_eq!(p.bit_length(), 128);
+        let q = (&p - NativeBigInt::from(1u32)) / NativeBigInt::from(2u32);
+        assert!(q.is_probable_prime(MILLER_RABIN_ROUNDS));
+    }
+
+    #[test]
+    fn test_hash_to_prime_is_deterministic() {
+        let p1 = hash_to_prime::<sha3::Keccak256>(b"element");
+        let p2 = hash_to_prime::<sha3::Keccak256>(b"element");
+        let p3 = hash_to_prime::<sha3::Keccak256>(b"another element");
+        assert_eq!(p1, p2);
+        assert_ne!(p1, p3);
+        assert_eq!(p1.bit_length(), 256);
+        assert!(p1.is_probable_prime(MILLER_RABIN_ROUNDS));
+    }
+}
@@ -1,3 +1,5 @@
+use rand::{CryptoRng, RngCore};
+
 use crate::arithmetic::errors::ParseBigIntError;
 
 pub trait WrappedBigInt: InnerAccess + zeroize::Zeroize + Converter + num_traits::Num {}
@@ -42,4 +44,75 @@ pub trait Converter: Sized {
         array[N - bytes.len()..].copy_from_slice(&bytes);
         Some(array)
     }
-}
\ No newline at end of file
+}
+
+/// Modular arithmetic, results are always reduced into `[0, modulus)`
+pub trait Modulo: Sized {
+    /// Calculates base^(exponent) (mod m)
+    ///
+    /// Exponent must not be negative. Function will panic otherwise.
+    fn mod_pow(base: &Self, exponent: &Self, m: &Self) -> Self;
+
+    fn mod_mul(a: &Self, b: &Self, modulus: &Self) -> Self;
+
+    fn mod_sub(a: &Self, b: &Self, modulus: &Self) -> Self;
+
+    fn mod_add(a: &Self, b: &Self, modulus: &Self) -> Self;
+
+    /// Returns None if a and m are not coprime
+    fn mod_inv(a: &Self, m: &Self) -> Option<Self>;
+
+    fn modulus(&self, modulus: &Self) -> Self;
+}
+
+/// Extended euclidean algorithm
+pub trait EGCD: Sized {
+    /// Returns (gcd, x, y) such that a*x + b*y = gcd
+    fn egcd(a: &Self, b: &Self) -> (Self, Self, Self);
+}
+
+/// Generates random numbers
+pub trait Samplable {
+    /// Generates random number within `[0; upper)` range
+    fn sample_below(upper: &Self) -> Self;
+
+    /// Generates random number within `[lower; upper)` range
+    fn sample_range(lower: &Self, upper: &Self) -> Self;
+
+    /// Generates random number within `[0; 2^bit_size)` range
+    fn sample(bit_size: usize) -> Self;
+
+    /// Generates random number within `[2^(bit_size-1); 2^bit_size)` range
+    fn strict_sample(bit_size: usize) -> Self;
+
+    /// Like [Samplable::sample_below], with randomness from `rng`
+    fn sample_below_with_rng(upper: &Self, rng: &mut (impl RngCore + CryptoRng)) -> Self;
+
+    /// Like [Samplable::sample], with randomness from `rng`
+    fn sample_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> Self;
+
+    /// Like [Samplable::sample_range], with randomness from `rng`
+    fn sample_range_with_rng(lower: &Self, upper: &Self, rng: &mut (impl RngCore + CryptoRng)) -> Self;
+
+    /// Like [Samplable::strict_sample], with randomness from `rng`
+    fn strict_sample_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> Self;
+}
+
+pub trait BitManipulation {
+    fn set_bit(&mut self, bit: usize, bit_val: bool);
+
+    fn test_bit(&self, bit: usize) -> bool;
+
+    fn bit_length(&self) -> usize;
+}
+
+pub trait Primes {
+    /// Finds next prime number using probabilistic algorithms
+    fn next_prime(&self) -> Self;
+
+    /// Probabilistically determine whether number is prime
+    ///
+    /// If number is prime, `is_probable_prime` always returns true. If number is composite,
+    /// `is_probable_prime` probably return false. `n` is the number of Miller-Rabin rounds.
+    fn is_probable_prime(&self, n: u32) -> bool;
+}
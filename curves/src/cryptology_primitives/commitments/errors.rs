@@ -0,0 +1,18 @@
+use std::error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CommitmentError {
+    /// the message and blinding factor do not open the commitment
+    InvalidOpening,
+}
+
+impl Display for CommitmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommitmentError::InvalidOpening => write!(f, "commitment: invalid opening"),
+        }
+    }
+}
+
+impl error::Error for CommitmentError {}
@@ -0,0 +1,300 @@
+//! Pedersen commitments C = m * G + r * H: perfectly hiding, and binding as long as nobody knows
+//! log_G(H). H is hashed to the curve from a public seed, so nobody does.
+//!
+//! Commitments are additively homomorphic, commit(m1, r1) + commit(m2, r2) = commit(m1 + m2, r1 + r2).
+//! [PedersenOpeningProof] proves knowledge of an opening (m, r) without revealing it.
+//! <<Non-Interactive and Information-Theoretic Secure Verifiable Secret Sharing>> Pedersen
+
+use std::marker::PhantomData;
+use std::ops::Add;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::commitments::errors::CommitmentError;
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::sigma_protocol::{FiatShamir, FiatShamirProof, SigmaProtocol};
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::rng::random_scalar;
+use crate::elliptic::per_curve::PerCurve;
+
+const GENERATOR_H_LABEL: &[u8] = b"pedersen_generator_h";
+const TRANSCRIPT_LABEL: &[u8] = b"pedersen_opening";
+
+/// The second generator H, nobody knows log_G(H). Hashed to the curve on the first call only.
+pub fn generator_h<E: Curve>() -> Point<E> {
+    static GENERATOR_H: PerCurve = PerCurve::new();
+    GENERATOR_H.get_or_init::<E, _>(|| hash_to_point::<E>(GENERATOR_H_LABEL))
+}
+
+/// A generator with unknown discrete log for each seed, by try-and-increment: candidate encodings
+/// are drawn from a transcript over the seed, the curve name and G until one decodes to a point of
+/// the prime order subgroup.
+pub fn hash_to_point<E: Curve>(seed: &[u8]) -> Point<E> {
+    let generator = Point::<E>::generator().to_point();
+    let encoded_len = generator.to_bytes(true).len();
+
+    let mut transcript = Transcript::<sha3::Sha3_256>::new(seed);
+    transcript.append_bytes(b"curve", E::CURVE_NAME.as_bytes());
+    transcript.append_point(b"g", &generator);
+    loop {
+        let mut candidate = transcript.challenge_bytes(b"candidate", encoded_len);
+        if encoded_len == 33 {
+            // SEC1 compressed encoding, the first byte only selects the parity of y
+            candidate[0] = 0x02 | (candidate[0] & 1);
+        }
+        if let Ok(point) = Point::from_bytes(&candidate) {
+            return point;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PedersenCommitment<E: Curve> {
+    pub commitment: Point<E>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PedersenOpening<E: Curve> {
+    pub message: Scalar<E>,
+    pub blinding_factor: Scalar<E>,
+}
+
+impl<E: Curve> PedersenCommitment<E> {
+    pub fn commit(message: &Scalar<E>, blinding_factor: &Scalar<E>) -> PedersenCommitment<E> {
+        PedersenCommitment {
+            commitment: Point::generator() * message + generator_h::<E>() * blinding_factor,
+        }
+    }
+
+    /// Commits with a fresh random blinding factor, the opening must be kept secret until revealed
+    pub fn commit_random(message: &Scalar<E>) -> (PedersenCommitment<E>, PedersenOpening<E>) {
+        Self::commit_random_with_rng(message, &mut rand::thread_rng())
+    }
+
+    pub fn commit_random_with_rng(message: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (PedersenCommitment<E>, PedersenOpening<E>) {
+        let opening = PedersenOpening {
+            message: message.clone(),
+            blinding_factor: random_scalar(rng),
+        };
+        (Self::commit_opening(&opening), opening)
+    }
+
+    pub fn commit_opening(opening: &PedersenOpening<E>) -> PedersenCommitment<E> {
+        Self::commit(&opening.message, &opening.blinding_factor)
+    }
+
+    pub fn verify(&self, opening: &PedersenOpening<E>) -> Result<(), CommitmentError> {
+        if Self::commit_opening(opening) == *self {
+            Ok(())
+        } else {
+            Err(CommitmentError::InvalidOpening)
+        }
+    }
+}
+
+impl<E: Curve> Add for &PedersenCommitment<E> {
+    type Output = PedersenCommitment<E>;
+
+    fn add(self, rhs: Self) -> PedersenCommitment<E> {
+        PedersenCommitment {
+            commitment: &self.commitment + &rhs.commitment,
+        }
+    }
+}
+
+impl<E: Curve> Add for PedersenCommitment<E> {
+    type Output = PedersenCommitment<E>;
+
+    fn add(self, rhs: Self) -> PedersenCommitment<E> {
+        &self + &rhs
+    }
+}
+
+/// Opens the sum of the commitments opened by the operands
+impl<E: Curve> Add for &PedersenOpening<E> {
+    type Output = PedersenOpening<E>;
+
+    fn add(self, rhs: Self) -> PedersenOpening<E> {
+        PedersenOpening {
+            message: &self.message + &rhs.message,
+            blinding_factor: &self.blinding_factor + &rhs.blinding_factor,
+        }
+    }
+}
+
+impl<E: Curve> Add for PedersenOpening<E> {
+    type Output = PedersenOpening<E>;
+
+    fn add(self, rhs: Self) -> PedersenOpening<E> {
+        &self + &rhs
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PedersenOpeningProof<E: Curve, H: Digest + Clone> {
+    pub t_rand_commitment: Point<E>,
+    pub challenge_response: PedersenOpening<E>,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+/// Commit A = a * G + b * H, respond with z = (a - c * m, b - c * r), check commit(z) + c * C = A.
+/// The response is itself an opening, of A - c * C.
+pub struct PedersenOpeningSigma<E: Curve> {
+    _phantom: PhantomData<fn(E)>,
+}
+
+impl<E: Curve> SigmaProtocol<E> for PedersenOpeningSigma<E> {
+    type Statement = PedersenCommitment<E>;
+    type Witness = PedersenOpening<E>;
+    type Commitment = Point<E>;
+    type ProverState = PedersenOpening<E>;
+    type Response = PedersenOpening<E>;
+
+    fn commit(_statement: &PedersenCommitment<E>, _opening: &PedersenOpening<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Point<E>, PedersenOpening<E>) {
+        let (t_rand_commitment, t_rand) = PedersenCommitment::commit_random_with_rng(&random_scalar(rng), rng);
+        (t_rand_commitment.commitment, t_rand)
+    }
+
+    fn respond(_statement: &PedersenCommitment<E>, opening: &PedersenOpening<E>, t_rand: PedersenOpening<E>, challenge: &Scalar<E>) -> Result<PedersenOpening<E>, ProofError> {
+        Ok(PedersenOpening {
+            message: t_rand.message - challenge * &opening.message,
+            blinding_factor: t_rand.blinding_factor - challenge * &opening.blinding_factor,
+        })
+    }
+
+    fn verify(statement: &PedersenCommitment<E>, t_rand_commitment: &Point<E>, challenge: &Scalar<E>, challenge_response: &PedersenOpening<E>) -> Result<(), ProofError> {
+        let t_rand_verifier = PedersenCommitment::commit_opening(challenge_response).commitment + challenge * &statement.commitment;
+
+        if &t_rand_verifier == t_rand_commitment {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidResponse)
+        }
+    }
+
+    fn simulate(statement: &PedersenCommitment<E>, challenge: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Point<E>, PedersenOpening<E>) {
+        let challenge_response = PedersenOpening {
+            message: random_scalar(rng),
+            blinding_factor: random_scalar(rng),
+        };
+        let t_rand_commitment = PedersenCommitment::commit_opening(&challenge_response).commitment + challenge * &statement.commitment;
+        (t_rand_commitment, challenge_response)
+    }
+
+    fn append_statement<H: Digest + Clone>(statement: &PedersenCommitment<E>, transcript: &mut Transcript<H>) {
+        transcript.append_point(b"g", &Point::<E>::generator().to_point());
+        transcript.append_point(b"h", &generator_h::<E>());
+        transcript.append_point(b"commitment", &statement.commitment);
+    }
+
+    fn append_commitment<H: Digest + Clone>(t_rand_commitment: &Point<E>, transcript: &mut Transcript<H>) {
+        transcript.append_point(b"t_rand_commitment", t_rand_commitment);
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> PedersenOpeningProof<E, H> {
+    pub fn prove(commitment: &PedersenCommitment<E>, opening: &PedersenOpening<E>) -> PedersenOpeningProof<E, H> {
+        Self::prove_with_rng(commitment, opening, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(commitment: &PedersenCommitment<E>, opening: &PedersenOpening<E>, rng: &mut (impl RngCore + CryptoRng)) -> PedersenOpeningProof<E, H> {
+        let proof = FiatShamir::<E, PedersenOpeningSigma<E>, H>::prove_with_rng(commitment, opening, &mut Transcript::new(TRANSCRIPT_LABEL), rng)
+            .expect("an opening response can't fail");
+        PedersenOpeningProof {
+            t_rand_commitment: proof.commitment,
+            challenge_response: proof.response,
+            hash_choice: PhantomData,
+        }
+    }
+
+    pub fn verify(proof: &PedersenOpeningProof<E, H>, commitment: &PedersenCommitment<E>) -> Result<(), ProofError> {
+        let sigma_proof = FiatShamirProof::<E, PedersenOpeningSigma<E>> {
+            commitment: proof.t_rand_commitment.clone(),
+            response: proof.challenge_response.clone(),
+        };
+        FiatShamir::<E, PedersenOpeningSigma<E>, H>::verify(commitment, &sigma_proof, &mut Transcript::new(TRANSCRIPT_LABEL))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Ed25519, Point, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::commitments::errors::CommitmentError;
+    use crate::cryptology_primitives::commitments::pedersen::{generator_h, hash_to_point, PedersenCommitment, PedersenOpening, PedersenOpeningProof};
+    use crate::cryptology_primitives::errors::ProofError;
+
+    #[test]
+    fn test_generator_h() {
+        let h = generator_h::<Secp256k1>();
+        assert_eq!(h, generator_h::<Secp256k1>());
+        assert_ne!(h, Point::generator().to_point());
+        assert!(!h.is_zero());
+
+        let h = generator_h::<Ed25519>();
+        assert_ne!(h, Point::generator().to_point());
+        assert!(!h.is_zero());
+        assert_eq!(h, hash_to_point::<Ed25519>(b"pedersen_generator_h"));
+    }
+
+    #[test]
+    fn test_pedersen_commitment() {
+        let message = Scalar::<Secp256k1>::from(42);
+        let (commitment, opening) = PedersenCommitment::commit_random(&message);
+        assert!(commitment.verify(&opening).is_ok());
+
+        let wrong_message = PedersenOpening {
+            message: Scalar::from(43),
+            ..opening.clone()
+        };
+        assert_eq!(commitment.verify(&wrong_message), Err(CommitmentError::InvalidOpening));
+
+        // same message, fresh blinding factor: unlinkable commitments
+        let (other_commitment, _) = PedersenCommitment::commit_random(&message);
+        assert_ne!(commitment, other_commitment);
+
+        let commitment_json = serde_json::to_string(&commitment).unwrap();
+        let decoded: PedersenCommitment<Secp256k1> = serde_json::from_str(&commitment_json).unwrap();
+        let opening_json = serde_json::to_string(&opening).unwrap();
+        let decoded_opening: PedersenOpening<Secp256k1> = serde_json::from_str(&opening_json).unwrap();
+        assert!(decoded.verify(&decoded_opening).is_ok());
+    }
+
+    #[test]
+    fn test_pedersen_commitment_addition() {
+        let (commitment1, opening1) = PedersenCommitment::<Ed25519>::commit_random(&Scalar::from(20));
+        let (commitment2, opening2) = PedersenCommitment::<Ed25519>::commit_random(&Scalar::from(22));
+
+        let sum = &commitment1 + &commitment2;
+        let opening = opening1 + opening2;
+        assert_eq!(opening.message, Scalar::from(42));
+        assert!(sum.verify(&opening).is_ok());
+        assert_eq!(sum, PedersenCommitment::commit(&Scalar::from(42), &opening.blinding_factor));
+    }
+
+    #[test]
+    fn test_pedersen_opening_proof() {
+        let (commitment, opening) = PedersenCommitment::<Secp256k1>::commit_random(&Scalar::random());
+        let proof = PedersenOpeningProof::<Secp256k1, sha3::Keccak256>::prove(&commitment, &opening);
+        assert!(PedersenOpeningProof::verify(&proof, &commitment).is_ok());
+
+        let proof_json = serde_json::to_string(&proof).unwrap();
+        let decoded: PedersenOpeningProof<Secp256k1, sha3::Keccak256> = serde_json::from_str(&proof_json).unwrap();
+        assert!(PedersenOpeningProof::verify(&decoded, &commitment).is_ok());
+
+        let other_commitment = &commitment + &PedersenCommitment::commit(&Scalar::from(1), &Scalar::zero());
+        assert_eq!(PedersenOpeningProof::verify(&proof, &other_commitment), Err(ProofError::InvalidResponse));
+
+        // a prover who doesn't know the opening can't make the proof verify
+        let wrong_opening = PedersenOpening {
+            message: Scalar::random(),
+            blinding_factor: Scalar::random(),
+        };
+        let forged = PedersenOpeningProof::<Secp256k1, sha3::Keccak256>::prove(&commitment, &wrong_opening);
+        assert_eq!(PedersenOpeningProof::verify(&forged, &commitment), Err(ProofError::InvalidResponse));
+    }
+}
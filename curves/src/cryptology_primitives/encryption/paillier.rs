@@ -0,0 +1,320 @@
+//! Paillier cryptosystem, additively homomorphic public key encryption.
+//! With N = p*q and g = N + 1, a plaintext m in Z_N is encrypted as c = (1 + m*N) * r^N mod N^2.
+//! Enc(m1) * Enc(m2) = Enc(m1 + m2) and Enc(m)^k = Enc(k*m).
+//!
+//! Decryption works modulo p^2 and q^2 separately and recombines with the CRT, section 7 of
+//! <<Public-Key Cryptosystems Based on Composite Degree Residuosity Classes>> Paillier
+//! https://link.springer.com/content/pdf/10.1007/3-540-48910-X_16.pdf
+
+use num_traits::One;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::primes::{generate_prime_with_rng, generate_safe_prime_with_rng};
+use crate::arithmetic::traits::{BitManipulation, EGCD, Modulo, Samplable};
+
+/// Deserializing checks n > 1 and nn = n^2, a peer can't hand over a key that breaks the
+/// homomorphic operations
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedEncryptionKey")]
+pub struct EncryptionKey {
+    pub n: NativeBigInt,
+    pub nn: NativeBigInt,
+}
+
+#[derive(Deserialize)]
+struct UncheckedEncryptionKey {
+    n: NativeBigInt,
+    nn: NativeBigInt,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DecryptionKey {
+    p: NativeBigInt,
+    q: NativeBigInt,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ciphertext {
+    pub c: NativeBigInt,
+}
+
+/// The r in r^N, a unit of Z_N
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Randomness(pub NativeBigInt);
+
+pub struct Paillier;
+
+impl EncryptionKey {
+    fn from_modulus(n: NativeBigInt) -> EncryptionKey {
+        EncryptionKey {
+            nn: &n * &n,
+            n,
+        }
+    }
+}
+
+impl TryFrom<UncheckedEncryptionKey> for EncryptionKey {
+    type Error = &'static str;
+
+    fn try_from(key: UncheckedEncryptionKey) -> Result<EncryptionKey, Self::Error> {
+        if key.n <= NativeBigInt::one() || key.nn != &key.n * &key.n {
+            return Err("invalid Paillier encryption key, expected n > 1 and nn = n^2");
+        }
+        Ok(EncryptionKey { n: key.n, nn: key.nn })
+    }
+}
+
+impl DecryptionKey {
+    pub fn encryption_key(&self) -> EncryptionKey {
+        EncryptionKey::from_modulus(&self.p * &self.q)
+    }
+
+    /// sigma with sigma^N = rho mod N, i.e. rho^(N^-1 mod phi(N)). Only a unit rho has one.
+    pub(crate) fn nth_root(&self, rho: &NativeBigInt) -> NativeBigInt {
+        let n = &self.p * &self.q;
+        let phi = (&self.p - NativeBigInt::one()) * (&self.q - NativeBigInt::one());
+        let n_inv = NativeBigInt::mod_inv(&n, &phi).expect("N is coprime to phi(N) for primes of equal size");
+        NativeBigInt::mod_pow(rho, &n_inv, &n)
+    }
+}
+
+impl Drop for DecryptionKey {
+    fn drop(&mut self) {
+        self.p.zeroize();
+        self.q.zeroize();
+    }
+}
+
+impl Randomness {
+    pub fn sample(ek: &EncryptionKey) -> Randomness {
+        Self::sample_with_rng(ek, &mut rand::thread_rng())
+    }
+
+    pub fn sample_with_rng(ek: &EncryptionKey, rng: &mut (impl RngCore + CryptoRng)) -> Randomness {
+        loop {
+            let r = NativeBigInt::sample_below_with_rng(&ek.n, rng);
+            let (gcd, _, _) = NativeBigInt::egcd(&r, &ek.n);
+            if gcd.is_one() {
+                return Randomness(r);
+            }
+        }
+    }
+}
+
+impl Paillier {
+    /// Key pair with a modulus of `bit_size` bits from two random primes of equal size
+    pub fn keypair(bit_size: usize) -> (EncryptionKey, DecryptionKey) {
+        Self::keypair_with_rng(bit_size, &mut rand::thread_rng())
+    }
+
+    pub fn keypair_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> (EncryptionKey, DecryptionKey) {
+        Self::keypair_from(bit_size, |bits| generate_prime_with_rng(bits, rng))
+    }
+
+    /// Key pair whose primes are safe primes, slower to generate but needed by some of the
+    /// zero knowledge proofs about the modulus
+    pub fn keypair_with_safe_primes(bit_size: usize) -> (EncryptionKey, DecryptionKey) {
+        Self::keypair_with_safe_primes_with_rng(bit_size, &mut rand::thread_rng())
+    }
+
+    pub fn keypair_with_safe_primes_with_rng(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> (EncryptionKey, DecryptionKey) {
+        Self::keypair_from(bit_size, |bits| generate_safe_prime_with_rng(bits, rng))
+    }
+
+    pub fn encrypt(ek: &EncryptionKey, m: &NativeBigInt) -> Ciphertext {
+        Self::encrypt_with_randomness(ek, m, &Randomness::sample(ek))
+    }
+
+    pub fn encrypt_with_rng(ek: &EncryptionKey, m: &NativeBigInt, rng: &mut (impl RngCore + CryptoRng)) -> Ciphertext {
+        Self::encrypt_with_randomness(ek, m, &Randomness::sample_with_rng(ek, rng))
+    }
+
+    /// (1 + m*N) * r^N mod N^2, deterministic given r
+    pub fn encrypt_with_randomness(ek: &EncryptionKey, m: &NativeBigInt, r: &Randomness) -> Ciphertext {
+        let gm = (NativeBigInt::one() + m.modulus(&ek.n) * &ek.n).modulus(&ek.nn);
+        let rn = NativeBigInt::mod_pow(&r.0, &ek.n, &ek.nn);
+        Ciphertext {
+            c: NativeBigInt::mod_mul(&gm, &rn, &ek.nn),
+        }
+    }
+
+    /// m_p = L_p(c^(p-1) mod p^2) * h_p mod p with L_p(x) = (x - 1) / p and h_p = L_p(g^(p-1) mod p^2)^-1,
+    /// same for q, then m = CRT(m_p, m_q)
+    pub fn decrypt(dk: &DecryptionKey, ciphertext: &Ciphertext) -> NativeBigInt {
+        let n = &dk.p * &dk.q;
+        let g = &n + NativeBigInt::one();
+        let m_p = Self::decrypt_modulo_prime(&ciphertext.c, &g, &dk.p);
+        let m_q = Self::decrypt_modulo_prime(&ciphertext.c, &g, &dk.q);
+
+        let p_inv = NativeBigInt::mod_inv(&dk.p, &dk.q).expect("p and q are distinct primes");
+        let h = NativeBigInt::mod_mul(&NativeBigInt::mod_sub(&m_q, &m_p, &dk.q), &p_inv, &dk.q);
+        m_p + h * &dk.p
+    }
+
+    /// Enc(m1) * Enc(m2) = Enc(m1 + m2 mod N)
+    pub fn add(ek: &EncryptionKey, c1: &Ciphertext, c2: &Ciphertext) -> Ciphertext {
+        Ciphertext {
+            c: NativeBigInt::mod_mul(&c1.c, &c2.c, &ek.nn),
+        }
+    }
+
+    /// Enc(m)^k = Enc(k*m mod N), k is taken modulo N
+    pub fn mul(ek: &EncryptionKey, c: &Ciphertext, k: &NativeBigInt) -> Ciphertext {
+        Ciphertext {
+            c: NativeBigInt::mod_pow(&c.c, &k.modulus(&ek.n), &ek.nn),
+        }
+    }
+
+    fn keypair_from(bit_size: usize, mut prime: impl FnMut(usize) -> NativeBigInt) -> (EncryptionKey, DecryptionKey) {
+        let prime_bits = bit_size / 2;
+        loop {
+            let p = prime(prime_bits);
+            let q = prime(bit_size - prime_bits);
+            let n = &p * &q;
+            if p == q || n.bit_length() != bit_size {
+                continue;
+            }
+            let dk = DecryptionKey { p, q };
+            return (EncryptionKey::from_modulus(n), dk);
+        }
+    }
+
+    fn decrypt_modulo_prime(c: &NativeBigInt, g: &NativeBigInt, p: &NativeBigInt) -> NativeBigInt {
+        let pp = p * p;
+        let p_minus_one = p - NativeBigInt::one();
+        let l = |x: NativeBigInt| (x - NativeBigInt::one()) / p;
+
+        let h = l(NativeBigInt::mod_pow(g, &p_minus_one, &pp));
+        let h_inv = NativeBigInt::mod_inv(&h, p).expect("h_p is invertible for g = N + 1");
+        let m = l(NativeBigInt::mod_pow(c, &p_minus_one, &pp));
+        NativeBigInt::mod_mul(&m, &h_inv, p)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::arithmetic::traits::{BitManipulation, Modulo, Samplable};
+    use crate::cryptology_primitives::encryption::paillier::{Ciphertext, DecryptionKey, EncryptionKey, Paillier, Randomness};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let (ek, dk) = Paillier::keypair(1024);
+        assert_eq!(ek.n.bit_length(), 1024);
+        assert_eq!(dk.encryption_key(), ek);
+
+        for m in [NativeBigInt::from(0u32), NativeBigInt::from(42u32), &ek.n - NativeBigInt::from(1u32)] {
+            let c = Paillier::encrypt(&ek, &m);
+            assert_eq!(Paillier::decrypt(&dk, &c), m);
+        }
+    }
+
+    #[test]
+    fn test_explicit_randomness() {
+        let (ek, dk) = Paillier::keypair(512);
+        let m = NativeBigInt::from(7u32);
+        let r = Randomness::sample(&ek);
+
+        let c1 = Paillier::encrypt_with_randomness(&ek, &m, &r);
+        let c2 = Paillier::encrypt_with_randomness(&ek, &m, &r);
+        assert_eq!(c1, c2);
+
+        let c3 = Paillier::encrypt_with_randomness(&ek, &m, &Randomness::sample(&ek));
+        assert_ne!(c1, c3);
+        assert_eq!(Paillier::decrypt(&dk, &c3), m);
+    }
+
+    #[test]
+    fn test_homomorphic_identities() {
+        let (ek, dk) = Paillier::keypair(512);
+        let m1 = NativeBigInt::sample_below(&ek.n);
+        let m2 = NativeBigInt::sample_below(&ek.n);
+        let k = NativeBigInt::sample_below(&ek.n);
+        let c1 = Paillier::encrypt(&ek, &m1);
+        let c2 = Paillier::encrypt(&ek, &m2);
+
+        // Dec(Enc(m1) * Enc(m2)) = m1 + m2 mod N
+        let sum = Paillier::add(&ek, &c1, &c2);
+        assert_eq!(Paillier::decrypt(&dk, &sum), NativeBigInt::mod_add(&m1, &m2, &ek.n));
+
+        // Dec(Enc(m)^k) = k * m mod N
+        let product = Paillier::mul(&ek, &c1, &k);
+        assert_eq!(Paillier::decrypt(&dk, &product), NativeBigInt::mod_mul(&m1, &k, &ek.n));
+
+        // Dec(Enc(m1)^k * Enc(m2)) = k * m1 + m2 mod N
+        let affine = Paillier::add(&ek, &product, &c2);
+        let expected = NativeBigInt::mod_add(&NativeBigInt::mod_mul(&m1, &k, &ek.n), &m2, &ek.n);
+        assert_eq!(Paillier::decrypt(&dk, &affine), expected);
+
+        // Enc(m)^-1 = Enc(-m)
+        let negated = Paillier::mul(&ek, &c1, &(&ek.n - NativeBigInt::from(1u32)));
+        assert_eq!(Paillier::decrypt(&dk, &Paillier::add(&ek, &negated, &c1)), NativeBigInt::from(0u32));
+
+        // explicit randomness multiplies: Enc(m1; r1) * Enc(m2; r2) = Enc(m1 + m2; r1 * r2)
+        let r1 = Randomness::sample(&ek);
+        let r2 = Randomness::sample(&ek);
+        let r12 = Randomness(NativeBigInt::mod_mul(&r1.0, &r2.0, &ek.n));
+        assert_eq!(
+            Paillier::add(&ek, &Paillier::encrypt_with_randomness(&ek, &m1, &r1), &Paillier::encrypt_with_randomness(&ek, &m2, &r2)),
+            Paillier::encrypt_with_randomness(&ek, &(&m1 + &m2), &r12)
+        );
+    }
+
+    #[test]
+    fn test_safe_prime_keypair() {
+        let (ek, dk) = Paillier::keypair_with_safe_primes(256);
+        assert_eq!(ek.n.bit_length(), 256);
+        let m = NativeBigInt::from(1234u32);
+        assert_eq!(Paillier::decrypt(&dk, &Paillier::encrypt(&ek, &m)), m);
+    }
+
+    #[test]
+    fn test_serde() {
+        let (ek, dk) = Paillier::keypair(512);
+        let c = Paillier::encrypt(&ek, &NativeBigInt::from(99u32));
+
+        let ek_json = serde_json::to_string(&ek).unwrap();
+        let dk_json = serde_json::to_string(&dk).unwrap();
+        let c_json = serde_json::to_string(&c).unwrap();
+
+        let ek2: EncryptionKey = serde_json::from_str(&ek_json).unwrap();
+        let dk2: DecryptionKey = serde_json::from_str(&dk_json).unwrap();
+        let c2: Ciphertext = serde_json::from_str(&c_json).unwrap();
+        assert_eq!(ek2, ek);
+        assert_eq!(c2, c);
+        assert_eq!(Paillier::decrypt(&dk2, &c2), NativeBigInt::from(99u32));
+
+        let mut forged = ek.clone();
+        forged.nn = &forged.nn + NativeBigInt::from(1u32);
+        assert!(serde_json::from_str::<EncryptionKey>(&serde_json::to_string(&forged).unwrap()).is_err());
+        let one = EncryptionKey { n: NativeBigInt::from(1u32), nn: NativeBigInt::from(1u32) };
+        assert!(serde_json::from_str::<EncryptionKey>(&serde_json::to_string(&one).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_keypair_with_seeded_rng() {
+        let (ek, dk) = Paillier::keypair_with_rng(512, &mut ChaCha20Rng::seed_from_u64(7));
+        assert_eq!(ek, Paillier::keypair_with_rng(512, &mut ChaCha20Rng::seed_from_u64(7)).0);
+        assert_ne!(ek, Paillier::keypair_with_rng(512, &mut ChaCha20Rng::seed_from_u64(8)).0);
+        let c = Paillier::encrypt(&ek, &NativeBigInt::from(42u32));
+        assert_eq!(Paillier::decrypt(&dk, &c), NativeBigInt::from(42u32));
+    }
+
+    #[test]
+    fn test_encrypt_with_seeded_rng() {
+        let (ek, dk) = Paillier::keypair(512);
+        let m = NativeBigInt::from(42u32);
+        let c1 = Paillier::encrypt_with_rng(&ek, &m, &mut ChaCha20Rng::seed_from_u64(7));
+        let c2 = Paillier::encrypt_with_rng(&ek, &m, &mut ChaCha20Rng::seed_from_u64(7));
+        assert_eq!(c1, c2);
+        assert_eq!(c1, Paillier::encrypt_with_randomness(&ek, &m, &Randomness::sample_with_rng(&ek, &mut ChaCha20Rng::seed_from_u64(7))));
+        assert_ne!(c1, Paillier::encrypt_with_rng(&ek, &m, &mut ChaCha20Rng::seed_from_u64(8)));
+        assert_eq!(Paillier::decrypt(&dk, &c1), m);
+    }
+}
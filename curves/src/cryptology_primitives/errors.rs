@@ -0,0 +1,120 @@
+use std::error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofError {
+    /// the verification equation doesn't hold for the prover's response
+    InvalidResponse,
+    /// the public key is the identity point, which anyone can prove knowledge of
+    IdentityPublicKey,
+    /// the prover's commitment is the identity point
+    IdentityCommitment,
+    /// a received point is not a valid point of the expected group
+    MalformedPoint,
+    /// a hash, challenge or hash commitment doesn't match the recomputed value
+    HashMismatch,
+    /// the proof has the wrong number of elements for the statement
+    MalformedProof,
+    /// the prover's witness doesn't satisfy the statement, no valid proof can be made
+    InvalidWitness,
+    /// the encoded proof has a format version this verifier doesn't know
+    UnsupportedVersion,
+    /// the encoded proof was made on another curve than the verifier expects
+    CurveMismatch,
+    /// the encoded proof was made with another hash function than the verifier expects
+    DigestMismatch,
+}
+
+impl Display for ProofError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProofError::InvalidResponse => write!(f, "ProofError: invalid response"),
+            ProofError::IdentityPublicKey => write!(f, "ProofError: public key is the identity"),
+            ProofError::IdentityCommitment => write!(f, "ProofError: commitment is the identity"),
+            ProofError::MalformedPoint => write!(f, "ProofError: malformed point"),
+            ProofError::HashMismatch => write!(f, "ProofError: hash mismatch"),
+            ProofError::MalformedProof => write!(f, "ProofError: malformed proof"),
+            ProofError::InvalidWitness => write!(f, "ProofError: witness doesn't satisfy the statement"),
+            ProofError::UnsupportedVersion => write!(f, "ProofError: unsupported encoding version"),
+            ProofError::CurveMismatch => write!(f, "ProofError: curve mismatch"),
+            ProofError::DigestMismatch => write!(f, "ProofError: digest mismatch"),
+        }
+    }
+}
+
+impl error::Error for ProofError {}
+
+/// Indices of the proofs that failed individual verification after a batch didn't verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchProofError {
+    pub invalid_indices: Vec<usize>,
+}
+
+impl Display for BatchProofError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ProofError: invalid proofs at indices {:?}", self.invalid_indices)
+    }
+}
+
+impl error::Error for BatchProofError {}
+
+/// Why a run of a two-party protocol failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// a proof or commitment received from the peer doesn't verify
+    Proof(ProofError),
+    /// the peer's Paillier modulus is shorter than the protocol requires
+    SmallPaillierModulus,
+    /// the signature computed from the peer's messages doesn't verify under the joint public key
+    InvalidSignature,
+}
+
+impl From<ProofError> for ProtocolError {
+    fn from(error: ProofError) -> Self {
+        ProtocolError::Proof(error)
+    }
+}
+
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolError::Proof(error) => write!(f, "ProtocolError: {}", error),
+            ProtocolError::SmallPaillierModulus => write!(f, "ProtocolError: Paillier modulus too small"),
+            ProtocolError::InvalidSignature => write!(f, "ProtocolError: invalid signature"),
+        }
+    }
+}
+
+impl error::Error for ProtocolError {}
+
+/// Why a received protocol message was rejected before reaching the protocol code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// the envelope has a format version this party doesn't know
+    UnsupportedVersion(u16),
+    /// the message belongs to another protocol
+    ProtocolMismatch,
+    /// the message belongs to another session
+    SessionMismatch,
+    /// the message isn't the one this party expects at this point of the protocol
+    UnexpectedRound,
+    /// the message claims to come from this party itself or from an unknown party
+    UnexpectedSender,
+    /// the bytes aren't a valid encoding of an envelope of the expected message type
+    Encoding(String),
+}
+
+impl Display for EnvelopeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvelopeError::UnsupportedVersion(version) => write!(f, "EnvelopeError: unsupported version {}", version),
+            EnvelopeError::ProtocolMismatch => write!(f, "EnvelopeError: protocol mismatch"),
+            EnvelopeError::SessionMismatch => write!(f, "EnvelopeError: session mismatch"),
+            EnvelopeError::UnexpectedRound => write!(f, "EnvelopeError: unexpected round"),
+            EnvelopeError::UnexpectedSender => write!(f, "EnvelopeError: unexpected sender"),
+            EnvelopeError::Encoding(reason) => write!(f, "EnvelopeError: {}", reason),
+        }
+    }
+}
+
+impl error::Error for EnvelopeError {}
@@ -0,0 +1,298 @@
+//! RSA accumulator: a constant size commitment to a set, with short proofs of membership and
+//! non-membership. Elements are mapped to primes with hash_to_prime, the accumulator value is
+//! A = g^(x_1 * ... * x_n) in the hidden-order group.
+//!
+//! A membership witness for x is w = g^(product of the other elements), verified by w^x = A.
+//! A non-membership witness for x is (a, B) with a*u + b*x = 1 where u is the accumulated product
+//! and B = g^b, verified by A^a * B^x = g.
+//! https://eprint.iacr.org/2018/1188.pdf <<Batching Techniques for Accumulators>>
+//! https://link.springer.com/chapter/10.1007/978-3-540-72738-5_17 <<Universal Accumulators>> Li, Li, Xue
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use num_traits::{One, Zero};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::primes::hash_to_prime;
+use crate::arithmetic::traits::{EGCD, Modulo};
+use crate::cryptology_primitives::hidden_order::errors::AccumulatorError;
+use crate::cryptology_primitives::hidden_order::rsa_group::HiddenOrderGroup;
+
+const GENERATOR_SEED: &[u8] = b"rsa accumulator generator";
+
+pub struct RsaAccumulator<H: Digest + Clone> {
+    pub generator: HiddenOrderGroup,
+    pub value: HiddenOrderGroup,
+    elements: Vec<NativeBigInt>,
+    hash_choice: PhantomData<fn(H)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MembershipWitness {
+    pub witness: HiddenOrderGroup,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonMembershipWitness {
+    pub a: NativeBigInt,
+    pub b: HiddenOrderGroup,
+}
+
+impl<H: Digest + Clone> RsaAccumulator<H> {
+    /// An empty accumulator over Z_N^*/{±1}; the generator is hashed to the group so that
+    /// nobody knows its discrete log.
+    pub fn new(modulus: &NativeBigInt) -> RsaAccumulator<H> {
+        let generator = HiddenOrderGroup::hash_to_group::<H>(modulus, GENERATOR_SEED);
+        RsaAccumulator {
+            value: generator.clone(),
+            generator,
+            elements: Vec::new(),
+            hash_choice: PhantomData,
+        }
+    }
+
+    pub fn contains(&self, element: &[u8]) -> bool {
+        self.elements.contains(&hash_to_prime::<H>(element))
+    }
+
+    /// Adds an element and returns its membership witness, the accumulator value before the add
+    pub fn add(&mut self, element: &[u8]) -> Result<MembershipWitness, AccumulatorError> {
+        let prime = hash_to_prime::<H>(element);
+        if self.elements.contains(&prime) {
+            return Err(AccumulatorError::AlreadyAccumulated);
+        }
+        let witness = self.value.clone();
+        self.value = self.value.exp(&prime);
+        self.elements.push(prime);
+        Ok(MembershipWitness { witness })
+    }
+
+    /// Removes an element, the new accumulator value is the element's membership witness
+    pub fn delete(&mut self, element: &[u8]) -> Result<(), AccumulatorError> {
+        let prime = hash_to_prime::<H>(element);
+        let position = self.elements.iter()
+            .position(|accumulated| accumulated == &prime)
+            .ok_or(AccumulatorError::NotAccumulated)?;
+        self.elements.swap_remove(position);
+        self.value = self.generator.exp(&self.product());
+        Ok(())
+    }
+
+    pub fn membership_witness(&self, element: &[u8]) -> Result<MembershipWitness, AccumulatorError> {
+        let prime = hash_to_prime::<H>(element);
+        if !self.elements.contains(&prime) {
+            return Err(AccumulatorError::NotAccumulated);
+        }
+        let others = self.elements.iter()
+            .filter(|accumulated| *accumulated != &prime)
+            .fold(NativeBigInt::one(), |acc, x| acc * x);
+        Ok(MembershipWitness { witness: self.generator.exp(&others) })
+    }
+
+    pub fn non_membership_witness(&self, element: &[u8]) -> Result<NonMembershipWitness, AccumulatorError> {
+        let prime = hash_to_prime::<H>(element);
+        if self.elements.contains(&prime) {
+            return Err(AccumulatorError::AlreadyAccumulated);
+        }
+        let product = self.product();
+        let (gcd, a, _) = NativeBigInt::egcd(&product, &prime);
+        if !gcd.is_one() {
+            return Err(AccumulatorError::AlreadyAccumulated);
+        }
+        // keep a small: a in [0, x), then b = (1 - a*u) / x is exact
+        let a = a.modulus(&prime);
+        let b = (NativeBigInt::one() - &a * &product) / &prime;
+        Ok(NonMembershipWitness {
+            a,
+            b: self.generator.exp(&b),
+        })
+    }
+
+    pub fn verify_membership(&self, element: &[u8], witness: &MembershipWitness) -> Result<(), AccumulatorError> {
+        let prime = hash_to_prime::<H>(element);
+        if witness.witness.exp(&prime) == self.value {
+            Ok(())
+        } else {
+            Err(AccumulatorError::InvalidWitness)
+        }
+    }
+
+    pub fn verify_non_membership(&self, element: &[u8], witness: &NonMembershipWitness) -> Result<(), AccumulatorError> {
+        let prime = hash_to_prime::<H>(element);
+        if witness.a.is_zero() || witness.b.modulus() != self.value.modulus() {
+            return Err(AccumulatorError::InvalidWitness);
+        }
+        let lhs = self.value.exp(&witness.a).op(&witness.b.exp(&prime));
+        if lhs == self.generator {
+            Ok(())
+        } else {
+            Err(AccumulatorError::InvalidWitness)
+        }
+    }
+
+    fn product(&self) -> NativeBigInt {
+        self.elements.iter().fold(NativeBigInt::one(), |acc, x| acc * x)
+    }
+}
+
+impl MembershipWitness {
+    /// Updates the witness of `element` after a batch of additions and deletions, given the new
+    /// accumulator value and without access to the other elements.
+    ///
+    /// Additions raise the witness to their product P_add. For the deletions with product P_del,
+    /// a*x + b*P_del = 1 gives the new witness w^b * A'^a, as (w^b * A'^a)^x = A'^(b*P_del + a*x).
+    ///
+    /// Fails with [AccumulatorError::InvalidWitness] if `new_value` is over another modulus.
+    pub fn update<H: Digest + Clone>(
+        &self,
+        element: &[u8],
+        added: &[&[u8]],
+        deleted: &[&[u8]],
+        new_value: &HiddenOrderGroup,
+    ) -> Result<MembershipWitness, AccumulatorError> {
+        if new_value.modulus() != self.witness.modulus() {
+            return Err(AccumulatorError::InvalidWitness);
+        }
+        let prime = hash_to_prime::<H>(element);
+        let added_product = added.iter()
+            .fold(NativeBigInt::one(), |acc, x| acc * hash_to_prime::<H>(x));
+        let deleted_product = deleted.iter()
+            .fold(NativeBigInt::one(), |acc, x| acc * hash_to_prime::<H>(x));
+
+        let witness = self.witness.exp(&added_product);
+        let (gcd, a, b) = NativeBigInt::egcd(&prime, &deleted_product);
+        if !gcd.is_one() {
+            return Err(AccumulatorError::NotAccumulated);
+        }
+        Ok(MembershipWitness {
+            witness: witness.exp(&b).op(&new_value.exp(&a)),
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptology_primitives::hidden_order::accumulator::RsaAccumulator;
+    use crate::cryptology_primitives::hidden_order::errors::AccumulatorError;
+    use crate::cryptology_primitives::hidden_order::rsa_group::RsaKeyPair;
+
+    type Accumulator = RsaAccumulator<sha3::Keccak256>;
+
+    #[test]
+    fn test_membership() {
+        let key_pair = RsaKeyPair::generate(512);
+        let mut accumulator = Accumulator::new(&key_pair.modulus);
+
+        let elements: [&[u8]; 4] = [b"alice", b"bob", b"carol", b"dave"];
+        for element in elements {
+            accumulator.add(element).unwrap();
+        }
+        assert_eq!(accumulator.add(b"bob"), Err(AccumulatorError::AlreadyAccumulated));
+
+        for element in elements {
+            let witness = accumulator.membership_witness(element).unwrap();
+            assert!(accumulator.verify_membership(element, &witness).is_ok());
+        }
+
+        let bob_witness = accumulator.membership_witness(b"bob").unwrap();
+        assert_eq!(
+            accumulator.verify_membership(b"eve", &bob_witness),
+            Err(AccumulatorError::InvalidWitness)
+        );
+        assert_eq!(accumulator.membership_witness(b"eve"), Err(AccumulatorError::NotAccumulated));
+    }
+
+    #[test]
+    fn test_delete() {
+        let key_pair = RsaKeyPair::generate(512);
+        let mut accumulator = Accumulator::new(&key_pair.modulus);
+        accumulator.add(b"alice").unwrap();
+        accumulator.add(b"bob").unwrap();
+
+        let bob_witness = accumulator.membership_witness(b"bob").unwrap();
+        accumulator.delete(b"bob").unwrap();
+        assert!(!accumulator.contains(b"bob"));
+        assert!(accumulator.verify_membership(b"bob", &bob_witness).is_err());
+        assert_eq!(accumulator.delete(b"bob"), Err(AccumulatorError::NotAccumulated));
+
+        // deleting every element returns to the generator
+        accumulator.delete(b"alice").unwrap();
+        assert_eq!(accumulator.value, accumulator.generator);
+    }
+
+    #[test]
+    fn test_non_membership() {
+        let key_pair = RsaKeyPair::generate(512);
+        let mut accumulator = Accumulator::new(&key_pair.modulus);
+        accumulator.add(b"alice").unwrap();
+        accumulator.add(b"bob").unwrap();
+
+        let witness = accumulator.non_membership_witness(b"eve").unwrap();
+        assert!(accumulator.verify_non_membership(b"eve", &witness).is_ok());
+        assert_eq!(
+            accumulator.verify_non_membership(b"alice", &witness),
+            Err(AccumulatorError::InvalidWitness)
+        );
+        assert_eq!(
+            accumulator.non_membership_witness(b"alice"),
+            Err(AccumulatorError::AlreadyAccumulated)
+        );
+
+        // a witness from an accumulator over another modulus is rejected, not a panic
+        let other_key_pair = RsaKeyPair::generate(512);
+        let mut other = Accumulator::new(&other_key_pair.modulus);
+        other.add(b"alice").unwrap();
+        let foreign_witness = other.non_membership_witness(b"eve").unwrap();
+        assert_eq!(
+            accumulator.verify_non_membership(b"eve", &foreign_witness),
+            Err(AccumulatorError::InvalidWitness)
+        );
+
+        // the witness is stale once the element is added
+        accumulator.add(b"eve").unwrap();
+        assert!(accumulator.verify_non_membership(b"eve", &witness).is_err());
+    }
+
+    #[test]
+    fn test_batch_witness_update() {
+        let key_pair = RsaKeyPair::generate(512);
+        let mut accumulator = Accumulator::new(&key_pair.modulus);
+        accumulator.add(b"alice").unwrap();
+        accumulator.add(b"bob").unwrap();
+        accumulator.add(b"carol").unwrap();
+        let alice_witness = accumulator.membership_witness(b"alice").unwrap();
+
+        let added: [&[u8]; 2] = [b"dave", b"erin"];
+        let deleted: [&[u8]; 2] = [b"bob", b"dave"];
+        for element in added {
+            accumulator.add(element).unwrap();
+        }
+        for element in deleted {
+            accumulator.delete(element).unwrap();
+        }
+        assert!(accumulator.verify_membership(b"alice", &alice_witness).is_err());
+
+        let updated = alice_witness
+            .update::<sha3::Keccak256>(b"alice", &added, &deleted, &accumulator.value)
+            .unwrap();
+        assert!(accumulator.verify_membership(b"alice", &updated).is_ok());
+        assert_eq!(updated, accumulator.membership_witness(b"alice").unwrap());
+
+        // a witness can't be updated past the deletion of its own element
+        assert_eq!(
+            alice_witness.update::<sha3::Keccak256>(b"alice", &added, &[b"alice"], &accumulator.value),
+            Err(AccumulatorError::NotAccumulated)
+        );
+
+        // the value of an accumulator over another modulus is rejected, not a panic
+        let other_key_pair = RsaKeyPair::generate(512);
+        let mut other = Accumulator::new(&other_key_pair.modulus);
+        other.add(b"alice").unwrap();
+        assert_eq!(
+            alice_witness.update::<sha3::Keccak256>(b"alice", &added, &deleted, &other.value),
+            Err(AccumulatorError::InvalidWitness)
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use std::error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AccumulatorError {
+    /// the element is already in the accumulated set
+    AlreadyAccumulated,
+    /// the element is not in the accumulated set
+    NotAccumulated,
+    /// the witness does not verify against the accumulator value
+    InvalidWitness,
+}
+
+impl Display for AccumulatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccumulatorError::AlreadyAccumulated => write!(f, "accumulator: element is already accumulated"),
+            AccumulatorError::NotAccumulated => write!(f, "accumulator: element is not accumulated"),
+            AccumulatorError::InvalidWitness => write!(f, "accumulator: witness does not verify"),
+        }
+    }
+}
+
+impl error::Error for AccumulatorError {}
@@ -0,0 +1,3 @@
+pub mod accumulator;
+pub mod errors;
+pub mod rsa_group;
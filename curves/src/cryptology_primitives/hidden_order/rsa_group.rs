@@ -0,0 +1,202 @@
+//! An RSA group Z_N^* is a group of unknown order: anyone can multiply and exponentiate modulo N,
+//! but the order phi(N) is only known to whoever generated the factors of N.
+//!
+//! Elements are taken modulo ±1, i.e. the group Z_N^*/{±1}, so that -1, the only non-trivial
+//! element of known order, is identified with the identity.
+//! https://eprint.iacr.org/2018/1188.pdf section 2 <<Batching Techniques for Accumulators>>
+
+use curv::cryptographic_primitives::hashing::Digest;
+use num_traits::{One, Signed, Zero};
+use zeroize::Zeroize;
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::primes::generate_prime;
+use crate::arithmetic::traits::{BitManipulation, Converter, EGCD, InnerAccess, Modulo};
+
+/// The generator of an RSA modulus knows its factorization, which is the trapdoor of the group.
+pub struct RsaKeyPair {
+    pub modulus: NativeBigInt,
+    p: NativeBigInt,
+    q: NativeBigInt,
+}
+
+impl RsaKeyPair {
+    /// Generates N = p*q of `bit_size` bits with two distinct primes of `bit_size / 2` bits
+    pub fn generate(bit_size: usize) -> RsaKeyPair {
+        let prime_bits = bit_size / 2;
+        loop {
+            let p = generate_prime(prime_bits);
+            let q = generate_prime(bit_size - prime_bits);
+            if p == q {
+                continue;
+            }
+            let modulus = &p * &q;
+            if modulus.bit_length() == bit_size {
+                return RsaKeyPair { modulus, p, q };
+            }
+        }
+    }
+
+    /// The order of Z_N^*, (p-1)(q-1)
+    pub fn phi(&self) -> NativeBigInt {
+        (&self.p - NativeBigInt::one()) * (&self.q - NativeBigInt::one())
+    }
+}
+
+impl Drop for RsaKeyPair {
+    fn drop(&mut self) {
+        self.p.zeroize();
+        self.q.zeroize();
+    }
+}
+
+/// An element of the hidden-order group Z_N^*/{±1}, kept in the canonical representative
+/// min(x, N - x)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HiddenOrderGroup {
+    value: NativeBigInt,
+    modulus: NativeBigInt,
+}
+
+impl HiddenOrderGroup {
+    /// Returns None if `value` is not a unit modulo `modulus`
+    pub fn new(value: &NativeBigInt, modulus: &NativeBigInt) -> Option<HiddenOrderGroup> {
+        let value = value.modulus(modulus);
+        let (gcd, _, _) = NativeBigInt::egcd(&value, modulus);
+        if value.is_zero() || !gcd.is_one() {
+            return None;
+        }
+        Some(Self::canonical(value, modulus.clone()))
+    }
+
+    pub fn identity(modulus: &NativeBigInt) -> HiddenOrderGroup {
+        HiddenOrderGroup {
+            value: NativeBigInt::one(),
+            modulus: modulus.clone(),
+        }
+    }
+
+    /// Maps bytes to a group element whose discrete log is unknown to everyone, including the
+    /// holder of the factorization.
+    ///
+    /// The digest is expanded with a block counter to 128 bits more than the modulus, so that
+    /// the reduction modulo N is close to uniform.
+    pub fn hash_to_group<H: Digest + Clone>(modulus: &NativeBigInt, bytes: &[u8]) -> HiddenOrderGroup {
+        let target_bits = modulus.bit_length() + 128;
+        let mut attempt = 0u32;
+        loop {
+            let mut expanded = Vec::new();
+            let mut block = 0u32;
+            while expanded.len() * 8 < target_bits {
+                let digest = H::new()
+                    .chain(attempt.to_be_bytes())
+                    .chain(block.to_be_bytes())
+                    .chain(bytes)
+                    .finalize();
+                expanded.extend_from_slice(&digest);
+                block += 1;
+            }
+            if let Some(element) = Self::new(&NativeBigInt::from_bytes(&expanded), modulus) {
+                return element;
+            }
+            attempt += 1;
+        }
+    }
+
+    /// The group operation, multiplication modulo N
+    pub fn op(&self, other: &HiddenOrderGroup) -> HiddenOrderGroup {
+        assert_eq!(self.modulus, other.modulus, "elements of different groups");
+        let value = NativeBigInt::mod_mul(&self.value, &other.value, &self.modulus);
+        Self::canonical(value, self.modulus.clone())
+    }
+
+    /// Raises the element to `exponent`, a negative exponent exponentiates the inverse
+    pub fn exp(&self, exponent: &NativeBigInt) -> HiddenOrderGroup {
+        let (base, exponent) = if exponent.inner_ref().is_negative() {
+            (self.inverse(), NativeBigInt::zero() - exponent)
+        } else {
+            (self.clone(), exponent.clone())
+        };
+        let value = NativeBigInt::mod_pow(&base.value, &exponent, &self.modulus);
+        Self::canonical(value, self.modulus.clone())
+    }
+
+    pub fn inverse(&self) -> HiddenOrderGroup {
+        let value = NativeBigInt::mod_inv(&self.value, &self.modulus)
+            .expect("group elements are units");
+        Self::canonical(value, self.modulus.clone())
+    }
+
+    pub fn value(&self) -> &NativeBigInt {
+        &self.value
+    }
+
+    pub fn modulus(&self) -> &NativeBigInt {
+        &self.modulus
+    }
+
+    /// Big-endian bytes of the canonical representative, padded to the byte length of N
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let len = self.modulus.bit_length().div_ceil(8);
+        let bytes = self.value.to_bytes();
+        let mut padded = vec![0u8; len - bytes.len()];
+        padded.extend_from_slice(&bytes);
+        padded
+    }
+
+    fn canonical(value: NativeBigInt, modulus: NativeBigInt) -> HiddenOrderGroup {
+        let negated = &modulus - &value;
+        let value = if negated < value { negated } else { value };
+        HiddenOrderGroup { value, modulus }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_traits::One;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::arithmetic::traits::{BitManipulation, Modulo};
+    use crate::cryptology_primitives::hidden_order::rsa_group::{HiddenOrderGroup, RsaKeyPair};
+
+    #[test]
+    fn test_rsa_key_generation() {
+        let key_pair = RsaKeyPair::generate(512);
+        assert_eq!(key_pair.modulus.bit_length(), 512);
+
+        // e*d = 1 mod phi(N) makes x -> x^e invertible
+        let e = NativeBigInt::from(65537u32);
+        let d = NativeBigInt::mod_inv(&e, &key_pair.phi()).unwrap();
+        let x = NativeBigInt::from(123456789u64);
+        let y = NativeBigInt::mod_pow(&x, &e, &key_pair.modulus);
+        assert_eq!(NativeBigInt::mod_pow(&y, &d, &key_pair.modulus), x);
+    }
+
+    #[test]
+    fn test_group_operations() {
+        let key_pair = RsaKeyPair::generate(512);
+        let modulus = &key_pair.modulus;
+        let g = HiddenOrderGroup::hash_to_group::<sha3::Keccak256>(modulus, b"g");
+        let h = HiddenOrderGroup::hash_to_group::<sha3::Keccak256>(modulus, b"h");
+        assert_ne!(g, h);
+
+        let identity = HiddenOrderGroup::identity(modulus);
+        assert_eq!(g.op(&g.inverse()), identity);
+        assert_eq!(g.op(&identity), g);
+        assert_eq!(g.op(&h), h.op(&g));
+
+        let a = NativeBigInt::from(1234u32);
+        let b = NativeBigInt::from(5678u32);
+        assert_eq!(g.exp(&a).op(&g.exp(&b)), g.exp(&(&a + &b)));
+        assert_eq!(g.exp(&(NativeBigInt::from(0u32) - &a)), g.exp(&a).inverse());
+
+        // the order of every element divides phi(N)
+        assert_eq!(g.exp(&key_pair.phi()), identity);
+
+        // -1 is identified with the identity
+        let minus_one = modulus - NativeBigInt::one();
+        assert_eq!(HiddenOrderGroup::new(&minus_one, modulus).unwrap(), identity);
+        assert!(HiddenOrderGroup::new(&NativeBigInt::from(0u32), modulus).is_none());
+    }
+}
@@ -1,2 +1,8 @@
+pub mod commitments;
+pub mod encryption;
+pub mod errors;
+pub mod hidden_order;
 pub mod proofs;
-pub mod twoparty;
\ No newline at end of file
+pub mod rng;
+pub mod twoparty;
+pub mod vdf;
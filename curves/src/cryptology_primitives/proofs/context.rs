@@ -0,0 +1,45 @@
+//! Context bound into Fiat-Shamir challenges, so that a proof made for one protocol, session or
+//! party doesn't verify anywhere else.
+
+/// Domain-separation label, session identifier and index of the proving party
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofContext<'a> {
+    pub label: &'a [u8],
+    pub session_id: &'a [u8],
+    pub party_index: u16,
+}
+
+impl<'a> ProofContext<'a> {
+    pub fn new(label: &'a [u8], session_id: &'a [u8], party_index: u16) -> ProofContext<'a> {
+        ProofContext {
+            label,
+            session_id,
+            party_index,
+        }
+    }
+
+    /// label and session id are length prefixed, so that no two contexts share an encoding
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(18 + self.label.len() + self.session_id.len());
+        bytes.extend_from_slice(&(self.label.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(self.label);
+        bytes.extend_from_slice(&(self.session_id.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(self.session_id);
+        bytes.extend_from_slice(&self.party_index.to_be_bytes());
+        bytes
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::cryptology_primitives::proofs::context::ProofContext;
+
+    #[test]
+    fn test_context_encoding_is_unambiguous() {
+        let a = ProofContext::new(b"ab", b"c", 1);
+        let b = ProofContext::new(b"a", b"bc", 1);
+        assert_ne!(a.to_bytes(), b.to_bytes());
+        assert_ne!(a.to_bytes(), ProofContext::new(b"ab", b"c", 2).to_bytes());
+    }
+}
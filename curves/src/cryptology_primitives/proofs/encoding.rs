@@ -0,0 +1,123 @@
+//! Compact binary encodings of proofs start with a 3 byte header: format version, curve id and
+//! hash id. A verifier decoding a proof checks the header against the curve and hash function it
+//! is instantiated with, so a proof made for another curve or digest is rejected up front instead
+//! of failing, or worse passing, an unrelated check.
+//!
+//! Ids are fixed once assigned, new curves and hash functions get new ids.
+
+use curv::elliptic::curves::{Curve, Ed25519, Ristretto, Scalar, Secp256k1, Secp256r1};
+
+use crate::cryptology_primitives::errors::ProofError;
+
+pub const ENCODING_VERSION: u8 = 1;
+pub const HEADER_LEN: usize = 3;
+
+pub trait CurveId: Curve {
+    const CURVE_ID: u8;
+}
+
+pub trait HashId {
+    const HASH_ID: u8;
+}
+
+impl CurveId for Secp256k1 {
+    const CURVE_ID: u8 = 1;
+}
+
+impl CurveId for Secp256r1 {
+    const CURVE_ID: u8 = 2;
+}
+
+impl CurveId for Ed25519 {
+    const CURVE_ID: u8 = 3;
+}
+
+impl CurveId for Ristretto {
+    const CURVE_ID: u8 = 4;
+}
+
+impl HashId for sha2::Sha256 {
+    const HASH_ID: u8 = 1;
+}
+
+impl HashId for sha2::Sha512 {
+    const HASH_ID: u8 = 2;
+}
+
+impl HashId for sha3::Sha3_256 {
+    const HASH_ID: u8 = 3;
+}
+
+impl HashId for sha3::Keccak256 {
+    const HASH_ID: u8 = 4;
+}
+
+pub fn encode_header<E: CurveId, H: HashId>() -> [u8; HEADER_LEN] {
+    [ENCODING_VERSION, E::CURVE_ID, H::HASH_ID]
+}
+
+/// Checks the header and returns the bytes after it
+pub fn decode_header<E: CurveId, H: HashId>(bytes: &[u8]) -> Result<&[u8], ProofError> {
+    if bytes.len() < HEADER_LEN {
+        return Err(ProofError::MalformedProof);
+    }
+    if bytes[0] != ENCODING_VERSION {
+        return Err(ProofError::UnsupportedVersion);
+    }
+    if bytes[1] != E::CURVE_ID {
+        return Err(ProofError::CurveMismatch);
+    }
+    if bytes[2] != H::HASH_ID {
+        return Err(ProofError::DigestMismatch);
+    }
+    Ok(&bytes[HEADER_LEN..])
+}
+
+/// Length of curv's canonical scalar encoding for the curve, which is fixed length but not the same
+/// byte order everywhere: big endian on secp256k1, little endian on Ed25519
+pub fn scalar_len<E: Curve>() -> usize {
+    Scalar::<E>::zero().to_bytes().len()
+}
+
+/// Only accepts the canonical encoding, so an encoded proof can't be altered into another valid one
+pub fn decode_scalar<E: Curve>(bytes: &[u8]) -> Result<Scalar<E>, ProofError> {
+    let scalar = Scalar::<E>::from_bytes(bytes).map_err(|_| ProofError::MalformedProof)?;
+    if *scalar.to_bytes() != *bytes {
+        return Err(ProofError::MalformedProof);
+    }
+    Ok(scalar)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Ed25519, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::encoding::{decode_header, decode_scalar, encode_header, scalar_len};
+
+    #[test]
+    fn test_header() {
+        let mut bytes = encode_header::<Secp256k1, sha3::Keccak256>().to_vec();
+        bytes.push(0xff);
+        assert_eq!(decode_header::<Secp256k1, sha3::Keccak256>(&bytes), Ok([0xff].as_slice()));
+        assert_eq!(decode_header::<Ed25519, sha3::Keccak256>(&bytes), Err(ProofError::CurveMismatch));
+        assert_eq!(decode_header::<Secp256k1, sha2::Sha256>(&bytes), Err(ProofError::DigestMismatch));
+        assert_eq!(decode_header::<Secp256k1, sha3::Keccak256>(&bytes[..2]), Err(ProofError::MalformedProof));
+
+        bytes[0] = 2;
+        assert_eq!(decode_header::<Secp256k1, sha3::Keccak256>(&bytes), Err(ProofError::UnsupportedVersion));
+    }
+
+    #[test]
+    fn test_scalar_encoding() {
+        assert_eq!(scalar_len::<Secp256k1>(), 32);
+        let scalar = Scalar::<Secp256k1>::random();
+        assert_eq!(decode_scalar::<Secp256k1>(&scalar.to_bytes()), Ok(scalar));
+
+        // the group order itself reduces to zero, but isn't the canonical encoding of zero
+        let order = curv::arithmetic::Converter::to_bytes(Scalar::<Secp256k1>::group_order());
+        assert_eq!(decode_scalar::<Secp256k1>(&order), Err(ProofError::MalformedProof));
+        assert_eq!(decode_scalar::<Secp256k1>(&[1u8; 31]), Err(ProofError::MalformedProof));
+    }
+}
@@ -0,0 +1,177 @@
+//! Inner product argument: proves knowledge of vectors a, b of length n with
+//! P = <a, g> + <b, h> + <a, b> * u, in log2(n) rounds that each halve the vectors, so the proof is
+//! 2 * log2(n) points and two scalars.
+//!
+//! The verifier doesn't fold the generators round by round, it expands the round challenges into
+//! one scalar per generator and checks everything with a single multi-scalar multiplication.
+//! https://eprint.iacr.org/2017/1066.pdf <<Bulletproofs: Short Proofs for Confidential Transactions and More>> Protocol 2
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::elliptic::msm::multi_scalar_mul;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InnerProductProof<E: Curve> {
+    pub l_vec: Vec<Point<E>>,
+    pub r_vec: Vec<Point<E>>,
+    pub a: Scalar<E>,
+    pub b: Scalar<E>,
+}
+
+pub fn inner_product<E: Curve>(a: &[Scalar<E>], b: &[Scalar<E>]) -> Scalar<E> {
+    a.iter().zip(b).fold(Scalar::zero(), |sum, (a_i, b_i)| sum + a_i * b_i)
+}
+
+impl<E: Curve> InnerProductProof<E> {
+    /// `g`, `h`, `a` and `b` must have the same length, a power of two.
+    /// The transcript must already hold P, or whatever P is computed from.
+    pub fn prove<H: Digest + Clone>(
+        transcript: &mut Transcript<H>,
+        g: &[Point<E>],
+        h: &[Point<E>],
+        u: &Point<E>,
+        a: &[Scalar<E>],
+        b: &[Scalar<E>],
+    ) -> InnerProductProof<E> {
+        let mut n = g.len();
+        assert!(n.is_power_of_two(), "inner product argument: length {} is not a power of two", n);
+        assert!(h.len() == n && a.len() == n && b.len() == n, "inner product argument: lengths differ");
+
+        let (mut g, mut h, mut a, mut b) = (g.to_vec(), h.to_vec(), a.to_vec(), b.to_vec());
+        let mut l_vec = Vec::with_capacity(n.trailing_zeros() as usize);
+        let mut r_vec = Vec::with_capacity(n.trailing_zeros() as usize);
+        transcript.append_u64(b"n", n as u64);
+        while n > 1 {
+            n /= 2;
+            let (a_lo, a_hi) = a.split_at(n);
+            let (b_lo, b_hi) = b.split_at(n);
+            let (g_lo, g_hi) = g.split_at(n);
+            let (h_lo, h_hi) = h.split_at(n);
+
+            let c_l = inner_product(a_lo, b_hi);
+            let c_r = inner_product(a_hi, b_lo);
+            let l = multi_scalar_mul(&[a_lo, b_hi, &[c_l]].concat(), &[g_hi, h_lo, std::slice::from_ref(u)].concat());
+            let r = multi_scalar_mul(&[a_hi, b_lo, &[c_r]].concat(), &[g_lo, h_hi, std::slice::from_ref(u)].concat());
+
+            transcript.append_point(b"L", &l);
+            transcript.append_point(b"R", &r);
+            let x = transcript.challenge_scalar::<E>(b"x");
+            let x_inv = x.invert().expect("challenge is zero with negligible probability");
+
+            a = (0..n).map(|i| &a_lo[i] * &x + &a_hi[i] * &x_inv).collect();
+            b = (0..n).map(|i| &b_lo[i] * &x_inv + &b_hi[i] * &x).collect();
+            g = (0..n).map(|i| &g_lo[i] * &x_inv + &g_hi[i] * &x).collect();
+            h = (0..n).map(|i| &h_lo[i] * &x + &h_hi[i] * &x_inv).collect();
+
+            l_vec.push(l);
+            r_vec.push(r);
+        }
+
+        InnerProductProof {
+            l_vec,
+            r_vec,
+            a: a[0].clone(),
+            b: b[0].clone(),
+        }
+    }
+
+    /// Checks P + sum(x_k^2 * L_k + x_k^-2 * R_k) = a * <s, g> + b * <s^-1, h> + a * b * u, where
+    /// s_i is the product of x_k for every round k in which g_i landed in the upper half, and of
+    /// x_k^-1 for the others
+    pub fn verify<H: Digest + Clone>(
+        &self,
+        transcript: &mut Transcript<H>,
+        g: &[Point<E>],
+        h: &[Point<E>],
+        u: &Point<E>,
+        p: &Point<E>,
+    ) -> Result<(), ProofError> {
+        let n = g.len();
+        let rounds = n.trailing_zeros() as usize;
+        if !n.is_power_of_two() || h.len() != n || self.l_vec.len() != rounds || self.r_vec.len() != rounds {
+            return Err(ProofError::MalformedProof);
+        }
+
+        transcript.append_u64(b"n", n as u64);
+        let mut challenges = Vec::with_capacity(rounds);
+        for (l, r) in self.l_vec.iter().zip(&self.r_vec) {
+            transcript.append_point(b"L", l);
+            transcript.append_point(b"R", r);
+            let x = transcript.challenge_scalar::<E>(b"x");
+            let x_inv = x.invert().ok_or(ProofError::InvalidResponse)?;
+            challenges.push((x, x_inv));
+        }
+
+        let mut scalars = Vec::with_capacity(2 * n + 2 * rounds + 2);
+        let mut points = Vec::with_capacity(2 * n + 2 * rounds + 2);
+        for i in 0..n {
+            let mut s = Scalar::<E>::from(1);
+            let mut s_inv = Scalar::<E>::from(1);
+            for (k, (x, x_inv)) in challenges.iter().enumerate() {
+                // round k splits on bit (rounds - 1 - k) of the index
+                if (i >> (rounds - 1 - k)) & 1 == 1 {
+                    s = s * x;
+                    s_inv = s_inv * x_inv;
+                } else {
+                    s = s * x_inv;
+                    s_inv = s_inv * x;
+                }
+            }
+            scalars.push(&self.a * s);
+            points.push(g[i].clone());
+            scalars.push(&self.b * s_inv);
+            points.push(h[i].clone());
+        }
+        scalars.push(&self.a * &self.b);
+        points.push(u.clone());
+        for ((x, x_inv), (l, r)) in challenges.iter().zip(self.l_vec.iter().zip(&self.r_vec)) {
+            scalars.push(-(x * x));
+            points.push(l.clone());
+            scalars.push(-(x_inv * x_inv));
+            points.push(r.clone());
+        }
+        scalars.push(-Scalar::<E>::from(1));
+        points.push(p.clone());
+
+        if multi_scalar_mul(&scalars, &points).is_zero() {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidResponse)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::commitments::pedersen::hash_to_point;
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::inner_product::{inner_product, InnerProductProof};
+    use crate::cryptology_primitives::proofs::transcript::Transcript;
+    use crate::elliptic::msm::multi_scalar_mul;
+
+    #[test]
+    fn test_inner_product_proof() {
+        for n in [1usize, 2, 16] {
+            let g: Vec<Point<Secp256k1>> = (0..n).map(|i| hash_to_point(&[b"test_g".as_slice(), &i.to_be_bytes()].concat())).collect();
+            let h: Vec<Point<Secp256k1>> = (0..n).map(|i| hash_to_point(&[b"test_h".as_slice(), &i.to_be_bytes()].concat())).collect();
+            let u = hash_to_point(b"test_u");
+            let a: Vec<Scalar<Secp256k1>> = (0..n).map(|_| Scalar::random()).collect();
+            let b: Vec<Scalar<Secp256k1>> = (0..n).map(|_| Scalar::random()).collect();
+            let p = multi_scalar_mul(&[a.as_slice(), &b, &[inner_product(&a, &b)]].concat(), &[g.as_slice(), &h, std::slice::from_ref(&u)].concat());
+
+            let proof = InnerProductProof::prove(&mut Transcript::<sha3::Keccak256>::new(b"test"), &g, &h, &u, &a, &b);
+            assert_eq!(proof.l_vec.len(), n.trailing_zeros() as usize);
+            assert!(proof.verify(&mut Transcript::<sha3::Keccak256>::new(b"test"), &g, &h, &u, &p).is_ok());
+
+            let wrong_p = &p + &u;
+            assert_eq!(proof.verify(&mut Transcript::<sha3::Keccak256>::new(b"test"), &g, &h, &u, &wrong_p), Err(ProofError::InvalidResponse));
+        }
+    }
+}
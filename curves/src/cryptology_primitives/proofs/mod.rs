@@ -1 +1,17 @@
-pub mod sigma_dlog;
\ No newline at end of file
+pub mod context;
+pub mod encoding;
+pub mod inner_product;
+pub mod paillier_correct_key;
+pub mod paillier_range;
+pub mod pdl;
+pub mod range_proof;
+pub mod sigma_dleq;
+pub mod sigma_dlog;
+pub mod sigma_dlog_evm;
+pub mod sigma_dlog_interactive;
+pub mod sigma_dlog_weierstrass;
+pub mod sigma_protocol;
+pub mod sigma_representation;
+pub mod sigma_set_membership;
+pub mod transcript;
+pub mod validation;
\ No newline at end of file
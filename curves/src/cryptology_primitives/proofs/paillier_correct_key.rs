@@ -0,0 +1,144 @@
+//! Non-interactive proof that a Paillier modulus N is coprime to phi(N), so that x -> x^N is a
+//! permutation of Z_N^* and every ciphertext decrypts to a single plaintext.
+//!
+//! The prover answers M challenges rho_i in Z_N^* derived from the transcript with N-th roots
+//! sigma_i = rho_i^(N^-1 mod phi(N)). The verifier additionally checks that N has no prime factor
+//! below ALPHA, which with M = 11 bounds the soundness error by 2^-128.
+//! https://eprint.iacr.org/2018/057.pdf <<Efficient Noninteractive Certification of RSA Moduli and Beyond>> section 3.2
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use num_traits::{One, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::traits::{BitManipulation, Converter, Modulo};
+use crate::cryptology_primitives::encryption::paillier::{DecryptionKey, EncryptionKey};
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+
+const TRANSCRIPT_LABEL: &[u8] = b"paillier_correct_key";
+
+/// Number of challenges
+pub const M: usize = 11;
+/// Bound on the prime factors of N the verifier rules out by trial division
+pub const ALPHA: u32 = 6370;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrectKeyProof<H: Digest + Clone> {
+    pub sigma_vec: Vec<NativeBigInt>,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+impl<H: Digest + Clone> CorrectKeyProof<H> {
+    /// Proof for the modulus of `dk`, bound to `ctx` like [DLogProof::prove_with_context](crate::cryptology_primitives::proofs::sigma_dlog::DLogProof::prove_with_context)
+    pub fn prove(dk: &DecryptionKey, ctx: &[u8]) -> CorrectKeyProof<H> {
+        let ek = dk.encryption_key();
+        let sigma_vec = challenges::<H>(&ek, ctx)
+            .iter()
+            .map(|rho| dk.nth_root(rho))
+            .collect();
+        CorrectKeyProof {
+            sigma_vec,
+            hash_choice: PhantomData,
+        }
+    }
+
+    pub fn verify(&self, ek: &EncryptionKey, ctx: &[u8]) -> Result<(), ProofError> {
+        if self.sigma_vec.len() != M {
+            return Err(ProofError::MalformedProof);
+        }
+        if ek.n <= NativeBigInt::one() || ek.nn != &ek.n * &ek.n {
+            return Err(ProofError::MalformedProof);
+        }
+        if small_primes().iter().any(|p| ek.n.modulus(&NativeBigInt::from(*p)).is_zero()) {
+            return Err(ProofError::InvalidWitness);
+        }
+
+        let rho_vec = challenges::<H>(ek, ctx);
+        for (sigma, rho) in self.sigma_vec.iter().zip(rho_vec.iter()) {
+            if sigma <= &NativeBigInt::zero() || sigma >= &ek.n {
+                return Err(ProofError::MalformedProof);
+            }
+            if &NativeBigInt::mod_pow(sigma, &ek.n, &ek.n) != rho {
+                return Err(ProofError::InvalidResponse);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// rho_1, ..., rho_M, uniform in Z_N up to a 2^-128 bias. A non-unit would factor N, it only
+/// turns up with negligible probability.
+fn challenges<H: Digest + Clone>(ek: &EncryptionKey, ctx: &[u8]) -> Vec<NativeBigInt> {
+    let mut transcript = Transcript::<H>::new(TRANSCRIPT_LABEL);
+    transcript.append_bytes(b"ctx", ctx);
+    transcript.append_bytes(b"n", &ek.n.to_bytes());
+    let len = ek.n.bit_length().div_ceil(8) + 16;
+    (0..M)
+        .map(|_| NativeBigInt::from_bytes(&transcript.challenge_bytes(b"rho", len)).modulus(&ek.n))
+        .collect()
+}
+
+/// The primes below ALPHA
+fn small_primes() -> Vec<u32> {
+    let mut is_composite = vec![false; ALPHA as usize];
+    let mut primes = Vec::new();
+    for i in 2..ALPHA {
+        if is_composite[i as usize] {
+            continue;
+        }
+        primes.push(i);
+        for multiple in (i * i..ALPHA).step_by(i as usize) {
+            is_composite[multiple as usize] = true;
+        }
+    }
+    primes
+}
+
+
+#[cfg(test)]
+mod tests {
+    use sha3::Keccak256;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::arithmetic::primes::generate_prime;
+    use crate::cryptology_primitives::encryption::paillier::{EncryptionKey, Paillier};
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::paillier_correct_key::{small_primes, CorrectKeyProof, ALPHA};
+
+    #[test]
+    fn test_correct_key_proof() {
+        let (ek, dk) = Paillier::keypair(1024);
+        let proof = CorrectKeyProof::<Keccak256>::prove(&dk, b"ctx");
+        assert!(proof.verify(&ek, b"ctx").is_ok());
+        assert_eq!(proof.verify(&ek, b"other ctx").unwrap_err(), ProofError::InvalidResponse);
+
+        let (other_ek, _) = Paillier::keypair(1024);
+        assert!(proof.verify(&other_ek, b"ctx").is_err());
+
+        let mut truncated = proof.clone();
+        truncated.sigma_vec.pop();
+        assert_eq!(truncated.verify(&ek, b"ctx").unwrap_err(), ProofError::MalformedProof);
+
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: CorrectKeyProof<Keccak256> = serde_json::from_str(&json).unwrap();
+        assert!(decoded.verify(&ek, b"ctx").is_ok());
+    }
+
+    #[test]
+    fn test_correct_key_proof_rejects_small_factors() {
+        let primes = small_primes();
+        assert_eq!(primes[..5], [2, 3, 5, 7, 11]);
+        assert!(primes.iter().all(|p| *p < ALPHA));
+
+        // N = 3 * p has gcd(N, phi(N)) = 3 when p = 1 mod 3, the verifier doesn't even look at
+        // the roots
+        let n = NativeBigInt::from(3u32) * generate_prime(512);
+        let ek = EncryptionKey { nn: &n * &n, n };
+        let (_, dk) = Paillier::keypair(512);
+        let proof = CorrectKeyProof::<Keccak256>::prove(&dk, b"ctx");
+        assert_eq!(proof.verify(&ek, b"ctx").unwrap_err(), ProofError::InvalidWitness);
+    }
+}
@@ -0,0 +1,237 @@
+//! Non-interactive cut-and-choose proof that a Paillier ciphertext c = Enc(x; r) encrypts a small
+//! value: the prover knows x in [0, l), the verifier learns that x mod N is in (-l, 2l).
+//!
+//! In each of the ROUNDS rounds the prover encrypts a pair of masks w_low in [0, l) and
+//! w_low + l in [l, 2l) in random order. For challenge bit 0 it opens both, for bit 1 it opens
+//! c * Enc(w_j) = Enc(x + w_j; r * r_j) for the mask that brings x + w_j into [l, 2l). That sum
+//! is uniform in [l, 2l) whatever x is, and a prover with x outside (-l, 2l) can answer at most
+//! one of the two challenges per round.
+//! https://eprint.iacr.org/2017/552.pdf <<Fast Secure Two-Party ECDSA Signing>> Lindell, appendix A
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use rand::{CryptoRng, Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::traits::{Converter, Modulo, Samplable};
+use crate::cryptology_primitives::encryption::paillier::{Ciphertext, EncryptionKey, Paillier, Randomness};
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+
+const TRANSCRIPT_LABEL: &[u8] = b"paillier_range_proof";
+
+/// Number of rounds, each halves a cheating prover's chance
+pub const ROUNDS: usize = 128;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaillierRangeProof<H: Digest + Clone> {
+    pub encrypted_pairs: Vec<(Ciphertext, Ciphertext)>,
+    pub responses: Vec<RangeResponse>,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RangeResponse {
+    /// both masks and their randomness, for challenge bit 0
+    Open {
+        w1: NativeBigInt,
+        r1: NativeBigInt,
+        w2: NativeBigInt,
+        r2: NativeBigInt,
+    },
+    /// x + w_j and r * r_j for j = 1 or 2, for challenge bit 1
+    Mask {
+        j: u8,
+        masked_x: NativeBigInt,
+        masked_r: NativeBigInt,
+    },
+}
+
+impl<H: Digest + Clone> PaillierRangeProof<H> {
+    /// Proof that `c` = Enc(x; r) with x in [0, l), fails with [ProofError::InvalidWitness] otherwise
+    pub fn prove(
+        ek: &EncryptionKey,
+        c: &Ciphertext,
+        l: &NativeBigInt,
+        x: &NativeBigInt,
+        r: &Randomness,
+        ctx: &[u8],
+    ) -> Result<PaillierRangeProof<H>, ProofError> {
+        Self::prove_with_rng(ek, c, l, x, r, ctx, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(
+        ek: &EncryptionKey,
+        c: &Ciphertext,
+        l: &NativeBigInt,
+        x: &NativeBigInt,
+        r: &Randomness,
+        ctx: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<PaillierRangeProof<H>, ProofError> {
+        if x < &NativeBigInt::from(0u32) || x >= l || &Paillier::encrypt_with_randomness(ek, x, r) != c {
+            return Err(ProofError::InvalidWitness);
+        }
+
+        let mut masks = Vec::with_capacity(ROUNDS);
+        let mut encrypted_pairs = Vec::with_capacity(ROUNDS);
+        for _ in 0..ROUNDS {
+            let w_low = NativeBigInt::sample_below_with_rng(l, rng);
+            let w_high = &w_low + l;
+            let (w1, w2) = if rng.gen::<bool>() { (w_high, w_low) } else { (w_low, w_high) };
+            let r1 = Randomness::sample_with_rng(ek, rng);
+            let r2 = Randomness::sample_with_rng(ek, rng);
+            encrypted_pairs.push((
+                Paillier::encrypt_with_randomness(ek, &w1, &r1),
+                Paillier::encrypt_with_randomness(ek, &w2, &r2),
+            ));
+            masks.push((w1, r1, w2, r2));
+        }
+
+        let challenge = challenge_bits::<H>(ek, c, l, &encrypted_pairs, ctx);
+        let responses = masks.into_iter()
+            .zip(challenge)
+            .map(|((w1, r1, w2, r2), bit)| {
+                if !bit {
+                    return RangeResponse::Open { w1, r1: r1.0, w2, r2: r2.0 };
+                }
+                let x_w1 = x + &w1;
+                let (j, w_j, r_j) = if &x_w1 >= l && x_w1 < l + l { (1, w1, r1) } else { (2, w2, r2) };
+                RangeResponse::Mask {
+                    j,
+                    masked_x: x + w_j,
+                    masked_r: NativeBigInt::mod_mul(&r.0, &r_j.0, &ek.n),
+                }
+            })
+            .collect();
+
+        Ok(PaillierRangeProof {
+            encrypted_pairs,
+            responses,
+            hash_choice: PhantomData,
+        })
+    }
+
+    pub fn verify(&self, ek: &EncryptionKey, c: &Ciphertext, l: &NativeBigInt, ctx: &[u8]) -> Result<(), ProofError> {
+        if self.encrypted_pairs.len() != ROUNDS || self.responses.len() != ROUNDS {
+            return Err(ProofError::MalformedProof);
+        }
+        let zero = NativeBigInt::from(0u32);
+        let two_l = l + l;
+        let in_low = |w: &NativeBigInt| w >= &zero && w < l;
+        let in_high = |w: &NativeBigInt| w >= l && w < &two_l;
+
+        let challenge = challenge_bits::<H>(ek, c, l, &self.encrypted_pairs, ctx);
+        for (((c1, c2), response), bit) in self.encrypted_pairs.iter().zip(&self.responses).zip(challenge) {
+            match response {
+                RangeResponse::Open { w1, r1, w2, r2 } if !bit => {
+                    if !(in_low(w1) && in_high(w2) || in_high(w1) && in_low(w2)) {
+                        return Err(ProofError::InvalidResponse);
+                    }
+                    if &Paillier::encrypt_with_randomness(ek, w1, &Randomness(r1.clone())) != c1
+                        || &Paillier::encrypt_with_randomness(ek, w2, &Randomness(r2.clone())) != c2 {
+                        return Err(ProofError::InvalidResponse);
+                    }
+                }
+                RangeResponse::Mask { j, masked_x, masked_r } if bit => {
+                    let c_j = match j {
+                        1 => c1,
+                        2 => c2,
+                        _ => return Err(ProofError::MalformedProof),
+                    };
+                    if !in_high(masked_x)
+                        || Paillier::add(ek, c, c_j) != Paillier::encrypt_with_randomness(ek, masked_x, &Randomness(masked_r.clone())) {
+                        return Err(ProofError::InvalidResponse);
+                    }
+                }
+                _ => return Err(ProofError::InvalidResponse),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One bit per round, bound to the statement and all the encrypted masks
+fn challenge_bits<H: Digest + Clone>(
+    ek: &EncryptionKey,
+    c: &Ciphertext,
+    l: &NativeBigInt,
+    encrypted_pairs: &[(Ciphertext, Ciphertext)],
+    ctx: &[u8],
+) -> Vec<bool> {
+    let mut transcript = Transcript::<H>::new(TRANSCRIPT_LABEL);
+    transcript.append_bytes(b"ctx", ctx);
+    transcript.append_bytes(b"n", &ek.n.to_bytes());
+    transcript.append_bytes(b"c", &c.c.modulus(&ek.nn).to_bytes());
+    transcript.append_bytes(b"l", &l.to_bytes());
+    transcript.append_u64(b"rounds", encrypted_pairs.len() as u64);
+    for (c1, c2) in encrypted_pairs {
+        transcript.append_bytes(b"c1", &c1.c.to_bytes());
+        transcript.append_bytes(b"c2", &c2.c.to_bytes());
+    }
+    let bytes = transcript.challenge_bytes(b"challenge", ROUNDS.div_ceil(8));
+    (0..ROUNDS).map(|i| (bytes[i / 8] >> (i % 8)) & 1 == 1).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use sha3::Keccak256;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::arithmetic::traits::{Converter, Samplable};
+    use crate::cryptology_primitives::encryption::paillier::{Paillier, Randomness};
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::paillier_range::{PaillierRangeProof, RangeResponse};
+
+    type Proof = PaillierRangeProof<Keccak256>;
+
+    #[test]
+    fn test_paillier_range_proof() {
+        let (ek, _) = Paillier::keypair(1024);
+        let l = NativeBigInt::from_hex(&format!("1{}", "0".repeat(63))).unwrap();
+        for x in [NativeBigInt::from(0u32), NativeBigInt::sample_below(&l), &l - NativeBigInt::from(1u32)] {
+            let r = Randomness::sample(&ek);
+            let c = Paillier::encrypt_with_randomness(&ek, &x, &r);
+            let proof = Proof::prove(&ek, &c, &l, &x, &r, b"ctx").unwrap();
+            assert!(proof.verify(&ek, &c, &l, b"ctx").is_ok());
+            assert!(proof.verify(&ek, &c, &l, b"other ctx").is_err());
+
+            // every masked value is in [l, 2l), whatever x is
+            for response in &proof.responses {
+                if let RangeResponse::Mask { masked_x, .. } = response {
+                    assert!(masked_x >= &l && masked_x < &(&l + &l));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_paillier_range_proof_rejects_large_values() {
+        let (ek, _) = Paillier::keypair(1024);
+        let l = NativeBigInt::from_hex(&format!("1{}", "0".repeat(32))).unwrap();
+        let x = &l + NativeBigInt::from(1u32);
+        let r = Randomness::sample(&ek);
+        let c = Paillier::encrypt_with_randomness(&ek, &x, &r);
+        assert_eq!(Proof::prove(&ek, &c, &l, &x, &r, b"ctx").unwrap_err(), ProofError::InvalidWitness);
+
+        // a proof for a small value doesn't carry over to another ciphertext
+        let y = NativeBigInt::from(5u32);
+        let proof = Proof::prove(&ek, &Paillier::encrypt_with_randomness(&ek, &y, &r), &l, &y, &r, b"ctx").unwrap();
+        assert!(proof.verify(&ek, &c, &l, b"ctx").is_err());
+
+        // nor does a mask opened for the wrong challenge bit
+        let c_y = Paillier::encrypt_with_randomness(&ek, &y, &r);
+        let mut swapped = proof.clone();
+        let open = swapped.responses.iter().position(|response| matches!(response, RangeResponse::Open { .. })).unwrap();
+        let mask = swapped.responses.iter().position(|response| matches!(response, RangeResponse::Mask { .. })).unwrap();
+        swapped.responses.swap(open, mask);
+        assert_eq!(swapped.verify(&ek, &c_y, &l, b"ctx").unwrap_err(), ProofError::InvalidResponse);
+
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: Proof = serde_json::from_str(&json).unwrap();
+        assert!(decoded.verify(&ek, &c_y, &l, b"ctx").is_ok());
+    }
+}
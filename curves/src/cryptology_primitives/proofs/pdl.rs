@@ -0,0 +1,292 @@
+//! Interactive proof that a Paillier ciphertext c encrypts the discrete log of Q1 = x1 * G, the
+//! prover being the owner of the decryption key:
+//!
+//! ```text
+//! prover                                         verifier
+//!                       <--- c', com(a, b) ---   a <- Z_q, b <- Z_q^2, c' = c^a * Enc(b), Q' = a * Q1 + b * G
+//! alpha = Dec(c'), Q^ = alpha * G --- com(Q^) --->
+//!                       <--- a, b ---
+//! alpha == a * x1 + b ?   --- Q^ --->            Q^ == Q' ?
+//! ```
+//!
+//! The verifier only learns Q^, which it could compute itself when c is honest. The prover's
+//! check that alpha = a * x1 + b over the integers makes sure a malformed c' can't be used to
+//! learn anything about the decryption key. Together with a range proof on x1 this is the proof
+//! of section 6 of <<Fast Secure Two-Party ECDSA Signing>> Lindell https://eprint.iacr.org/2017/552.pdf
+
+use std::marker::PhantomData;
+
+use curv::arithmetic::Converter as _;
+use curv::BigInt;
+use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
+use curv::cryptographic_primitives::commitments::traits::Commitment;
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::traits::{Converter, Samplable};
+use crate::cryptology_primitives::encryption::paillier::{Ciphertext, DecryptionKey, EncryptionKey, Paillier};
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::rng::{random_scalar, sample_bits};
+
+const SECURITY_BITS: usize = 256;
+
+/// c encrypts the discrete log of q1 under ek
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PDLStatement<E: Curve> {
+    pub ek: EncryptionKey,
+    pub ciphertext: Ciphertext,
+    pub q1: Point<E>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PDLVerifierFirstMessage {
+    pub c_prime: Ciphertext,
+    pub ab_commitment: BigInt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PDLProverFirstMessage {
+    pub q_hat_commitment: BigInt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PDLVerifierSecondMessage<E: Curve> {
+    pub a: Scalar<E>,
+    pub b: NativeBigInt,
+    pub blind_factor: BigInt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PDLProverSecondMessage<E: Curve> {
+    pub q_hat: Point<E>,
+    pub blind_factor: BigInt,
+}
+
+/// What the verifier keeps between its messages
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PDLVerifierState<E: Curve, H: Digest + Clone> {
+    a: Scalar<E>,
+    b: NativeBigInt,
+    blind_factor: BigInt,
+    q_prime: Point<E>,
+    hash_choice: PhantomData<fn(H)>,
+}
+
+/// What the prover keeps between its messages
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PDLProverState<E: Curve, H: Digest + Clone> {
+    alpha: NativeBigInt,
+    q_hat: Point<E>,
+    blind_factor: BigInt,
+    ab_commitment: BigInt,
+    hash_choice: PhantomData<fn(H)>,
+}
+
+impl PDLVerifierFirstMessage {
+    pub fn create<E: Curve, H: Digest + Clone>(statement: &PDLStatement<E>) -> (PDLVerifierFirstMessage, PDLVerifierState<E, H>) {
+        Self::create_with_rng(statement, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        statement: &PDLStatement<E>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PDLVerifierFirstMessage, PDLVerifierState<E, H>) {
+        let a: Scalar<E> = random_scalar(rng);
+        let q = NativeBigInt::group_order::<E>();
+        let b = NativeBigInt::sample_below_with_rng(&(&q * &q), rng);
+
+        let c_a = Paillier::mul(&statement.ek, &statement.ciphertext, &NativeBigInt::from_scalar(&a));
+        let c_prime = Paillier::add(&statement.ek, &c_a, &Paillier::encrypt_with_rng(&statement.ek, &b, rng));
+        let blind_factor = sample_bits(SECURITY_BITS, rng);
+        let ab_commitment = HashCommitment::<H>::create_commitment_with_user_defined_randomness(&ab_message(&a, &b), &blind_factor);
+        let q_prime = &statement.q1 * &a + Point::generator() * b.to_scalar::<E>();
+
+        let state = PDLVerifierState {
+            a,
+            b,
+            blind_factor,
+            q_prime,
+            hash_choice: PhantomData,
+        };
+        (PDLVerifierFirstMessage { c_prime, ab_commitment }, state)
+    }
+}
+
+impl PDLProverFirstMessage {
+    pub fn create<E: Curve, H: Digest + Clone>(
+        dk: &DecryptionKey,
+        verifier_first_message: &PDLVerifierFirstMessage,
+    ) -> (PDLProverFirstMessage, PDLProverState<E, H>) {
+        Self::create_with_rng(dk, verifier_first_message, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        dk: &DecryptionKey,
+        verifier_first_message: &PDLVerifierFirstMessage,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (PDLProverFirstMessage, PDLProverState<E, H>) {
+        let alpha = Paillier::decrypt(dk, &verifier_first_message.c_prime);
+        let q_hat = Point::generator() * alpha.to_scalar::<E>();
+        let blind_factor = sample_bits(SECURITY_BITS, rng);
+        let q_hat_commitment = HashCommitment::<H>::create_commitment_with_user_defined_randomness(
+            &BigInt::from_bytes(&q_hat.to_bytes(true)),
+            &blind_factor,
+        );
+        let state = PDLProverState {
+            alpha,
+            q_hat,
+            blind_factor,
+            ab_commitment: verifier_first_message.ab_commitment.clone(),
+            hash_choice: PhantomData,
+        };
+        (PDLProverFirstMessage { q_hat_commitment }, state)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> PDLVerifierState<E, H> {
+    /// Opens (a, b) once the prover committed to Q^
+    pub fn second_message(&self) -> PDLVerifierSecondMessage<E> {
+        PDLVerifierSecondMessage {
+            a: self.a.clone(),
+            b: self.b.clone(),
+            blind_factor: self.blind_factor.clone(),
+        }
+    }
+
+    /// Accepts if the prover's Q^ opens its commitment and equals a * Q1 + b * G
+    pub fn verify(
+        &self,
+        prover_first_message: &PDLProverFirstMessage,
+        prover_second_message: &PDLProverSecondMessage<E>,
+    ) -> Result<(), ProofError> {
+        if prover_first_message.q_hat_commitment != HashCommitment::<H>::create_commitment_with_user_defined_randomness(
+            &BigInt::from_bytes(&prover_second_message.q_hat.to_bytes(true)),
+            &prover_second_message.blind_factor,
+        ) {
+            return Err(ProofError::HashMismatch);
+        }
+        if prover_second_message.q_hat != self.q_prime {
+            return Err(ProofError::InvalidResponse);
+        }
+        Ok(())
+    }
+}
+
+impl<E: Curve> PDLProverSecondMessage<E> {
+    /// Opens Q^ if the verifier's (a, b) open its commitment and c' was a * x1 + b, `x1` being
+    /// the plaintext of the statement's ciphertext
+    pub fn create<H: Digest + Clone>(
+        state: PDLProverState<E, H>,
+        x1: &Scalar<E>,
+        verifier_second_message: &PDLVerifierSecondMessage<E>,
+    ) -> Result<PDLProverSecondMessage<E>, ProofError> {
+        let PDLVerifierSecondMessage { a, b, blind_factor } = verifier_second_message;
+        if state.ab_commitment != HashCommitment::<H>::create_commitment_with_user_defined_randomness(&ab_message(a, b), blind_factor) {
+            return Err(ProofError::HashMismatch);
+        }
+        let q = NativeBigInt::group_order::<E>();
+        if b < &NativeBigInt::from(0u32) || b >= &(&q * &q) {
+            return Err(ProofError::InvalidResponse);
+        }
+        if state.alpha != NativeBigInt::from_scalar(a) * NativeBigInt::from_scalar(x1) + b {
+            return Err(ProofError::InvalidResponse);
+        }
+        Ok(PDLProverSecondMessage {
+            q_hat: state.q_hat,
+            blind_factor: state.blind_factor,
+        })
+    }
+}
+
+/// a in its fixed size encoding followed by b
+fn ab_message<E: Curve>(a: &Scalar<E>, b: &NativeBigInt) -> BigInt {
+    let mut bytes = a.to_bytes().to_vec();
+    bytes.extend_from_slice(&b.to_bytes());
+    BigInt::from_bytes(&bytes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+    use sha3::Keccak256;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::cryptology_primitives::encryption::paillier::{DecryptionKey, Paillier};
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::pdl::{
+        PDLProverFirstMessage, PDLProverSecondMessage, PDLStatement, PDLVerifierFirstMessage, PDLVerifierState,
+    };
+
+    fn run(statement: &PDLStatement<Secp256k1>, dk: &DecryptionKey, x1: &Scalar<Secp256k1>) -> Result<(), ProofError> {
+        let (verifier_first_message, verifier_state): (_, PDLVerifierState<Secp256k1, Keccak256>) = PDLVerifierFirstMessage::create(statement);
+        let (prover_first_message, prover_state) = PDLProverFirstMessage::create::<Secp256k1, Keccak256>(dk, &verifier_first_message);
+        let verifier_second_message = verifier_state.second_message();
+        let prover_second_message = PDLProverSecondMessage::create(prover_state, x1, &verifier_second_message)?;
+        verifier_state.verify(&prover_first_message, &prover_second_message)
+    }
+
+    #[test]
+    fn test_pdl() {
+        let (ek, dk) = Paillier::keypair(1024);
+        let x1 = Scalar::<Secp256k1>::random();
+        let statement = PDLStatement {
+            ciphertext: Paillier::encrypt(&ek, &NativeBigInt::from_scalar(&x1)),
+            ek,
+            q1: Point::generator() * &x1,
+        };
+        assert!(run(&statement, &dk, &x1).is_ok());
+    }
+
+    #[test]
+    fn test_pdl_wrong_plaintext() {
+        let (ek, dk) = Paillier::keypair(1024);
+        let x1 = Scalar::<Secp256k1>::random();
+        let other = Scalar::<Secp256k1>::random();
+        let statement = PDLStatement {
+            ciphertext: Paillier::encrypt(&ek, &NativeBigInt::from_scalar(&other)),
+            ek: ek.clone(),
+            q1: Point::generator() * &x1,
+        };
+        // an honest prover notices that c doesn't hold x1
+        assert_eq!(run(&statement, &dk, &x1).unwrap_err(), ProofError::InvalidResponse);
+        // and a prover claiming the ciphertext's plaintext gives a Q^ that isn't a * Q1 + b * G
+        assert_eq!(run(&statement, &dk, &other).unwrap_err(), ProofError::InvalidResponse);
+
+        // x1 + q is right modulo q, but the prover checks the plaintext over the integers
+        let q = NativeBigInt::group_order::<Secp256k1>();
+        let statement = PDLStatement {
+            ciphertext: Paillier::encrypt(&ek, &(NativeBigInt::from_scalar(&x1) + q)),
+            ek,
+            q1: Point::generator() * &x1,
+        };
+        assert_eq!(run(&statement, &dk, &x1).unwrap_err(), ProofError::InvalidResponse);
+    }
+
+    #[test]
+    fn test_pdl_rejects_wrong_openings() {
+        let (ek, dk) = Paillier::keypair(1024);
+        let x1 = Scalar::<Secp256k1>::random();
+        let statement = PDLStatement {
+            ciphertext: Paillier::encrypt(&ek, &NativeBigInt::from_scalar(&x1)),
+            ek,
+            q1: Point::generator() * &x1,
+        };
+
+        let (verifier_first_message, verifier_state): (_, PDLVerifierState<Secp256k1, Keccak256>) = PDLVerifierFirstMessage::create(&statement);
+        let (prover_first_message, prover_state) = PDLProverFirstMessage::create::<Secp256k1, Keccak256>(&dk, &verifier_first_message);
+        let mut verifier_second_message = verifier_state.second_message();
+        verifier_second_message.a = &verifier_second_message.a + Scalar::from(1);
+        assert_eq!(
+            PDLProverSecondMessage::create(prover_state.clone(), &x1, &verifier_second_message).unwrap_err(),
+            ProofError::HashMismatch
+        );
+
+        let mut prover_second_message = PDLProverSecondMessage::create(prover_state, &x1, &verifier_state.second_message()).unwrap();
+        prover_second_message.q_hat = &prover_second_message.q_hat + Point::generator();
+        assert_eq!(verifier_state.verify(&prover_first_message, &prover_second_message).unwrap_err(), ProofError::HashMismatch);
+    }
+}
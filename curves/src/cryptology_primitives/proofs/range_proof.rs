@@ -0,0 +1,350 @@
+//! Bulletproofs range proof: the Pedersen commitments V_j = v_j * G + gamma_j * H open to values
+//! in [0, 2^n), without revealing them. m values are proven at once in an aggregated proof of
+//! 2 * log2(n * m) + 4 points and 5 scalars, a single proof is the m = 1 case.
+//!
+//! The prover commits to the bits a_L of the values and to a_R = a_L - 1, the verifier's random
+//! challenges y, z fold "every a_L[i] is a bit and the bits add up to v_j" into one inner product
+//! <l(x), r(x)> = t(x), whose constant term only depends on the values. The inner product itself
+//! is proven with [InnerProductProof].
+//! https://eprint.iacr.org/2017/1066.pdf <<Bulletproofs: Short Proofs for Confidential Transactions and More>> section 4
+
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use curv::arithmetic::BitManipulation;
+use curv::BigInt;
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::commitments::pedersen::{generator_h, hash_to_point, PedersenCommitment, PedersenOpening};
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::inner_product::{inner_product, InnerProductProof};
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::rng::random_scalar;
+use crate::elliptic::msm::multi_scalar_mul;
+use crate::elliptic::per_curve::PerCurve;
+
+const TRANSCRIPT_LABEL: &[u8] = b"bulletproofs_range_proof";
+const GENERATORS_G_SEED: &[u8] = b"bulletproofs_g";
+const GENERATORS_H_SEED: &[u8] = b"bulletproofs_h";
+
+pub const MAX_BIT_SIZE: usize = 64;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RangeProof<E: Curve, H: Digest + Clone> {
+    pub a: Point<E>,
+    pub s: Point<E>,
+    pub t1: Point<E>,
+    pub t2: Point<E>,
+    pub tau_x: Scalar<E>,
+    pub mu: Scalar<E>,
+    pub t_hat: Scalar<E>,
+    pub inner_product_proof: InnerProductProof<E>,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+/// The vector generators g_i, h_i, hashed to the curve like the pedersen H. Each one is hashed
+/// once per curve, a longer request extends the cached ones.
+pub fn generators<E: Curve>(len: usize) -> (Vec<Point<E>>, Vec<Point<E>>) {
+    static GENERATORS: PerCurve = PerCurve::new();
+    let cached = GENERATORS.get_or_init::<E, _>(|| Arc::new(Mutex::new((Vec::<Point<E>>::new(), Vec::<Point<E>>::new()))));
+    let mut cached = cached.lock().unwrap();
+    let (g_vec, h_vec) = &mut *cached;
+    for i in g_vec.len() as u64..len as u64 {
+        g_vec.push(hash_to_point([GENERATORS_G_SEED, &i.to_be_bytes()].concat().as_slice()));
+        h_vec.push(hash_to_point([GENERATORS_H_SEED, &i.to_be_bytes()].concat().as_slice()));
+    }
+    (g_vec[..len].to_vec(), h_vec[..len].to_vec())
+}
+
+impl<E: Curve, H: Digest + Clone> RangeProof<E, H> {
+    /// Proves every opening's message is in [0, 2^bit_size). `bit_size` must be a power of two up
+    /// to [MAX_BIT_SIZE] and the number of openings a power of two, otherwise this fails with
+    /// [ProofError::MalformedProof]. A message out of range fails with [ProofError::InvalidWitness].
+    pub fn prove(openings: &[PedersenOpening<E>], bit_size: usize) -> Result<RangeProof<E, H>, ProofError> {
+        Self::prove_with_rng(openings, bit_size, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(openings: &[PedersenOpening<E>], bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> Result<RangeProof<E, H>, ProofError> {
+        Self::check_sizes(openings.len(), bit_size)?;
+        let bound = BigInt::from(1) << bit_size;
+        if openings.iter().any(|opening| opening.message.to_bigint() >= bound) {
+            return Err(ProofError::InvalidWitness);
+        }
+        Ok(Self::prove_internal(openings, bit_size, rng))
+    }
+
+    pub fn verify(proof: &RangeProof<E, H>, commitments: &[PedersenCommitment<E>], bit_size: usize) -> Result<(), ProofError> {
+        Self::check_sizes(commitments.len(), bit_size)?;
+        let (n, m) = (bit_size, commitments.len());
+        let nm = n * m;
+        let (g_vec, h_vec) = generators::<E>(nm);
+        let g = Point::<E>::generator().to_point();
+        let h = generator_h::<E>();
+
+        let mut transcript = Self::transcript(commitments, bit_size);
+        transcript.append_point(b"A", &proof.a);
+        transcript.append_point(b"S", &proof.s);
+        let y = transcript.challenge_scalar::<E>(b"y");
+        let z = transcript.challenge_scalar::<E>(b"z");
+        transcript.append_point(b"T1", &proof.t1);
+        transcript.append_point(b"T2", &proof.t2);
+        let x = transcript.challenge_scalar::<E>(b"x");
+        transcript.append_scalar(b"tau_x", &proof.tau_x);
+        transcript.append_scalar(b"mu", &proof.mu);
+        transcript.append_scalar(b"t_hat", &proof.t_hat);
+        let w = transcript.challenge_scalar::<E>(b"w");
+
+        let y_inv = y.invert().ok_or(ProofError::InvalidResponse)?;
+        let y_powers = powers(&y, nm);
+        let z_powers = powers(&z, m + 3);
+        let two_powers = powers(&Scalar::from(2), n);
+
+        // t_hat * G + tau_x * H = sum(z^(2+j) * V_j) + delta(y, z) * G + x * T1 + x^2 * T2
+        let sum_y = y_powers.iter().fold(Scalar::zero(), |sum, y_i| sum + y_i);
+        let sum_two = two_powers.iter().fold(Scalar::zero(), |sum, two_i| sum + two_i);
+        let delta = (&z - &z_powers[2]) * sum_y
+            - (0..m).fold(Scalar::zero(), |sum, j| sum + &z_powers[3 + j]) * sum_two;
+        let mut scalars = vec![&proof.t_hat - &delta, proof.tau_x.clone(), -x.clone(), -(&x * &x)];
+        let mut points = vec![g.clone(), h.clone(), proof.t1.clone(), proof.t2.clone()];
+        for (j, commitment) in commitments.iter().enumerate() {
+            scalars.push(-z_powers[2 + j].clone());
+            points.push(commitment.commitment.clone());
+        }
+        if !multi_scalar_mul(&scalars, &points).is_zero() {
+            return Err(ProofError::InvalidResponse);
+        }
+
+        // P = A + x * S - z * <1, g> + <z * y^i + z^(2+j) * 2^(i mod n), h'> - mu * H, with h'_i = y^-i * h_i
+        let y_inv_powers = powers(&y_inv, nm);
+        let h_prime: Vec<Point<E>> = h_vec.iter().zip(&y_inv_powers).map(|(h_i, y_inv_i)| h_i * y_inv_i).collect();
+        let mut scalars = vec![Scalar::from(1), x, -proof.mu.clone()];
+        let mut points = vec![proof.a.clone(), proof.s.clone(), h.clone()];
+        for i in 0..nm {
+            scalars.push(-z.clone());
+            points.push(g_vec[i].clone());
+            scalars.push(&z * &y_powers[i] + &z_powers[2 + i / n] * &two_powers[i % n]);
+            points.push(h_prime[i].clone());
+        }
+        let u = &g * &w;
+        let p = multi_scalar_mul(&scalars, &points) + &u * &proof.t_hat;
+
+        proof.inner_product_proof.verify(&mut transcript, &g_vec, &h_prime, &u, &p)
+    }
+
+    /// Proves a single value is in [0, 2^bit_size)
+    pub fn prove_single(opening: &PedersenOpening<E>, bit_size: usize) -> Result<RangeProof<E, H>, ProofError> {
+        Self::prove(std::slice::from_ref(opening), bit_size)
+    }
+
+    pub fn prove_single_with_rng(opening: &PedersenOpening<E>, bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> Result<RangeProof<E, H>, ProofError> {
+        Self::prove_with_rng(std::slice::from_ref(opening), bit_size, rng)
+    }
+
+    pub fn verify_single(proof: &RangeProof<E, H>, commitment: &PedersenCommitment<E>, bit_size: usize) -> Result<(), ProofError> {
+        Self::verify(proof, std::slice::from_ref(commitment), bit_size)
+    }
+
+    fn check_sizes(values: usize, bit_size: usize) -> Result<(), ProofError> {
+        if values.is_power_of_two() && bit_size.is_power_of_two() && bit_size <= MAX_BIT_SIZE {
+            Ok(())
+        } else {
+            Err(ProofError::MalformedProof)
+        }
+    }
+
+    /// Takes the low `bit_size` bits of each message as is, a message out of range gives a proof
+    /// that doesn't verify
+    fn prove_internal(openings: &[PedersenOpening<E>], bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> RangeProof<E, H> {
+        let (n, m) = (bit_size, openings.len());
+        let nm = n * m;
+        let (g_vec, h_vec) = generators::<E>(nm);
+        let g = Point::<E>::generator().to_point();
+        let h = generator_h::<E>();
+        let one = Scalar::<E>::from(1);
+
+        let commitments: Vec<PedersenCommitment<E>> = openings.iter().map(PedersenCommitment::commit_opening).collect();
+        let mut transcript = Self::transcript(&commitments, bit_size);
+
+        let a_l: Vec<Scalar<E>> = openings.iter()
+            .flat_map(|opening| {
+                let value = opening.message.to_bigint();
+                (0..n).map(move |i| Scalar::from(u16::from(value.test_bit(i))))
+            })
+            .collect();
+        let a_r: Vec<Scalar<E>> = a_l.iter().map(|bit| bit - &one).collect();
+        let alpha = random_scalar::<E>(rng);
+        let a = multi_scalar_mul(&[a_l.as_slice(), &a_r, std::slice::from_ref(&alpha)].concat(), &[g_vec.as_slice(), &h_vec, std::slice::from_ref(&h)].concat());
+
+        let s_l: Vec<Scalar<E>> = (0..nm).map(|_| random_scalar(rng)).collect();
+        let s_r: Vec<Scalar<E>> = (0..nm).map(|_| random_scalar(rng)).collect();
+        let rho = random_scalar::<E>(rng);
+        let s = multi_scalar_mul(&[s_l.as_slice(), &s_r, std::slice::from_ref(&rho)].concat(), &[g_vec.as_slice(), &h_vec, std::slice::from_ref(&h)].concat());
+
+        transcript.append_point(b"A", &a);
+        transcript.append_point(b"S", &s);
+        let y = transcript.challenge_scalar::<E>(b"y");
+        let z = transcript.challenge_scalar::<E>(b"z");
+
+        let y_powers = powers(&y, nm);
+        let z_powers = powers(&z, m + 2);
+        let two_powers = powers(&Scalar::from(2), n);
+
+        // l(X) = l0 + l1 * X, r(X) = r0 + r1 * X, t(X) = <l(X), r(X)> = t0 + t1 * X + t2 * X^2
+        let l0: Vec<Scalar<E>> = a_l.iter().map(|a_l_i| a_l_i - &z).collect();
+        let l1 = s_l;
+        let r0: Vec<Scalar<E>> = (0..nm)
+            .map(|i| &y_powers[i] * (&a_r[i] + &z) + &z_powers[2 + i / n] * &two_powers[i % n])
+            .collect();
+        let r1: Vec<Scalar<E>> = (0..nm).map(|i| &y_powers[i] * &s_r[i]).collect();
+
+        let t1 = inner_product(&l0, &r1) + inner_product(&l1, &r0);
+        let t2 = inner_product(&l1, &r1);
+        let tau1 = random_scalar::<E>(rng);
+        let tau2 = random_scalar::<E>(rng);
+        let t1_commitment = &g * &t1 + &h * &tau1;
+        let t2_commitment = &g * &t2 + &h * &tau2;
+
+        transcript.append_point(b"T1", &t1_commitment);
+        transcript.append_point(b"T2", &t2_commitment);
+        let x = transcript.challenge_scalar::<E>(b"x");
+
+        let l: Vec<Scalar<E>> = (0..nm).map(|i| &l0[i] + &l1[i] * &x).collect();
+        let r: Vec<Scalar<E>> = (0..nm).map(|i| &r0[i] + &r1[i] * &x).collect();
+        let t_hat = inner_product(&l, &r);
+        let tau_x = openings.iter()
+            .enumerate()
+            .fold(&tau2 * &x * &x + &tau1 * &x, |sum, (j, opening)| sum + &z_powers[2 + j] * &opening.blinding_factor);
+        let mu = alpha + rho * &x;
+
+        transcript.append_scalar(b"tau_x", &tau_x);
+        transcript.append_scalar(b"mu", &mu);
+        transcript.append_scalar(b"t_hat", &t_hat);
+        let w = transcript.challenge_scalar::<E>(b"w");
+        let u = &g * &w;
+
+        let y_inv = y.invert().expect("challenge is zero with negligible probability");
+        let h_prime: Vec<Point<E>> = h_vec.iter().zip(powers(&y_inv, nm)).map(|(h_i, y_inv_i)| h_i * y_inv_i).collect();
+        let inner_product_proof = InnerProductProof::prove(&mut transcript, &g_vec, &h_prime, &u, &l, &r);
+
+        RangeProof {
+            a,
+            s,
+            t1: t1_commitment,
+            t2: t2_commitment,
+            tau_x,
+            mu,
+            t_hat,
+            inner_product_proof,
+            hash_choice: PhantomData,
+        }
+    }
+
+    fn transcript(commitments: &[PedersenCommitment<E>], bit_size: usize) -> Transcript<H> {
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_u64(b"n", bit_size as u64);
+        transcript.append_u64(b"m", commitments.len() as u64);
+        for commitment in commitments {
+            transcript.append_point(b"V", &commitment.commitment);
+        }
+        transcript
+    }
+}
+
+/// 1, x, x^2, ..., x^(len - 1)
+fn powers<E: Curve>(x: &Scalar<E>, len: usize) -> Vec<Scalar<E>> {
+    let mut powers = Vec::with_capacity(len);
+    let mut power = Scalar::from(1);
+    for _ in 0..len {
+        powers.push(power.clone());
+        power = power * x;
+    }
+    powers
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::arithmetic::Converter;
+    use curv::BigInt;
+    use curv::elliptic::curves::{Ed25519, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::commitments::pedersen::{hash_to_point, PedersenCommitment, PedersenOpening};
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::range_proof::{generators, RangeProof};
+
+    type Proof = RangeProof<Secp256k1, sha3::Keccak256>;
+
+    #[test]
+    fn test_generators() {
+        let (g_short, h_short) = generators::<Secp256k1>(4);
+        let (g_vec, h_vec) = generators::<Secp256k1>(8);
+        assert_eq!(g_vec[..4], g_short[..]);
+        assert_eq!(h_vec[..4], h_short[..]);
+        assert_eq!(g_vec[7], hash_to_point([b"bulletproofs_g".as_slice(), &7u64.to_be_bytes()].concat().as_slice()));
+        assert_eq!(h_vec[7], hash_to_point([b"bulletproofs_h".as_slice(), &7u64.to_be_bytes()].concat().as_slice()));
+        assert_ne!(g_vec[0], h_vec[0]);
+        assert_ne!(generators::<Ed25519>(1).0[0].to_bytes(true)[..], g_vec[0].to_bytes(true)[..]);
+    }
+
+    #[test]
+    fn test_range_proof() {
+        for value in [0u64, 1, 1 << 32, u64::MAX] {
+            let (commitment, opening) = PedersenCommitment::commit_random(&Scalar::from(value));
+            let proof = Proof::prove_single(&opening, 64).unwrap();
+            assert!(Proof::verify_single(&proof, &commitment, 64).is_ok());
+        }
+
+        let (commitment, opening) = PedersenCommitment::commit_random(&Scalar::from(1000u64));
+        let proof = Proof::prove_single(&opening, 64).unwrap();
+        let proof_json = serde_json::to_string(&proof).unwrap();
+        let decoded: Proof = serde_json::from_str(&proof_json).unwrap();
+        assert!(Proof::verify_single(&decoded, &commitment, 64).is_ok());
+
+        // the proof is bound to the commitment and to the range
+        let (other_commitment, _) = PedersenCommitment::commit_random(&Scalar::from(1000u64));
+        assert_eq!(Proof::verify_single(&proof, &other_commitment, 64), Err(ProofError::InvalidResponse));
+        assert!(Proof::verify_single(&proof, &commitment, 32).is_err());
+    }
+
+    #[test]
+    fn test_aggregated_range_proof() {
+        let values = [7u64, 0, u32::MAX as u64, 123_456_789];
+        let (commitments, openings): (Vec<_>, Vec<_>) = values.iter()
+            .map(|value| PedersenCommitment::<Secp256k1>::commit_random(&Scalar::from(*value)))
+            .unzip();
+        let proof = Proof::prove(&openings, 32).unwrap();
+        assert_eq!(proof.inner_product_proof.l_vec.len(), 7);
+        assert!(Proof::verify(&proof, &commitments, 32).is_ok());
+
+        let mut swapped = commitments.clone();
+        swapped.swap(0, 1);
+        assert_eq!(Proof::verify(&proof, &swapped, 32), Err(ProofError::InvalidResponse));
+        assert_eq!(Proof::verify(&proof, &commitments[..2], 32), Err(ProofError::InvalidResponse));
+        assert_eq!(Proof::verify(&proof, &commitments[..3], 32), Err(ProofError::MalformedProof));
+    }
+
+    #[test]
+    fn test_range_proof_out_of_range() {
+        let too_big = Scalar::<Secp256k1>::from(&(BigInt::from(1) << 64));
+        let negative = -Scalar::<Secp256k1>::from(1);
+        for value in [too_big, negative, Scalar::from(256)] {
+            let (commitment, opening) = PedersenCommitment::commit_random(&value);
+            let bit_size = if value == Scalar::from(256) { 8 } else { 64 };
+            assert_eq!(Proof::prove_single(&opening, bit_size).unwrap_err(), ProofError::InvalidWitness);
+
+            // a cheating prover proving the low bits of the value
+            let forged = Proof::prove_internal(std::slice::from_ref(&opening), bit_size, &mut rand::thread_rng());
+            assert_eq!(Proof::verify_single(&forged, &commitment, bit_size), Err(ProofError::InvalidResponse));
+        }
+
+        let opening = PedersenOpening {
+            message: Scalar::<Secp256k1>::from(BigInt::from_bytes(&[1, 0])),
+            blinding_factor: Scalar::random(),
+        };
+        assert_eq!(Proof::prove_single(&opening, 48).unwrap_err(), ProofError::MalformedProof);
+        assert_eq!(Proof::prove(&[opening.clone(), opening.clone(), opening], 8).unwrap_err(), ProofError::MalformedProof);
+    }
+}
@@ -0,0 +1,169 @@
+//! Chaum-Pedersen proof of discrete log equality: the prover knows x with pk1 = x * base1 and
+//! pk2 = x * base2, without revealing x.
+//! e.g. with base1 = G, pk1 = A, base2 = P and pk2 = C it shows the Diffie-Hellman output C was
+//! computed with the secret behind the published share A.
+//! <<Wallet Databases with Observers>> Chaum, Pedersen
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::sigma_dlog::validate_points;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::rng::random_scalar;
+
+const TRANSCRIPT_LABEL: &[u8] = b"sigma_dleq";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DLEqStatement<E: Curve> {
+    pub base1: Point<E>,
+    pub pk1: Point<E>,
+    pub base2: Point<E>,
+    pub pk2: Point<E>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DLEqProof<E: Curve, H: Digest + Clone> {
+    pub pk1_t_rand_commitment: Point<E>,
+    pub pk2_t_rand_commitment: Point<E>,
+    pub challenge_response: Scalar<E>,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+impl<E: Curve> DLEqStatement<E> {
+    pub fn new(sk: &Scalar<E>, base1: &Point<E>, base2: &Point<E>) -> DLEqStatement<E> {
+        DLEqStatement {
+            base1: base1.clone(),
+            pk1: base1 * sk,
+            base2: base2.clone(),
+            pk2: base2 * sk,
+        }
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> DLEqProof<E, H> {
+    pub fn prove(sk: &Scalar<E>, statement: &DLEqStatement<E>) -> DLEqProof<E, H> {
+        Self::prove_with_rng(sk, statement, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(sk: &Scalar<E>, statement: &DLEqStatement<E>, rng: &mut (impl RngCore + CryptoRng)) -> DLEqProof<E, H> {
+        let sk_t_rand = random_scalar(rng);
+        let pk1_t_rand_commitment = &statement.base1 * &sk_t_rand;
+        let pk2_t_rand_commitment = &statement.base2 * &sk_t_rand;
+
+        let challenge = Self::challenge(statement, &pk1_t_rand_commitment, &pk2_t_rand_commitment);
+
+        let challenge_response = &sk_t_rand - challenge * sk;
+        DLEqProof {
+            pk1_t_rand_commitment,
+            pk2_t_rand_commitment,
+            challenge_response,
+            hash_choice: PhantomData,
+        }
+    }
+
+    /// Checks both (base, pk, commitment) triples like [DLogProof::verify](crate::cryptology_primitives::proofs::sigma_dlog::DLogProof::verify)
+    /// does before the equations: identities and points outside the prime order subgroup are rejected.
+    pub fn verify(proof: &DLEqProof<E, H>, statement: &DLEqStatement<E>) -> Result<(), ProofError> {
+        validate_points(&statement.base1, &statement.pk1, &proof.pk1_t_rand_commitment)?;
+        validate_points(&statement.base2, &statement.pk2, &proof.pk2_t_rand_commitment)?;
+        let challenge = Self::challenge(statement, &proof.pk1_t_rand_commitment, &proof.pk2_t_rand_commitment);
+
+        let pk1_verifier = &proof.challenge_response * &statement.base1 + &challenge * &statement.pk1;
+        let pk2_verifier = &proof.challenge_response * &statement.base2 + &challenge * &statement.pk2;
+
+        if pk1_verifier == proof.pk1_t_rand_commitment && pk2_verifier == proof.pk2_t_rand_commitment {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidResponse)
+        }
+    }
+
+    fn challenge(statement: &DLEqStatement<E>, pk1_t_rand_commitment: &Point<E>, pk2_t_rand_commitment: &Point<E>) -> Scalar<E> {
+        let mut transcript = Transcript::<H>::new(TRANSCRIPT_LABEL);
+        transcript.append_point(b"base1", &statement.base1);
+        transcript.append_point(b"pk1", &statement.pk1);
+        transcript.append_point(b"base2", &statement.base2);
+        transcript.append_point(b"pk2", &statement.pk2);
+        transcript.append_point(b"pk1_t_rand_commitment", pk1_t_rand_commitment);
+        transcript.append_point(b"pk2_t_rand_commitment", pk2_t_rand_commitment);
+        transcript.challenge_scalar(b"challenge")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::sigma_dleq::{DLEqProof, DLEqStatement};
+    use crate::cryptology_primitives::twoparty::dh_key_exchange::{compute_pubkey_with_proof, Party1FirstMessage, Party2FirstMessage};
+
+    #[test]
+    fn test_dleq_proof() {
+        let sk = Scalar::<Secp256k1>::random();
+        let statement = DLEqStatement::new(&sk, &Point::generator().to_point(), Point::base_point2());
+        let proof = DLEqProof::<Secp256k1, sha3::Keccak256>::prove(&sk, &statement);
+        assert!(DLEqProof::verify(&proof, &statement).is_ok());
+
+        let proof_json = serde_json::to_string(&proof).unwrap();
+        let decoded: DLEqProof<Secp256k1, sha3::Keccak256> = serde_json::from_str(&proof_json).unwrap();
+        assert!(DLEqProof::verify(&decoded, &statement).is_ok());
+    }
+
+    #[test]
+    fn test_dleq_proof_unequal_logs() {
+        let sk = Scalar::<Secp256k1>::random();
+        let mut statement = DLEqStatement::new(&sk, &Point::generator().to_point(), Point::base_point2());
+        statement.pk2 = Point::base_point2() * Scalar::random();
+        let proof = DLEqProof::<Secp256k1, sha3::Keccak256>::prove(&sk, &statement);
+        assert_eq!(DLEqProof::verify(&proof, &statement), Err(ProofError::InvalidResponse));
+    }
+
+    #[test]
+    fn test_dleq_proof_rejects_identity() {
+        // sk = 0 is a valid witness for pk1 = pk2 = 0, the proof is still rejected
+        let statement = DLEqStatement::new(&Scalar::<Secp256k1>::zero(), &Point::generator().to_point(), Point::base_point2());
+        let proof = DLEqProof::<Secp256k1, sha3::Keccak256>::prove(&Scalar::zero(), &statement);
+        assert_eq!(DLEqProof::verify(&proof, &statement), Err(ProofError::IdentityPublicKey));
+
+        let sk = Scalar::<Secp256k1>::random();
+        let statement = DLEqStatement::new(&sk, &Point::generator().to_point(), Point::base_point2());
+        let mut proof = DLEqProof::<Secp256k1, sha3::Keccak256>::prove(&sk, &statement);
+        proof.pk2_t_rand_commitment = Point::zero();
+        assert_eq!(DLEqProof::verify(&proof, &statement), Err(ProofError::IdentityCommitment));
+
+        // an identity base2 gives an identity pk2
+        let statement = DLEqStatement::new(&sk, &Point::generator().to_point(), &Point::zero());
+        let proof = DLEqProof::<Secp256k1, sha3::Keccak256>::prove(&sk, &statement);
+        assert_eq!(DLEqProof::verify(&proof, &statement), Err(ProofError::IdentityPublicKey));
+    }
+
+    #[test]
+    fn test_third_party_verifies_dh_shared_point() {
+        let (one_first_msg, one_key_pair) = Party1FirstMessage::<Secp256k1>::first();
+        let (two_first_msg, _two_key_pair) = Party2FirstMessage::<Secp256k1>::first();
+
+        let (shared, proof) = compute_pubkey_with_proof::<Secp256k1, sha3::Keccak256>(&one_key_pair, &two_first_msg.public_share);
+
+        // the verifier only sees the two public shares and the claimed shared point
+        let statement = DLEqStatement {
+            base1: Point::generator().to_point(),
+            pk1: one_first_msg.public_share.clone(),
+            base2: two_first_msg.public_share.clone(),
+            pk2: shared.clone(),
+        };
+        assert!(DLEqProof::verify(&proof, &statement).is_ok());
+
+        let wrong_shared = DLEqStatement {
+            pk2: shared + Point::generator(),
+            ..statement
+        };
+        assert_eq!(DLEqProof::verify(&proof, &wrong_shared), Err(ProofError::InvalidResponse));
+    }
+}
@@ -1,9 +1,25 @@
+//! Schnorr proof of knowledge of a discrete log: pk = sk * base.
+//! [DLogSigma] is the interactive protocol, [DLogProof] its Fiat-Shamir transform.
+
 use std::marker::PhantomData;
 
-use curv::cryptographic_primitives::hashing::{Digest, DigestExt};
+use curv::cryptographic_primitives::hashing::Digest;
 use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use serde::{Deserialize, Serialize};
 
+use crate::cryptology_primitives::errors::{BatchProofError, ProofError};
+use crate::cryptology_primitives::proofs::encoding::{CurveId, decode_header, decode_scalar, encode_header, HashId, scalar_len};
+use crate::cryptology_primitives::proofs::sigma_protocol::{FiatShamir, FiatShamirProof, SigmaProtocol};
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::proofs::validation::ensure_prime_order;
+use crate::cryptology_primitives::rng::random_scalar;
+use crate::elliptic::msm::multi_scalar_mul;
+
+const TRANSCRIPT_LABEL: &[u8] = b"sigma_dlog";
+const NONCE_LABEL: &[u8] = b"sigma_dlog_nonce";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DLogProof<E: Curve, H: Digest + Clone> {
     pub pk: Point<E>,
@@ -12,55 +28,312 @@ pub struct DLogProof<E: Curve, H: Digest + Clone> {
     pub hash_choice: PhantomData<fn(H)>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DLogStatement<E: Curve> {
+    pub base: Point<E>,
+    pub pk: Point<E>,
+}
+
+/// Commit R = r * base, respond z = r - c * sk, check z * base + c * pk = R
+pub struct DLogSigma<E: Curve> {
+    _phantom: PhantomData<fn(E)>,
+}
+
+impl<E: Curve> SigmaProtocol<E> for DLogSigma<E> {
+    type Statement = DLogStatement<E>;
+    type Witness = Scalar<E>;
+    type Commitment = Point<E>;
+    type ProverState = Scalar<E>;
+    type Response = Scalar<E>;
+
+    fn commit(statement: &DLogStatement<E>, _sk: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Point<E>, Scalar<E>) {
+        let sk_t_rand = random_scalar(rng);
+        (&statement.base * &sk_t_rand, sk_t_rand)
+    }
+
+    fn respond(_statement: &DLogStatement<E>, sk: &Scalar<E>, sk_t_rand: Scalar<E>, challenge: &Scalar<E>) -> Result<Scalar<E>, ProofError> {
+        Ok(sk_t_rand - challenge * sk)
+    }
+
+    fn verify(statement: &DLogStatement<E>, pk_t_rand_commitment: &Point<E>, challenge: &Scalar<E>, challenge_response: &Scalar<E>) -> Result<(), ProofError> {
+        let pk_verifier = challenge_response * &statement.base + challenge * &statement.pk;
+
+        if &pk_verifier == pk_t_rand_commitment {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidResponse)
+        }
+    }
+
+    fn simulate(statement: &DLogStatement<E>, challenge: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Point<E>, Scalar<E>) {
+        let challenge_response = random_scalar(rng);
+        let pk_t_rand_commitment = &challenge_response * &statement.base + challenge * &statement.pk;
+        (pk_t_rand_commitment, challenge_response)
+    }
+
+    fn append_statement<H: Digest + Clone>(statement: &DLogStatement<E>, transcript: &mut Transcript<H>) {
+        transcript.append_point(b"base", &statement.base);
+        transcript.append_point(b"pk", &statement.pk);
+    }
+
+    fn append_commitment<H: Digest + Clone>(pk_t_rand_commitment: &Point<E>, transcript: &mut Transcript<H>) {
+        transcript.append_point(b"pk_t_rand_commitment", pk_t_rand_commitment);
+    }
+}
+
+/// Rejects an identity pk, which anyone can prove knowledge of, an identity commitment, and
+/// points outside the prime order subgroup. See [validation](crate::cryptology_primitives::proofs::validation).
+pub(crate) fn validate_points<E: Curve>(base: &Point<E>, pk: &Point<E>, pk_t_rand_commitment: &Point<E>) -> Result<(), ProofError> {
+    if pk.is_zero() {
+        return Err(ProofError::IdentityPublicKey);
+    }
+    if pk_t_rand_commitment.is_zero() {
+        return Err(ProofError::IdentityCommitment);
+    }
+    ensure_prime_order(base)?;
+    ensure_prime_order(pk)?;
+    ensure_prime_order(pk_t_rand_commitment)
+}
+
 impl<E: Curve, H: Digest + Clone> DLogProof<E, H> {
+    /// Proves knowledge of sk with pk = sk * G
     pub fn prove(sk: &Scalar<E>) -> DLogProof<E, H> {
-        let generator = Point::<E>::generator();
+        Self::prove_with_rng(sk, &mut rand::thread_rng())
+    }
 
-        let sk_t_rand = Scalar::random();
-        let pk_t_rand_commitment = generator * &sk_t_rand;
+    pub fn prove_with_rng(sk: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> DLogProof<E, H> {
+        Self::prove_internal(sk, &Point::<E>::generator().to_point(), &[], rng)
+    }
 
-        let pk = generator * sk;
+    /// RFC 6979 style: the nonce is derived from sk and everything the challenge is bound to, so
+    /// the same inputs always give the same proof and no RNG is needed. Two proofs for the same
+    /// statement never share a nonce with different challenges, which would reveal sk.
+    pub fn prove_deterministic(sk: &Scalar<E>) -> DLogProof<E, H> {
+        Self::prove_with_context_deterministic(sk, &[])
+    }
 
-        let challenge = H::new()
-            .chain_point(&pk_t_rand_commitment)
-            .chain_point(&generator.to_point())
-            .chain_point(&pk)
-            .result_scalar();
+    /// Proves knowledge of sk with pk = sk * base, for any base such as a second pedersen generator
+    /// or a peer's public share. The base takes the generator's place in the challenge, so
+    /// `prove_with_base(sk, G)` is the same as `prove(sk)`.
+    pub fn prove_with_base(sk: &Scalar<E>, base: &Point<E>) -> DLogProof<E, H> {
+        Self::prove_with_base_with_rng(sk, base, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_base_with_rng(sk: &Scalar<E>, base: &Point<E>, rng: &mut (impl RngCore + CryptoRng)) -> DLogProof<E, H> {
+        Self::prove_internal(sk, base, &[], rng)
+    }
 
-        let challenge_response = &sk_t_rand - challenge * sk;
+    /// Like [DLogProof::prove], with `ctx` (usually [ProofContext::to_bytes](crate::cryptology_primitives::proofs::context::ProofContext::to_bytes))
+    /// absorbed into the challenge, the proof only verifies under the same context
+    pub fn prove_with_context(sk: &Scalar<E>, ctx: &[u8]) -> DLogProof<E, H> {
+        Self::prove_with_context_with_rng(sk, ctx, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_context_with_rng(sk: &Scalar<E>, ctx: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> DLogProof<E, H> {
+        Self::prove_internal(sk, &Point::<E>::generator().to_point(), ctx, rng)
+    }
+
+    pub fn prove_with_context_deterministic(sk: &Scalar<E>, ctx: &[u8]) -> DLogProof<E, H> {
+        let base = Point::<E>::generator().to_point();
+        let mut nonce_transcript = Transcript::<H>::new(NONCE_LABEL);
+        nonce_transcript.append_scalar(b"sk", sk);
+        nonce_transcript.append_point(b"base", &base);
+        nonce_transcript.append_bytes(b"ctx", ctx);
+        let seed = nonce_transcript.challenge_bytes(b"seed", 32);
+        let mut rng = ChaCha20Rng::from_seed(seed.try_into().expect("32 byte seed"));
+        Self::prove_internal(sk, &base, ctx, &mut rng)
+    }
+
+    pub fn verify(proof: &DLogProof<E, H>) -> Result<(), ProofError> {
+        Self::verify_internal(proof, &Point::<E>::generator().to_point(), &[])
+    }
+
+    /// The verifier supplies the base, it is not taken from the proof
+    pub fn verify_with_base(proof: &DLogProof<E, H>, base: &Point<E>) -> Result<(), ProofError> {
+        Self::verify_internal(proof, base, &[])
+    }
+
+    pub fn verify_with_context(proof: &DLogProof<E, H>, ctx: &[u8]) -> Result<(), ProofError> {
+        Self::verify_internal(proof, &Point::<E>::generator().to_point(), ctx)
+    }
+
+    /// Verifies many proofs at once with a random linear combination of their equations:
+    /// sum(rho_i * (z_i * G + c_i * pk_i - R_i)) = 0 for random rho_i, as a single multi-scalar
+    /// multiplication. A batch containing an invalid proof passes with negligible probability.
+    ///
+    /// On failure every proof is verified on its own, to report the offending indices.
+    pub fn verify_batch(proofs: &[DLogProof<E, H>]) -> Result<(), BatchProofError> {
+        Self::verify_batch_internal(proofs, &[])
+    }
+
+    pub fn verify_batch_with_context(proofs: &[DLogProof<E, H>], ctx: &[u8]) -> Result<(), BatchProofError> {
+        Self::verify_batch_internal(proofs, ctx)
+    }
+
+    fn verify_batch_internal(proofs: &[DLogProof<E, H>], ctx: &[u8]) -> Result<(), BatchProofError> {
+        let generator = Point::<E>::generator().to_point();
+        let valid_points = proofs.iter()
+            .all(|proof| validate_points(&generator, &proof.pk, &proof.pk_t_rand_commitment).is_ok());
+
+        let mut scalars = Vec::with_capacity(2 * proofs.len() + 1);
+        let mut points = Vec::with_capacity(2 * proofs.len() + 1);
+        let mut generator_scalar = Scalar::<E>::zero();
+        for proof in proofs {
+            let statement = DLogStatement {
+                base: generator.clone(),
+                pk: proof.pk.clone(),
+            };
+            let challenge = FiatShamir::<E, DLogSigma<E>, H>::challenge(
+                &statement,
+                &proof.pk_t_rand_commitment,
+                &mut Self::transcript(ctx),
+            );
+            let rho = Scalar::<E>::random();
+
+            generator_scalar = generator_scalar + &rho * &proof.challenge_response;
+            scalars.push(&rho * challenge);
+            points.push(proof.pk.clone());
+            scalars.push(-rho);
+            points.push(proof.pk_t_rand_commitment.clone());
+        }
+        scalars.push(generator_scalar);
+        points.push(generator);
+
+        if valid_points && multi_scalar_mul(&scalars, &points).is_zero() {
+            return Ok(());
+        }
+        let invalid_indices = proofs.iter()
+            .enumerate()
+            .filter(|(_, proof)| Self::verify_with_context(proof, ctx).is_err())
+            .map(|(index, _)| index)
+            .collect();
+        Err(BatchProofError { invalid_indices })
+    }
+
+    fn prove_internal(sk: &Scalar<E>, base: &Point<E>, ctx: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> DLogProof<E, H> {
+        let statement = DLogStatement {
+            base: base.clone(),
+            pk: base * sk,
+        };
+        let proof = FiatShamir::<E, DLogSigma<E>, H>::prove_with_rng(&statement, sk, &mut Self::transcript(ctx), rng)
+            .expect("a dlog response can't fail");
         DLogProof {
-            pk,
-            pk_t_rand_commitment,
-            challenge_response,
+            pk: statement.pk,
+            pk_t_rand_commitment: proof.commitment,
+            challenge_response: proof.response,
             hash_choice: PhantomData,
         }
     }
 
-    pub fn verify(proof: &DLogProof<E, H>) -> Result<(), String> {
-        let generator = Point::<E>::generator();
+    fn verify_internal(proof: &DLogProof<E, H>, base: &Point<E>, ctx: &[u8]) -> Result<(), ProofError> {
+        validate_points(base, &proof.pk, &proof.pk_t_rand_commitment)?;
+        let statement = DLogStatement {
+            base: base.clone(),
+            pk: proof.pk.clone(),
+        };
+        let sigma_proof = FiatShamirProof::<E, DLogSigma<E>> {
+            commitment: proof.pk_t_rand_commitment.clone(),
+            response: proof.challenge_response.clone(),
+        };
+        FiatShamir::<E, DLogSigma<E>, H>::verify(&statement, &sigma_proof, &mut Self::transcript(ctx))
+    }
+
+    fn transcript(ctx: &[u8]) -> Transcript<H> {
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_bytes(b"ctx", ctx);
+        transcript
+    }
+}
+
+/// Compact encoding, header || c || z with the challenge c instead of the commitment R, 3 + 64
+/// bytes on secp256k1. Neither pk nor the base is encoded, the verifier has to know them anyway.
+/// Decoding recomputes R = z * base + c * pk and checks c is the challenge for R, so it verifies
+/// the proof.
+impl<E: CurveId, H: Digest + Clone + HashId> DLogProof<E, H> {
+    /// Fails with [ProofError::InvalidResponse] for a proof that isn't over the generator, such as
+    /// one from [DLogProof::prove_with_base], since c and z wouldn't decode to its R
+    pub fn to_compact_bytes(&self) -> Result<Vec<u8>, ProofError> {
+        self.to_compact_bytes_internal(&Point::<E>::generator().to_point(), &[])
+    }
 
-        let challenge = H::new()
-            .chain_point(&proof.pk_t_rand_commitment)
-            .chain_point(&generator.to_point())
-            .chain_point(&proof.pk)
-            .result_scalar();
+    pub fn to_compact_bytes_with_context(&self, ctx: &[u8]) -> Result<Vec<u8>, ProofError> {
+        self.to_compact_bytes_internal(&Point::<E>::generator().to_point(), ctx)
+    }
 
-        let pk_verifier = &proof.challenge_response * generator + &challenge * &proof.pk;
+    /// For a proof from [DLogProof::prove_with_base], decoded with
+    /// [DLogProof::from_compact_bytes_with_base] and the same base
+    pub fn to_compact_bytes_with_base(&self, base: &Point<E>) -> Result<Vec<u8>, ProofError> {
+        self.to_compact_bytes_internal(base, &[])
+    }
 
-        if pk_verifier == proof.pk_t_rand_commitment {
-            Ok(())
-        } else {
-            Err(String::from("ProofError: Error while verifying"))
+    fn to_compact_bytes_internal(&self, base: &Point<E>, ctx: &[u8]) -> Result<Vec<u8>, ProofError> {
+        let statement = DLogStatement {
+            base: base.clone(),
+            pk: self.pk.clone(),
+        };
+        let challenge = FiatShamir::<E, DLogSigma<E>, H>::challenge(&statement, &self.pk_t_rand_commitment, &mut Self::transcript(ctx));
+        if &self.challenge_response * base + &challenge * &self.pk != self.pk_t_rand_commitment {
+            return Err(ProofError::InvalidResponse);
         }
+        Ok([
+            encode_header::<E, H>().as_slice(),
+            &challenge.to_bytes(),
+            &self.challenge_response.to_bytes(),
+        ].concat())
+    }
+
+    pub fn from_compact_bytes(bytes: &[u8], pk: &Point<E>) -> Result<DLogProof<E, H>, ProofError> {
+        Self::from_compact_bytes_internal(bytes, pk, &Point::<E>::generator().to_point(), &[])
+    }
+
+    pub fn from_compact_bytes_with_context(bytes: &[u8], pk: &Point<E>, ctx: &[u8]) -> Result<DLogProof<E, H>, ProofError> {
+        Self::from_compact_bytes_internal(bytes, pk, &Point::<E>::generator().to_point(), ctx)
+    }
+
+    /// The verifier supplies the base, like [DLogProof::verify_with_base]
+    pub fn from_compact_bytes_with_base(bytes: &[u8], pk: &Point<E>, base: &Point<E>) -> Result<DLogProof<E, H>, ProofError> {
+        Self::from_compact_bytes_internal(bytes, pk, base, &[])
+    }
+
+    fn from_compact_bytes_internal(bytes: &[u8], pk: &Point<E>, base: &Point<E>, ctx: &[u8]) -> Result<DLogProof<E, H>, ProofError> {
+        let body = decode_header::<E, H>(bytes)?;
+        let scalar_len = scalar_len::<E>();
+        if body.len() != 2 * scalar_len {
+            return Err(ProofError::MalformedProof);
+        }
+        let challenge = decode_scalar::<E>(&body[..scalar_len])?;
+        let challenge_response = decode_scalar::<E>(&body[scalar_len..])?;
+
+        let statement = DLogStatement {
+            base: base.clone(),
+            pk: pk.clone(),
+        };
+        let pk_t_rand_commitment = &challenge_response * &statement.base + &challenge * pk;
+        validate_points(&statement.base, pk, &pk_t_rand_commitment)?;
+        let expected_challenge = FiatShamir::<E, DLogSigma<E>, H>::challenge(&statement, &pk_t_rand_commitment, &mut Self::transcript(ctx));
+        if expected_challenge != challenge {
+            return Err(ProofError::InvalidResponse);
+        }
+        Ok(DLogProof {
+            pk: statement.pk,
+            pk_t_rand_commitment,
+            challenge_response,
+            hash_choice: PhantomData,
+        })
     }
 }
 
 
 #[cfg(test)]
 mod tests {
-    use curv::elliptic::curves::{Scalar, Secp256k1};
+    use curv::BigInt;
+    use curv::elliptic::curves::{Curve, ECPoint, ECScalar, Ed25519, Point, Scalar, Secp256k1, Secp256r1};
 
+    use crate::cryptology_primitives::errors::{BatchProofError, ProofError};
+    use crate::cryptology_primitives::proofs::context::ProofContext;
     use crate::cryptology_primitives::proofs::sigma_dlog::DLogProof;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
 
     #[test]
     fn test_dlog_proof() {
@@ -71,4 +344,217 @@ mod tests {
         println!("dlog_proof={:?}", dlog_proof);
         assert!(DLogProof::verify(&dlog_proof).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_dlog_proof_tampered() {
+        let witness = Scalar::random();
+        let mut dlog_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&witness);
+        dlog_proof.pk = Point::generator() * Scalar::random();
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::InvalidResponse));
+    }
+
+    #[test]
+    fn test_dlog_proof_with_context() {
+        let witness = Scalar::random();
+        let ctx = ProofContext::new(b"test_dlog_proof", b"session-1", 1).to_bytes();
+        let dlog_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_context(&witness, &ctx);
+        assert!(DLogProof::verify_with_context(&dlog_proof, &ctx).is_ok());
+
+        // replayed into another session, by another party, or without any context
+        let other_session = ProofContext::new(b"test_dlog_proof", b"session-2", 1).to_bytes();
+        let other_party = ProofContext::new(b"test_dlog_proof", b"session-1", 2).to_bytes();
+        let other_label = ProofContext::new(b"another_protocol", b"session-1", 1).to_bytes();
+        for wrong_ctx in [other_session, other_party, other_label] {
+            assert_eq!(DLogProof::verify_with_context(&dlog_proof, &wrong_ctx), Err(ProofError::InvalidResponse));
+        }
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::InvalidResponse));
+    }
+
+    #[test]
+    fn test_dlog_proof_with_base() {
+        let witness = Scalar::random();
+        let base = Point::<Secp256k1>::base_point2();
+        let dlog_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_base(&witness, base);
+        assert_eq!(dlog_proof.pk, base * &witness);
+        assert!(DLogProof::verify_with_base(&dlog_proof, base).is_ok());
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::InvalidResponse));
+
+        // a peer's public share as the base
+        let peer_share = Point::generator() * Scalar::<Secp256k1>::random();
+        let proof_on_share = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_base(&witness, &peer_share);
+        assert!(DLogProof::verify_with_base(&proof_on_share, &peer_share).is_ok());
+        assert_eq!(DLogProof::verify_with_base(&proof_on_share, base), Err(ProofError::InvalidResponse));
+
+        // the generator form is the base form with G
+        let generator_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&witness);
+        assert!(DLogProof::verify_with_base(&generator_proof, &Point::generator().to_point()).is_ok());
+    }
+
+    #[test]
+    fn test_dlog_proof_verify_batch() {
+        let proofs: Vec<_> = (0..100)
+            .map(|_| DLogProof::<Secp256k1, sha3::Keccak256>::prove(&Scalar::random()))
+            .collect();
+
+        assert!(proofs.iter().map(DLogProof::verify).all(|result| result.is_ok()));
+        assert!(DLogProof::verify_batch(&proofs).is_ok());
+        assert!(DLogProof::<Secp256k1, sha3::Keccak256>::verify_batch(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_dlog_proof_verify_batch_reports_invalid_proofs() {
+        let ctx = ProofContext::new(b"keygen", b"round-1", 0).to_bytes();
+        let mut proofs: Vec<_> = (0..64)
+            .map(|_| DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_context(&Scalar::random(), &ctx))
+            .collect();
+        proofs[3].challenge_response = Scalar::random();
+        proofs[57].pk = Point::generator() * Scalar::random();
+
+        let individual: Vec<_> = proofs.iter()
+            .enumerate()
+            .filter(|(_, proof)| DLogProof::verify_with_context(proof, &ctx).is_err())
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(individual, vec![3, 57]);
+        assert_eq!(
+            DLogProof::verify_batch_with_context(&proofs, &ctx),
+            Err(BatchProofError { invalid_indices: individual })
+        );
+
+        // valid proofs, but for another context
+        proofs.truncate(2);
+        assert_eq!(
+            DLogProof::verify_batch(&proofs).map_err(|e| e.invalid_indices),
+            Err(vec![0, 1])
+        );
+    }
+
+    #[test]
+    fn test_dlog_proof_known_answer() {
+        let sk = Scalar::<Secp256k1>::from(0x5eed_u64);
+
+        let proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_rng(&sk, &mut ChaCha20Rng::seed_from_u64(2023));
+        assert!(DLogProof::verify(&proof).is_ok());
+        assert_eq!(hex::encode(proof.pk_t_rand_commitment.to_bytes(true)), "025386ac64ed3bce7526fb0f0a7d679ba4d50bc405566e0941305ccd5ed189b315");
+        assert_eq!(hex::encode(&*proof.challenge_response.to_bytes()), "1415e275543579c4a280314fe393e1ce5b6724d19f9b1a2978b4fafe4eb597ce");
+
+        let again = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_rng(&sk, &mut ChaCha20Rng::seed_from_u64(2023));
+        assert_eq!(again.challenge_response, proof.challenge_response);
+    }
+
+    #[test]
+    fn test_dlog_proof_deterministic() {
+        let sk = Scalar::<Secp256k1>::from(0x5eed_u64);
+
+        let proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove_deterministic(&sk);
+        assert!(DLogProof::verify(&proof).is_ok());
+        assert_eq!(hex::encode(proof.pk_t_rand_commitment.to_bytes(true)), "0267d6c3acacbe5b91cd15380929a2edd3e57fb87cc9ecb98d591053de174e0e8d");
+        assert_eq!(hex::encode(&*proof.challenge_response.to_bytes()), "d84553598957cd5d11592a242876f40e0fe464c27e402a7d23abaef909e39d28");
+        let again = DLogProof::<Secp256k1, sha3::Keccak256>::prove_deterministic(&sk);
+        assert_eq!(again.pk_t_rand_commitment, proof.pk_t_rand_commitment);
+        assert_eq!(again.challenge_response, proof.challenge_response);
+
+        // another context or another key gives another nonce
+        let with_context = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_context_deterministic(&sk, b"ctx");
+        assert!(DLogProof::verify_with_context(&with_context, b"ctx").is_ok());
+        assert_ne!(with_context.pk_t_rand_commitment, proof.pk_t_rand_commitment);
+        let other_key = DLogProof::<Secp256k1, sha3::Keccak256>::prove_deterministic(&Scalar::from(0x5eee_u64));
+        assert_ne!(other_key.pk_t_rand_commitment, proof.pk_t_rand_commitment);
+    }
+
+    #[test]
+    fn test_dlog_proof_compact_encoding() {
+        let sk = Scalar::<Secp256k1>::random();
+        let proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&sk);
+        let bytes = proof.to_compact_bytes().unwrap();
+        assert_eq!(bytes.len(), 3 + 64);
+        assert_eq!(bytes[..3], [1, 1, 4]);
+
+        let decoded = DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes(&bytes, &proof.pk).unwrap();
+        assert_eq!(decoded.pk_t_rand_commitment, proof.pk_t_rand_commitment);
+        assert_eq!(decoded.challenge_response, proof.challenge_response);
+
+        let other_pk = Point::generator() * Scalar::random();
+        assert_eq!(DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes(&bytes, &other_pk).unwrap_err(), ProofError::InvalidResponse);
+        let mut tampered = bytes.clone();
+        tampered[40] ^= 1;
+        assert_eq!(DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes(&tampered, &proof.pk).unwrap_err(), ProofError::InvalidResponse);
+        assert_eq!(DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes(&bytes[..66], &proof.pk).unwrap_err(), ProofError::MalformedProof);
+
+        // the verifier expects another digest or another curve
+        assert_eq!(DLogProof::<Secp256k1, sha2::Sha256>::from_compact_bytes(&bytes, &proof.pk).unwrap_err(), ProofError::DigestMismatch);
+        let ed_sk = Scalar::<Ed25519>::random();
+        let ed_proof = DLogProof::<Ed25519, sha3::Keccak256>::prove(&ed_sk);
+        let ed_bytes = ed_proof.to_compact_bytes().unwrap();
+        assert!(DLogProof::<Ed25519, sha3::Keccak256>::from_compact_bytes(&ed_bytes, &ed_proof.pk).is_ok());
+        assert_eq!(DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes(&ed_bytes, &proof.pk).unwrap_err(), ProofError::CurveMismatch);
+
+        // the challenge is bound to the context
+        let ctx = ProofContext::new(b"keygen", b"session", 1).to_bytes();
+        let proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_context(&sk, &ctx);
+        let bytes = proof.to_compact_bytes_with_context(&ctx).unwrap();
+        assert!(DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes_with_context(&bytes, &proof.pk, &ctx).is_ok());
+        assert_eq!(DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes(&bytes, &proof.pk).unwrap_err(), ProofError::InvalidResponse);
+        assert_eq!(proof.to_compact_bytes().unwrap_err(), ProofError::InvalidResponse);
+
+        // a proof over another base only encodes together with that base
+        let base = Point::<Secp256k1>::base_point2();
+        let proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove_with_base(&sk, base);
+        assert_eq!(proof.to_compact_bytes().unwrap_err(), ProofError::InvalidResponse);
+        let bytes = proof.to_compact_bytes_with_base(base).unwrap();
+        let decoded = DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes_with_base(&bytes, &proof.pk, base).unwrap();
+        assert_eq!(decoded.pk_t_rand_commitment, proof.pk_t_rand_commitment);
+        assert!(DLogProof::<Secp256k1, sha3::Keccak256>::from_compact_bytes(&bytes, &proof.pk).is_err());
+    }
+
+    #[test]
+    fn test_dlog_proof_rejects_identity() {
+        let mut dlog_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&Scalar::random());
+        dlog_proof.pk = Point::zero();
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::IdentityPublicKey));
+
+        // sk = 0 is a valid witness for pk = 0, the proof is still rejected
+        let zero_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&Scalar::zero());
+        assert_eq!(DLogProof::verify(&zero_proof), Err(ProofError::IdentityPublicKey));
+        assert_eq!(DLogProof::verify_batch(&[zero_proof]).map_err(|e| e.invalid_indices), Err(vec![0]));
+
+        let mut dlog_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&Scalar::random());
+        dlog_proof.pk_t_rand_commitment = Point::zero();
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::IdentityCommitment));
+
+        let dlog_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&Scalar::random());
+        assert_eq!(DLogProof::verify_with_base(&dlog_proof, &Point::zero()), Err(ProofError::MalformedPoint));
+    }
+
+    #[test]
+    fn test_dlog_proof_rejects_identity_secp256r1() {
+        let mut dlog_proof = DLogProof::<Secp256r1, sha3::Keccak256>::prove(&Scalar::random());
+        assert!(DLogProof::verify(&dlog_proof).is_ok());
+        dlog_proof.pk_t_rand_commitment = Point::zero();
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::IdentityCommitment));
+        dlog_proof.pk = Point::zero();
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::IdentityPublicKey));
+    }
+
+    #[test]
+    fn test_dlog_proof_rejects_small_order_component_ed25519() {
+        // a point of order 8, P + T is on the curve but outside the prime order subgroup
+        let torsion = hex::decode("26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05").unwrap();
+        let torsion = <Ed25519 as Curve>::Point::deserialize(&torsion).unwrap();
+        assert!(!torsion.is_zero());
+        assert!(torsion.scalar_mul(&<Ed25519 as Curve>::Scalar::from_bigint(&BigInt::from(8))).is_zero());
+
+        let sk = Scalar::<Ed25519>::random();
+        let dlog_proof = DLogProof::<Ed25519, sha3::Keccak256>::prove(&sk);
+        assert!(DLogProof::verify(&dlog_proof).is_ok());
+        let dlog_proof = DLogProof::<Ed25519, sha3::Keccak256>::prove(&Scalar::zero());
+        assert_eq!(DLogProof::verify(&dlog_proof), Err(ProofError::IdentityPublicKey));
+
+        let pk = Point::<Ed25519>::generator() * &sk;
+        let mixed = pk.as_raw().add_point(&torsion);
+        // curv refuses to decode it into a Point, so it can't be carried by a proof
+        assert!(Point::<Ed25519>::from_bytes(&mixed.serialize_compressed()).is_err());
+        assert!(Point::<Ed25519>::from_raw(mixed).is_err());
+        assert!(Point::<Ed25519>::from_raw(torsion).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+//! secp256k1 [DLogProof]s checked on Ethereum by the verifier contract in `contracts/`, called as
+//! `IDLogVerifier.verify(pk, pkTRandCommitment, challengeResponse, ctx)`.
+//!
+//! The contract recomputes the Keccak256 transcript challenge c like [DLogProof::verify] does, and
+//! checks z * G + c * pk = R with one ecrecover call instead of two scalar multiplications:
+//! ecrecover(h, v, r, s) = r^-1 * (s * Q - h * G) where Q is the point with x coordinate r, so
+//! r = pk.x, s = c * pk.x and h = -z * pk.x recover the address of c * pk + z * G, which must
+//! equal the address of R. A call costs about 7k gas on top of the transaction.
+//!
+//! `contracts/DLogVerifier.sol` is the verifier's reference source and `contracts/DLogVerifier.etk`
+//! its assembly, which reverts and returns on the same calldata. `contracts/DLogVerifier.bin`, the
+//! creation code, is the listing assembled with etk.
+//! <<https://ethresear.ch/t/you-can-kinda-abuse-ecrecover-to-do-ecmul-in-secp256k1-today/2384>>
+
+use curv::arithmetic::Converter;
+use curv::BigInt;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use ethers::abi::{encode, Token};
+use ethers::types::U256;
+use sha3::Keccak256;
+
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::sigma_dlog::DLogProof;
+
+pub const DLOG_VERIFIER_BYTECODE: &str = include_str!("../../../contracts/DLogVerifier.bin");
+pub const VERIFY_SIGNATURE: &str = "verify(uint256[2],uint256[2],uint256,bytes)";
+
+impl DLogProof<Secp256k1, Keccak256> {
+    /// Calldata of `verify` for a proof made with [DLogProof::prove]
+    pub fn to_evm_calldata(&self) -> Result<Vec<u8>, ProofError> {
+        self.to_evm_calldata_with_context(&[])
+    }
+
+    /// Calldata of `verify` for a proof made with [DLogProof::prove_with_context].
+    ///
+    /// Fails with [ProofError::MalformedPoint] if pk is the identity, or if pk.x isn't below the
+    /// group order, ecrecover can't take it as r then. That happens with probability about 2^-128.
+    pub fn to_evm_calldata_with_context(&self, ctx: &[u8]) -> Result<Vec<u8>, ProofError> {
+        let pk = evm_point(&self.pk)?;
+        let pk_t_rand_commitment = evm_point(&self.pk_t_rand_commitment)?;
+        if self.pk.x_coord().ok_or(ProofError::MalformedPoint)? >= *Scalar::<Secp256k1>::group_order() {
+            return Err(ProofError::MalformedPoint);
+        }
+
+        let mut calldata = ethers::utils::id(VERIFY_SIGNATURE).to_vec();
+        calldata.extend(encode(&[
+            pk,
+            pk_t_rand_commitment,
+            Token::Uint(evm_uint(&self.challenge_response.to_bigint())),
+            Token::Bytes(ctx.to_vec()),
+        ]));
+        Ok(calldata)
+    }
+}
+
+/// uint256[2] affine coordinates
+fn evm_point(point: &Point<Secp256k1>) -> Result<Token, ProofError> {
+    let x = point.x_coord().ok_or(ProofError::MalformedPoint)?;
+    let y = point.y_coord().ok_or(ProofError::MalformedPoint)?;
+    Ok(Token::FixedArray(vec![Token::Uint(evm_uint(&x)), Token::Uint(evm_uint(&y))]))
+}
+
+fn evm_uint(value: &BigInt) -> U256 {
+    U256::from_big_endian(&value.to_bytes())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+    use revm::db::InMemoryDB;
+    use revm::Evm;
+    use revm::primitives::{Address, Bytes, ExecutionResult, Output, TxKind};
+    use sha3::Keccak256;
+
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::context::ProofContext;
+    use crate::cryptology_primitives::proofs::sigma_dlog::DLogProof;
+    use crate::cryptology_primitives::proofs::sigma_dlog_evm::DLOG_VERIFIER_BYTECODE;
+
+    type Proof = DLogProof<Secp256k1, Keccak256>;
+
+    struct Verifier {
+        evm: Evm<'static, (), InMemoryDB>,
+        address: Address,
+    }
+
+    impl Verifier {
+        fn deploy() -> Verifier {
+            let mut evm = Evm::builder()
+                .with_db(InMemoryDB::default())
+                .modify_tx_env(|tx| {
+                    tx.transact_to = TxKind::Create;
+                    tx.data = Bytes::from(hex::decode(DLOG_VERIFIER_BYTECODE.trim()).unwrap());
+                })
+                .build();
+            let address = match evm.transact_commit().unwrap() {
+                ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => address,
+                result => panic!("deployment failed: {:?}", result),
+            };
+            Verifier { evm, address }
+        }
+
+        /// Some((verified, gas used)), None if the call reverted
+        fn call(&mut self, calldata: Vec<u8>) -> Option<(bool, u64)> {
+            self.evm.tx_mut().transact_to = TxKind::Call(self.address);
+            self.evm.tx_mut().data = Bytes::from(calldata);
+            match self.evm.transact().unwrap().result {
+                ExecutionResult::Success { output, gas_used, .. } => {
+                    let output = output.into_data();
+                    assert_eq!(output.len(), 32);
+                    Some((output[31] == 1, gas_used))
+                }
+                _ => None,
+            }
+        }
+
+        fn verify(&mut self, proof: &Proof, ctx: &[u8]) -> bool {
+            self.call(proof.to_evm_calldata_with_context(ctx).unwrap()).unwrap().0
+        }
+    }
+
+    #[test]
+    fn test_bytecode_matches_source() {
+        let mut bytecode = Vec::new();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/contracts/DLogVerifier.etk");
+        etk_asm::ingest::Ingest::new(&mut bytecode).ingest_file(path).unwrap();
+        assert_eq!(hex::encode(bytecode), DLOG_VERIFIER_BYTECODE.trim());
+    }
+
+    #[test]
+    fn test_evm_verifier() {
+        let mut verifier = Verifier::deploy();
+        for _ in 0..8 {
+            let proof = Proof::prove(&Scalar::random());
+            assert!(Proof::verify(&proof).is_ok());
+            let (verified, gas_used) = verifier.call(proof.to_evm_calldata().unwrap()).unwrap();
+            assert!(verified);
+            // calldata and execution on top of the 21k of the transaction
+            assert!(gas_used - 21_000 < 7_500, "gas used: {}", gas_used);
+        }
+
+        let ctx = ProofContext::new(b"evm_test", b"session", 1).to_bytes();
+        let proof = Proof::prove_with_context(&Scalar::random(), &ctx);
+        assert!(verifier.verify(&proof, &ctx));
+        assert!(!verifier.verify(&proof, &[]));
+        assert!(!verifier.verify(&proof, &ctx[1..]));
+    }
+
+    #[test]
+    fn test_evm_verifier_rejects_invalid_proofs() {
+        let mut verifier = Verifier::deploy();
+
+        let mut proof = Proof::prove(&Scalar::random());
+        proof.challenge_response = &proof.challenge_response + Scalar::from(1);
+        assert!(!verifier.verify(&proof, &[]));
+
+        let mut proof = Proof::prove(&Scalar::random());
+        proof.pk_t_rand_commitment = &proof.pk_t_rand_commitment + Point::generator();
+        assert!(!verifier.verify(&proof, &[]));
+
+        let mut proof = Proof::prove(&Scalar::random());
+        proof.pk = Point::generator() * Scalar::random();
+        assert!(!verifier.verify(&proof, &[]));
+
+        let proof = Proof::prove(&Scalar::zero());
+        assert_eq!(proof.to_evm_calldata().unwrap_err(), ProofError::MalformedPoint);
+    }
+
+    #[test]
+    fn test_evm_verifier_rejects_malformed_calldata() {
+        let mut verifier = Verifier::deploy();
+        let proof = Proof::prove(&Scalar::random());
+        let calldata = proof.to_evm_calldata().unwrap();
+
+        // a point off the curve
+        let mut off_curve = calldata.clone();
+        off_curve[4 + 63] ^= 1;
+        assert_eq!(verifier.call(off_curve).map(|(verified, _)| verified), Some(false));
+        // a response above the group order
+        let mut large_response = calldata.clone();
+        large_response[4 + 128..4 + 160].copy_from_slice(&[0xff; 32]);
+        assert_eq!(verifier.call(large_response).map(|(verified, _)| verified), Some(false));
+
+        // unknown selector, truncated, ctx length past the end of calldata, non-canonical ctx offset
+        let mut selector = calldata.clone();
+        selector[0] ^= 1;
+        assert_eq!(verifier.call(selector), None);
+        assert_eq!(verifier.call(calldata[..calldata.len() - 1].to_vec()), None);
+        let mut ctx_len = calldata.clone();
+        ctx_len[4 + 223] = 1;
+        assert_eq!(verifier.call(ctx_len), None);
+        let mut ctx_offset = calldata.clone();
+        ctx_offset[4 + 191] = 0xe0;
+        ctx_offset.extend([0; 32]);
+        assert_eq!(verifier.call(ctx_offset), None);
+    }
+}
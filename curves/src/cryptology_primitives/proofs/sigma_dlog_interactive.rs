@@ -0,0 +1,246 @@
+//! The Schnorr identification protocol of [DLogSigma] run as an actual interactive protocol, for
+//! settings where the challenge must come from a live verifier rather than a hash:
+//!
+//! ```text
+//! prover                                        verifier
+//! SchnorrProver::commit      --- R --->
+//!                            <--- c ---         SchnorrVerifier::challenge
+//! SchnorrProver::respond     --- z --->         SchnorrVerifier::check
+//! ```
+//!
+//! The protocol is only honest-verifier zero knowledge: [simulate] produces accepting transcripts
+//! with the same distribution as real ones without knowing sk, as long as the challenge is drawn
+//! uniformly and independently of R. A verifier that picks c depending on R gets no such guarantee,
+//! use [DLogProof](crate::cryptology_primitives::proofs::sigma_dlog::DLogProof) then.
+
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::sigma_dlog::{DLogSigma, DLogStatement, validate_points};
+use crate::cryptology_primitives::proofs::sigma_protocol::SigmaProtocol;
+use crate::cryptology_primitives::rng::random_scalar;
+
+/// The three messages of one run: R, c, z
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchnorrTranscript<E: Curve> {
+    pub pk_t_rand_commitment: Point<E>,
+    pub challenge: Scalar<E>,
+    pub challenge_response: Scalar<E>,
+}
+
+/// The prover between its two moves, holding the nonce behind R.
+/// Not `Clone`: answering two challenges with the same nonce reveals sk.
+pub struct SchnorrProver<E: Curve> {
+    statement: DLogStatement<E>,
+    sk: Scalar<E>,
+    sk_t_rand: Scalar<E>,
+}
+
+/// The verifier between sending c and receiving z
+pub struct SchnorrVerifier<E: Curve> {
+    statement: DLogStatement<E>,
+    pk_t_rand_commitment: Point<E>,
+    challenge: Scalar<E>,
+}
+
+impl<E: Curve> SchnorrProver<E> {
+    /// First move for pk = sk * base, returns the prover state and R to send
+    pub fn commit(sk: &Scalar<E>, base: &Point<E>) -> (SchnorrProver<E>, Point<E>) {
+        Self::commit_with_rng(sk, base, &mut rand::thread_rng())
+    }
+
+    pub fn commit_with_rng(sk: &Scalar<E>, base: &Point<E>, rng: &mut (impl RngCore + CryptoRng)) -> (SchnorrProver<E>, Point<E>) {
+        let statement = DLogStatement {
+            base: base.clone(),
+            pk: base * sk,
+        };
+        let (pk_t_rand_commitment, sk_t_rand) = DLogSigma::commit(&statement, sk, rng);
+        let prover = SchnorrProver {
+            statement,
+            sk: sk.clone(),
+            sk_t_rand,
+        };
+        (prover, pk_t_rand_commitment)
+    }
+
+    /// The statement to hand to the verifier
+    pub fn statement(&self) -> &DLogStatement<E> {
+        &self.statement
+    }
+
+    /// Third move, consumes the prover so the nonce answers a single challenge
+    pub fn respond(self, challenge: &Scalar<E>) -> Scalar<E> {
+        DLogSigma::respond(&self.statement, &self.sk, self.sk_t_rand, challenge).expect("a dlog response can't fail")
+    }
+}
+
+impl<E: Curve> SchnorrVerifier<E> {
+    /// Second move, draws a uniform challenge after receiving R. Fails before any challenge is
+    /// sent if pk or R is the identity or outside the prime order subgroup.
+    pub fn challenge(statement: DLogStatement<E>, pk_t_rand_commitment: Point<E>) -> Result<(SchnorrVerifier<E>, Scalar<E>), ProofError> {
+        Self::challenge_with_rng(statement, pk_t_rand_commitment, &mut rand::thread_rng())
+    }
+
+    pub fn challenge_with_rng(
+        statement: DLogStatement<E>,
+        pk_t_rand_commitment: Point<E>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<(SchnorrVerifier<E>, Scalar<E>), ProofError> {
+        validate_points(&statement.base, &statement.pk, &pk_t_rand_commitment)?;
+        let challenge = random_scalar(rng);
+        let verifier = SchnorrVerifier {
+            statement,
+            pk_t_rand_commitment,
+            challenge: challenge.clone(),
+        };
+        Ok((verifier, challenge))
+    }
+
+    /// Checks z * base + c * pk = R, returns the accepted transcript
+    pub fn check(self, challenge_response: Scalar<E>) -> Result<SchnorrTranscript<E>, ProofError> {
+        DLogSigma::verify(&self.statement, &self.pk_t_rand_commitment, &self.challenge, &challenge_response)?;
+        Ok(SchnorrTranscript {
+            pk_t_rand_commitment: self.pk_t_rand_commitment,
+            challenge: self.challenge,
+            challenge_response,
+        })
+    }
+}
+
+impl<E: Curve> SchnorrTranscript<E> {
+    /// Whether the transcript is accepting. Says nothing about sk on its own, see [simulate].
+    pub fn verify(&self, statement: &DLogStatement<E>) -> Result<(), ProofError> {
+        validate_points(&statement.base, &statement.pk, &self.pk_t_rand_commitment)?;
+        DLogSigma::verify(statement, &self.pk_t_rand_commitment, &self.challenge, &self.challenge_response)
+    }
+}
+
+/// An accepting transcript for the statement computed without sk: draw c and z uniformly, set
+/// R = z * base + c * pk. Distributed exactly like a run between the honest prover and verifier.
+pub fn simulate<E: Curve>(statement: &DLogStatement<E>) -> SchnorrTranscript<E> {
+    simulate_with_rng(statement, &mut rand::thread_rng())
+}
+
+pub fn simulate_with_rng<E: Curve>(statement: &DLogStatement<E>, rng: &mut (impl RngCore + CryptoRng)) -> SchnorrTranscript<E> {
+    let challenge = random_scalar(rng);
+    let (pk_t_rand_commitment, challenge_response) = DLogSigma::simulate(statement, &challenge, rng);
+    SchnorrTranscript {
+        pk_t_rand_commitment,
+        challenge,
+        challenge_response,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Ed25519, Point, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::commitments::pedersen::generator_h;
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::sigma_dlog::DLogStatement;
+    use crate::cryptology_primitives::proofs::sigma_dlog_interactive::{simulate, SchnorrProver, SchnorrTranscript, SchnorrVerifier};
+
+    #[test]
+    fn test_interactive_schnorr() {
+        let sk = Scalar::<Secp256k1>::random();
+        for base in [Point::generator().to_point(), generator_h()] {
+            let (prover, pk_t_rand_commitment) = SchnorrProver::commit(&sk, &base);
+            let statement = prover.statement().clone();
+            let (verifier, challenge) = SchnorrVerifier::challenge(statement.clone(), pk_t_rand_commitment).unwrap();
+            let challenge_response = prover.respond(&challenge);
+            let transcript = verifier.check(challenge_response).unwrap();
+            assert!(transcript.verify(&statement).is_ok());
+
+            // a response to another challenge is rejected
+            let (prover, pk_t_rand_commitment) = SchnorrProver::commit(&sk, &base);
+            let (verifier, challenge) = SchnorrVerifier::challenge(statement.clone(), pk_t_rand_commitment).unwrap();
+            let challenge_response = prover.respond(&(challenge + Scalar::from(1)));
+            assert_eq!(verifier.check(challenge_response).unwrap_err(), ProofError::InvalidResponse);
+        }
+    }
+
+    #[test]
+    fn test_interactive_schnorr_rejects_identity() {
+        let (prover, _) = SchnorrProver::commit(&Scalar::<Ed25519>::zero(), &Point::generator().to_point());
+        let pk_t_rand_commitment = Point::generator() * Scalar::random();
+        let result = SchnorrVerifier::challenge(prover.statement().clone(), pk_t_rand_commitment);
+        assert_eq!(result.err().unwrap(), ProofError::IdentityPublicKey);
+
+        let (prover, _) = SchnorrProver::commit(&Scalar::<Ed25519>::random(), &Point::generator().to_point());
+        let result = SchnorrVerifier::challenge(prover.statement().clone(), Point::zero());
+        assert_eq!(result.err().unwrap(), ProofError::IdentityCommitment);
+    }
+
+    #[test]
+    fn test_simulator_hvzk() {
+        let sk = Scalar::<Secp256k1>::random();
+        let statement = DLogStatement {
+            base: Point::generator().to_point(),
+            pk: Point::generator() * &sk,
+        };
+
+        for _ in 0..16 {
+            // the simulator never sees sk, its transcripts are accepting
+            let simulated = simulate(&statement);
+            assert!(simulated.verify(&statement).is_ok());
+
+            // and each one is exactly the honest run with nonce r = z + c * sk. r <-> z is a
+            // bijection for a fixed c, so uniform nonces and uniform simulated responses give
+            // the same distribution of transcripts
+            let prover = SchnorrProver {
+                statement: statement.clone(),
+                sk: sk.clone(),
+                sk_t_rand: &simulated.challenge_response + &simulated.challenge * &sk,
+            };
+            let pk_t_rand_commitment = &statement.base * &prover.sk_t_rand;
+            let honest = SchnorrTranscript {
+                challenge_response: prover.respond(&simulated.challenge),
+                challenge: simulated.challenge.clone(),
+                pk_t_rand_commitment,
+            };
+            assert_eq!(honest, simulated);
+        }
+
+        let transcript_json = serde_json::to_string(&simulate(&statement)).unwrap();
+        let decoded: SchnorrTranscript<Secp256k1> = serde_json::from_str(&transcript_json).unwrap();
+        assert!(decoded.verify(&statement).is_ok());
+    }
+
+    #[test]
+    fn test_simulator_needs_challenge_in_advance() {
+        // without sk the simulator only helps a prover who knows c before sending R, a fresh
+        // challenge from an honest verifier catches it
+        let statement = DLogStatement {
+            base: Point::<Secp256k1>::generator().to_point(),
+            pk: Point::generator() * Scalar::random(),
+        };
+        let guess = simulate(&statement);
+        let (verifier, _) = SchnorrVerifier::challenge(statement, guess.pk_t_rand_commitment).unwrap();
+        assert_eq!(verifier.check(guess.challenge_response).unwrap_err(), ProofError::InvalidResponse);
+    }
+
+    #[test]
+    fn test_special_soundness_extracts_sk() {
+        // two accepting transcripts with the same R and different challenges give away sk
+        let sk = Scalar::<Secp256k1>::random();
+        let (prover, pk_t_rand_commitment) = SchnorrProver::commit(&sk, &Point::generator().to_point());
+        let statement = prover.statement().clone();
+        let rewound = SchnorrProver {
+            statement: statement.clone(),
+            sk: sk.clone(),
+            sk_t_rand: prover.sk_t_rand.clone(),
+        };
+
+        let (verifier1, challenge1) = SchnorrVerifier::challenge(statement.clone(), pk_t_rand_commitment.clone()).unwrap();
+        let transcript1 = verifier1.check(prover.respond(&challenge1)).unwrap();
+        let (verifier2, challenge2) = SchnorrVerifier::challenge(statement, pk_t_rand_commitment).unwrap();
+        let transcript2 = verifier2.check(rewound.respond(&challenge2)).unwrap();
+
+        let extracted = (transcript1.challenge_response - transcript2.challenge_response)
+            * (challenge2 - challenge1).invert().unwrap();
+        assert_eq!(extracted, sk);
+    }
+}
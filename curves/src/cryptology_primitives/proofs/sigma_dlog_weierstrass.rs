@@ -0,0 +1,156 @@
+//! [DLogProof](super::sigma_dlog::DLogProof) over a runtime-parameterized [PrimeOrderGroup]:
+//! commit R = r * G, challenge c = H(group, pk, R), respond z = r - c * sk, check
+//! z * G + c * pk = R, with the same point checks as the curv version.
+//!
+//! On a tiny group the challenge space is the group order, so soundness is only 1/n. This is for
+//! testing the protocol's edge cases on every point of a small curve, not for real keys.
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::traits::{BitManipulation, Converter, Modulo, Samplable};
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::elliptic::weierstrass::{AffinePoint, PrimeOrderGroup};
+
+const TRANSCRIPT_LABEL: &[u8] = b"sigma_dlog_weierstrass";
+
+#[derive(Clone, Debug)]
+pub struct WeierstrassDLogProof<H: Digest + Clone> {
+    pub pk: AffinePoint,
+    pub pk_t_rand_commitment: AffinePoint,
+    pub challenge_response: NativeBigInt,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+impl<H: Digest + Clone> WeierstrassDLogProof<H> {
+    /// Proves knowledge of sk with pk = sk * G
+    pub fn prove(group: &PrimeOrderGroup, sk: &NativeBigInt) -> WeierstrassDLogProof<H> {
+        Self::prove_with_rng(group, sk, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(group: &PrimeOrderGroup, sk: &NativeBigInt, rng: &mut (impl RngCore + CryptoRng)) -> WeierstrassDLogProof<H> {
+        let pk = group.mul_generator(sk);
+        let sk_t_rand = NativeBigInt::sample_below_with_rng(&(&group.order - NativeBigInt::one()), rng) + NativeBigInt::one();
+        let pk_t_rand_commitment = group.mul_generator(&sk_t_rand);
+        let challenge = challenge::<H>(group, &pk, &pk_t_rand_commitment);
+        let challenge_response = NativeBigInt::mod_sub(&sk_t_rand, &NativeBigInt::mod_mul(&challenge, sk, &group.order), &group.order);
+        WeierstrassDLogProof {
+            pk,
+            pk_t_rand_commitment,
+            challenge_response,
+            hash_choice: PhantomData,
+        }
+    }
+
+    pub fn verify(&self, group: &PrimeOrderGroup) -> Result<(), ProofError> {
+        if self.pk == AffinePoint::Identity {
+            return Err(ProofError::IdentityPublicKey);
+        }
+        if self.pk_t_rand_commitment == AffinePoint::Identity {
+            return Err(ProofError::IdentityCommitment);
+        }
+        if !group.contains(&self.pk) || !group.contains(&self.pk_t_rand_commitment) {
+            return Err(ProofError::MalformedPoint);
+        }
+        if self.challenge_response < NativeBigInt::zero() || self.challenge_response >= group.order {
+            return Err(ProofError::MalformedProof);
+        }
+        let challenge = challenge::<H>(group, &self.pk, &self.pk_t_rand_commitment);
+        let curve = &group.curve;
+        let pk_verifier = curve.add(&group.mul_generator(&self.challenge_response), &curve.scalar_mul(&challenge, &self.pk));
+        if pk_verifier == self.pk_t_rand_commitment {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidResponse)
+        }
+    }
+}
+
+/// Bound to the curve, the subgroup, pk and R, reduced modulo the order
+fn challenge<H: Digest + Clone>(group: &PrimeOrderGroup, pk: &AffinePoint, pk_t_rand_commitment: &AffinePoint) -> NativeBigInt {
+    let curve = &group.curve;
+    let mut transcript = Transcript::<H>::new(TRANSCRIPT_LABEL);
+    transcript.append_bytes(b"p", &curve.p.to_bytes());
+    transcript.append_bytes(b"a", &curve.a.to_bytes());
+    transcript.append_bytes(b"b", &curve.b.to_bytes());
+    transcript.append_bytes(b"generator", &curve.encode_point(&group.generator));
+    transcript.append_bytes(b"order", &group.order.to_bytes());
+    transcript.append_bytes(b"pk", &curve.encode_point(pk));
+    transcript.append_bytes(b"pk_t_rand_commitment", &curve.encode_point(pk_t_rand_commitment));
+    let bytes = transcript.challenge_bytes(b"challenge", group.order.bit_length().div_ceil(8) + 16);
+    NativeBigInt::from_bytes(&bytes).modulus(&group.order)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_traits::One;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::sigma_dlog_weierstrass::WeierstrassDLogProof;
+    use crate::elliptic::weierstrass::{AffinePoint, PrimeOrderGroup, WeierstrassCurve};
+
+    type Proof = WeierstrassDLogProof<sha3::Keccak256>;
+
+    fn group(p: u32, a: u32, b: u32, generator: (u32, u32), order: u32) -> PrimeOrderGroup {
+        let curve = WeierstrassCurve::new(NativeBigInt::from(p), NativeBigInt::from(a), NativeBigInt::from(b)).unwrap();
+        let generator = curve.point(&NativeBigInt::from(generator.0), &NativeBigInt::from(generator.1)).unwrap();
+        PrimeOrderGroup::new(curve, generator, NativeBigInt::from(order)).unwrap()
+    }
+
+    #[test]
+    fn test_dlog_proof_for_every_secret() {
+        // y^2 = x^3 + 7 over F_97 has prime order 79
+        let group = group(97, 0, 7, (1, 28), 79);
+        for sk in 1u32..79 {
+            let proof = Proof::prove(&group, &NativeBigInt::from(sk));
+            assert!(proof.verify(&group).is_ok());
+
+            let mut tampered = proof.clone();
+            tampered.challenge_response = (tampered.challenge_response + NativeBigInt::one()) % NativeBigInt::from(79u32);
+            assert_eq!(tampered.verify(&group), Err(ProofError::InvalidResponse));
+        }
+        let proof = Proof::prove(&group, &NativeBigInt::from(0u32));
+        assert_eq!(proof.verify(&group), Err(ProofError::IdentityPublicKey));
+        let proof = Proof::prove(&group, &NativeBigInt::from(79u32));
+        assert_eq!(proof.verify(&group), Err(ProofError::IdentityPublicKey));
+    }
+
+    #[test]
+    fn test_dlog_proof_rejects_points_outside_the_subgroup() {
+        // y^2 = x^3 + 2x + 3 over F_97 has 100 points, the subgroup of (3, 6) has order 5
+        let group = group(97, 2, 3, (3, 6), 5);
+        let proof = Proof::prove(&group, &NativeBigInt::from(2u32));
+        assert!(proof.verify(&group).is_ok());
+
+        for point in group.curve.points() {
+            if point == AffinePoint::Identity {
+                continue;
+            }
+            let mut with_pk = proof.clone();
+            with_pk.pk = point.clone();
+            let mut with_commitment = proof.clone();
+            with_commitment.pk_t_rand_commitment = point.clone();
+            if group.contains(&point) {
+                // a subgroup point gets to the verification equation
+                assert_ne!(with_pk.verify(&group), Err(ProofError::MalformedPoint));
+            } else {
+                assert_eq!(with_pk.verify(&group), Err(ProofError::MalformedPoint));
+                assert_eq!(with_commitment.verify(&group), Err(ProofError::MalformedPoint));
+            }
+        }
+
+        let mut identity_commitment = proof.clone();
+        identity_commitment.pk_t_rand_commitment = AffinePoint::Identity;
+        assert_eq!(identity_commitment.verify(&group), Err(ProofError::IdentityCommitment));
+        let mut unreduced = proof;
+        unreduced.challenge_response = unreduced.challenge_response + NativeBigInt::from(5u32);
+        assert_eq!(unreduced.verify(&group), Err(ProofError::MalformedProof));
+    }
+}
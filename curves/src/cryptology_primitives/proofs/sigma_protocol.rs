@@ -0,0 +1,334 @@
+//! Sigma protocols are three-move public coin proofs of knowledge: the prover commits, the verifier
+//! sends a random challenge, the prover responds. A protocol here only describes those moves,
+//! [FiatShamir] turns it into a non-interactive proof by drawing the challenge from a
+//! [Transcript], [And] and [Or] compose two protocols.
+//!
+//! Every protocol must be special honest-verifier zero knowledge, i.e. provide `simulate` which
+//! produces an accepting transcript for any given challenge without the witness. The OR
+//! composition relies on it to fake the branch it has no witness for.
+//! <<Proofs of Partial Knowledge and Simplified Design of Witness Hiding Protocols>> Cramer, Damgard, Schoenmakers
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::rng::random_scalar;
+
+pub trait SigmaProtocol<E: Curve> {
+    type Statement;
+    type Witness;
+    type Commitment: Clone;
+    /// the prover's secret randomness, kept between commit and respond
+    type ProverState;
+    type Response: Clone;
+
+    fn commit(statement: &Self::Statement, witness: &Self::Witness, rng: &mut (impl RngCore + CryptoRng)) -> (Self::Commitment, Self::ProverState);
+
+    /// Fails with [ProofError::InvalidWitness] if `state` wasn't committed to with `witness`
+    fn respond(
+        statement: &Self::Statement,
+        witness: &Self::Witness,
+        state: Self::ProverState,
+        challenge: &Scalar<E>,
+    ) -> Result<Self::Response, ProofError>;
+
+    fn verify(
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &Scalar<E>,
+        response: &Self::Response,
+    ) -> Result<(), ProofError>;
+
+    /// An accepting (commitment, response) for `challenge`, computed without the witness
+    fn simulate(statement: &Self::Statement, challenge: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Self::Commitment, Self::Response);
+
+    /// Appends everything the Fiat-Shamir challenge must be bound to, in a fixed order
+    fn append_statement<H: Digest + Clone>(statement: &Self::Statement, transcript: &mut Transcript<H>);
+
+    fn append_commitment<H: Digest + Clone>(commitment: &Self::Commitment, transcript: &mut Transcript<H>);
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "P::Commitment: Serialize, P::Response: Serialize",
+    deserialize = "P::Commitment: Deserialize<'de>, P::Response: Deserialize<'de>"
+))]
+pub struct FiatShamirProof<E: Curve, P: SigmaProtocol<E>> {
+    pub commitment: P::Commitment,
+    pub response: P::Response,
+}
+
+/// Non-interactive proofs, the challenge is drawn from the transcript after the statement and
+/// the commitment. The caller starts the transcript with its own domain label and context.
+pub struct FiatShamir<E: Curve, P: SigmaProtocol<E>, H: Digest + Clone> {
+    _phantom: PhantomData<fn(E, P, H)>,
+}
+
+impl<E: Curve, P: SigmaProtocol<E>, H: Digest + Clone> FiatShamir<E, P, H> {
+    pub fn prove(statement: &P::Statement, witness: &P::Witness, transcript: &mut Transcript<H>) -> Result<FiatShamirProof<E, P>, ProofError> {
+        Self::prove_with_rng(statement, witness, transcript, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(
+        statement: &P::Statement,
+        witness: &P::Witness,
+        transcript: &mut Transcript<H>,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<FiatShamirProof<E, P>, ProofError> {
+        let (commitment, state) = P::commit(statement, witness, rng);
+        let challenge = Self::challenge(statement, &commitment, transcript);
+        let response = P::respond(statement, witness, state, &challenge)?;
+        Ok(FiatShamirProof { commitment, response })
+    }
+
+    pub fn verify(statement: &P::Statement, proof: &FiatShamirProof<E, P>, transcript: &mut Transcript<H>) -> Result<(), ProofError> {
+        let challenge = Self::challenge(statement, &proof.commitment, transcript);
+        P::verify(statement, &proof.commitment, &challenge, &proof.response)
+    }
+
+    /// Appends the statement, then the commitment, and draws the challenge
+    pub fn challenge(statement: &P::Statement, commitment: &P::Commitment, transcript: &mut Transcript<H>) -> Scalar<E> {
+        P::append_statement(statement, transcript);
+        P::append_commitment(commitment, transcript);
+        transcript.challenge_scalar(b"challenge")
+    }
+}
+
+/// Knowledge of both witnesses, both branches answer the same challenge
+pub struct And<P, Q> {
+    _phantom: PhantomData<fn(P, Q)>,
+}
+
+impl<E: Curve, P: SigmaProtocol<E>, Q: SigmaProtocol<E>> SigmaProtocol<E> for And<P, Q> {
+    type Statement = (P::Statement, Q::Statement);
+    type Witness = (P::Witness, Q::Witness);
+    type Commitment = (P::Commitment, Q::Commitment);
+    type ProverState = (P::ProverState, Q::ProverState);
+    type Response = (P::Response, Q::Response);
+
+    fn commit(statement: &Self::Statement, witness: &Self::Witness, rng: &mut (impl RngCore + CryptoRng)) -> (Self::Commitment, Self::ProverState) {
+        let (p_commitment, p_state) = P::commit(&statement.0, &witness.0, rng);
+        let (q_commitment, q_state) = Q::commit(&statement.1, &witness.1, rng);
+        ((p_commitment, q_commitment), (p_state, q_state))
+    }
+
+    fn respond(statement: &Self::Statement, witness: &Self::Witness, state: Self::ProverState, challenge: &Scalar<E>) -> Result<Self::Response, ProofError> {
+        Ok((
+            P::respond(&statement.0, &witness.0, state.0, challenge)?,
+            Q::respond(&statement.1, &witness.1, state.1, challenge)?,
+        ))
+    }
+
+    fn verify(statement: &Self::Statement, commitment: &Self::Commitment, challenge: &Scalar<E>, response: &Self::Response) -> Result<(), ProofError> {
+        P::verify(&statement.0, &commitment.0, challenge, &response.0)?;
+        Q::verify(&statement.1, &commitment.1, challenge, &response.1)
+    }
+
+    fn simulate(statement: &Self::Statement, challenge: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Self::Commitment, Self::Response) {
+        let (p_commitment, p_response) = P::simulate(&statement.0, challenge, rng);
+        let (q_commitment, q_response) = Q::simulate(&statement.1, challenge, rng);
+        ((p_commitment, q_commitment), (p_response, q_response))
+    }
+
+    fn append_statement<H: Digest + Clone>(statement: &Self::Statement, transcript: &mut Transcript<H>) {
+        P::append_statement(&statement.0, transcript);
+        Q::append_statement(&statement.1, transcript);
+    }
+
+    fn append_commitment<H: Digest + Clone>(commitment: &Self::Commitment, transcript: &mut Transcript<H>) {
+        P::append_commitment(&commitment.0, transcript);
+        Q::append_commitment(&commitment.1, transcript);
+    }
+}
+
+/// Knowledge of one of the two witnesses, without revealing which.
+///
+/// The prover simulates the branch it can't answer with a challenge of its choice c_fake, and
+/// answers the real branch with c - c_fake. The verifier only checks that both branch challenges
+/// sum to c.
+pub struct Or<P, Q> {
+    _phantom: PhantomData<fn(P, Q)>,
+}
+
+pub enum OrWitness<L, R> {
+    Left(L),
+    Right(R),
+}
+
+pub enum OrProverState<E: Curve, P: SigmaProtocol<E>, Q: SigmaProtocol<E>> {
+    Left(P::ProverState, Scalar<E>, Q::Response),
+    Right(Scalar<E>, P::Response, Q::ProverState),
+}
+
+/// the left branch challenge, the right one is c minus it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "L: Serialize, R: Serialize",
+    deserialize = "L: Deserialize<'de>, R: Deserialize<'de>"
+))]
+pub struct OrResponse<E: Curve, L, R> {
+    pub left_challenge: Scalar<E>,
+    pub left: L,
+    pub right: R,
+}
+
+impl<E: Curve, P: SigmaProtocol<E>, Q: SigmaProtocol<E>> SigmaProtocol<E> for Or<P, Q> {
+    type Statement = (P::Statement, Q::Statement);
+    type Witness = OrWitness<P::Witness, Q::Witness>;
+    type Commitment = (P::Commitment, Q::Commitment);
+    type ProverState = OrProverState<E, P, Q>;
+    type Response = OrResponse<E, P::Response, Q::Response>;
+
+    fn commit(statement: &Self::Statement, witness: &Self::Witness, rng: &mut (impl RngCore + CryptoRng)) -> (Self::Commitment, Self::ProverState) {
+        let fake_challenge = random_scalar(rng);
+        match witness {
+            OrWitness::Left(witness) => {
+                let (p_commitment, p_state) = P::commit(&statement.0, witness, rng);
+                let (q_commitment, q_response) = Q::simulate(&statement.1, &fake_challenge, rng);
+                ((p_commitment, q_commitment), OrProverState::Left(p_state, fake_challenge, q_response))
+            }
+            OrWitness::Right(witness) => {
+                let (p_commitment, p_response) = P::simulate(&statement.0, &fake_challenge, rng);
+                let (q_commitment, q_state) = Q::commit(&statement.1, witness, rng);
+                ((p_commitment, q_commitment), OrProverState::Right(fake_challenge, p_response, q_state))
+            }
+        }
+    }
+
+    fn respond(statement: &Self::Statement, witness: &Self::Witness, state: Self::ProverState, challenge: &Scalar<E>) -> Result<Self::Response, ProofError> {
+        match (witness, state) {
+            (OrWitness::Left(witness), OrProverState::Left(p_state, right_challenge, right)) => {
+                let left_challenge = challenge - right_challenge;
+                let left = P::respond(&statement.0, witness, p_state, &left_challenge)?;
+                Ok(OrResponse { left_challenge, left, right })
+            }
+            (OrWitness::Right(witness), OrProverState::Right(left_challenge, left, q_state)) => {
+                let right_challenge = challenge - &left_challenge;
+                let right = Q::respond(&statement.1, witness, q_state, &right_challenge)?;
+                Ok(OrResponse { left_challenge, left, right })
+            }
+            // the state was committed to for the other branch
+            _ => Err(ProofError::InvalidWitness),
+        }
+    }
+
+    fn verify(statement: &Self::Statement, commitment: &Self::Commitment, challenge: &Scalar<E>, response: &Self::Response) -> Result<(), ProofError> {
+        let right_challenge = challenge - &response.left_challenge;
+        P::verify(&statement.0, &commitment.0, &response.left_challenge, &response.left)?;
+        Q::verify(&statement.1, &commitment.1, &right_challenge, &response.right)
+    }
+
+    fn simulate(statement: &Self::Statement, challenge: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Self::Commitment, Self::Response) {
+        let left_challenge = random_scalar(rng);
+        let right_challenge = challenge - &left_challenge;
+        let (p_commitment, left) = P::simulate(&statement.0, &left_challenge, rng);
+        let (q_commitment, right) = Q::simulate(&statement.1, &right_challenge, rng);
+        ((p_commitment, q_commitment), OrResponse { left_challenge, left, right })
+    }
+
+    fn append_statement<H: Digest + Clone>(statement: &Self::Statement, transcript: &mut Transcript<H>) {
+        P::append_statement(&statement.0, transcript);
+        Q::append_statement(&statement.1, transcript);
+    }
+
+    fn append_commitment<H: Digest + Clone>(commitment: &Self::Commitment, transcript: &mut Transcript<H>) {
+        P::append_commitment(&commitment.0, transcript);
+        Q::append_commitment(&commitment.1, transcript);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::sigma_dlog::{DLogSigma, DLogStatement};
+    use crate::cryptology_primitives::proofs::sigma_protocol::{And, FiatShamir, Or, OrWitness, SigmaProtocol};
+    use crate::cryptology_primitives::proofs::transcript::Transcript;
+
+    type DLog = DLogSigma<Secp256k1>;
+
+    fn transcript(label: &[u8]) -> Transcript<sha3::Keccak256> {
+        Transcript::new(label)
+    }
+
+    fn random_statement() -> (Scalar<Secp256k1>, DLogStatement<Secp256k1>) {
+        let sk = Scalar::random();
+        let statement = DLogStatement {
+            base: Point::generator().to_point(),
+            pk: Point::generator() * &sk,
+        };
+        (sk, statement)
+    }
+
+    #[test]
+    fn test_or_proof() {
+        // I know the dlog of A or of B
+        let (a, statement_a) = random_statement();
+        let (b, statement_b) = random_statement();
+        let statement = (statement_a, statement_b);
+
+        let knows_a = FiatShamir::<Secp256k1, Or<DLog, DLog>, sha3::Keccak256>::prove(&statement, &OrWitness::Left(a), &mut transcript(b"or")).unwrap();
+        let knows_b = FiatShamir::<Secp256k1, Or<DLog, DLog>, sha3::Keccak256>::prove(&statement, &OrWitness::Right(b), &mut transcript(b"or")).unwrap();
+        assert!(FiatShamir::<Secp256k1, Or<DLog, DLog>, sha3::Keccak256>::verify(&statement, &knows_a, &mut transcript(b"or")).is_ok());
+        assert!(FiatShamir::<Secp256k1, Or<DLog, DLog>, sha3::Keccak256>::verify(&statement, &knows_b, &mut transcript(b"or")).is_ok());
+
+        // a witness for neither statement
+        let (c, _) = random_statement();
+        let knows_neither = FiatShamir::<Secp256k1, Or<DLog, DLog>, sha3::Keccak256>::prove(&statement, &OrWitness::Left(c), &mut transcript(b"or")).unwrap();
+        assert_eq!(
+            FiatShamir::<Secp256k1, Or<DLog, DLog>, sha3::Keccak256>::verify(&statement, &knows_neither, &mut transcript(b"or")),
+            Err(ProofError::InvalidResponse)
+        );
+        assert!(FiatShamir::<Secp256k1, Or<DLog, DLog>, sha3::Keccak256>::verify(&statement, &knows_a, &mut transcript(b"other ctx")).is_err());
+    }
+
+    #[test]
+    fn test_or_respond_rejects_state_of_the_other_branch() {
+        let (a, statement_a) = random_statement();
+        let (b, statement_b) = random_statement();
+        let statement = (statement_a, statement_b);
+        let mut rng = rand::thread_rng();
+
+        let (_, state) = Or::<DLog, DLog>::commit(&statement, &OrWitness::Left(a), &mut rng);
+        let response = Or::<DLog, DLog>::respond(&statement, &OrWitness::Right(b), state, &Scalar::random());
+        assert_eq!(response.err(), Some(ProofError::InvalidWitness));
+    }
+
+    #[test]
+    fn test_and_proof() {
+        let (a, statement_a) = random_statement();
+        let (b, statement_b) = random_statement();
+        let statement = (statement_a, statement_b);
+
+        let proof = FiatShamir::<Secp256k1, And<DLog, DLog>, sha3::Keccak256>::prove(&statement, &(a.clone(), b), &mut transcript(b"and")).unwrap();
+        assert!(FiatShamir::<Secp256k1, And<DLog, DLog>, sha3::Keccak256>::verify(&statement, &proof, &mut transcript(b"and")).is_ok());
+
+        let (c, _) = random_statement();
+        let half_proof = FiatShamir::<Secp256k1, And<DLog, DLog>, sha3::Keccak256>::prove(&statement, &(a, c), &mut transcript(b"and")).unwrap();
+        assert!(FiatShamir::<Secp256k1, And<DLog, DLog>, sha3::Keccak256>::verify(&statement, &half_proof, &mut transcript(b"and")).is_err());
+    }
+
+    #[test]
+    fn test_simulated_transcripts_verify() {
+        let (_, statement_a) = random_statement();
+        let (_, statement_b) = random_statement();
+        let statement = (statement_a, statement_b);
+        let challenge = Scalar::random();
+
+        let (commitment, response) = DLog::simulate(&statement.0, &challenge, &mut rand::thread_rng());
+        assert!(DLog::verify(&statement.0, &commitment, &challenge, &response).is_ok());
+
+        let (commitment, response) = Or::<DLog, DLog>::simulate(&statement, &challenge, &mut rand::thread_rng());
+        assert!(Or::<DLog, DLog>::verify(&statement, &commitment, &challenge, &response).is_ok());
+
+        let (commitment, response) = And::<DLog, DLog>::simulate(&statement, &challenge, &mut rand::thread_rng());
+        assert!(And::<DLog, DLog>::verify(&statement, &commitment, &challenge, &response).is_ok());
+    }
+}
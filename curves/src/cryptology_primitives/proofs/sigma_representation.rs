@@ -0,0 +1,199 @@
+//! Okamoto proof of knowledge of a representation: the prover knows x_1..x_n with
+//! pk = x_1 * G_1 + ... + x_n * G_n, without revealing them. With a single base this is the
+//! Schnorr proof of [DLogSigma](crate::cryptology_primitives::proofs::sigma_dlog::DLogSigma).
+//! <<Provably Secure and Practical Identification Schemes and Corresponding Signature Schemes>> Okamoto
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::sigma_protocol::{FiatShamir, FiatShamirProof, SigmaProtocol};
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::rng::random_scalar;
+use crate::elliptic::msm::multi_scalar_mul;
+
+const TRANSCRIPT_LABEL: &[u8] = b"sigma_representation";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepresentationProof<E: Curve, H: Digest + Clone> {
+    pub pk: Point<E>,
+    pub pk_t_rand_commitment: Point<E>,
+    pub challenge_responses: Vec<Scalar<E>>,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepresentationStatement<E: Curve> {
+    pub bases: Vec<Point<E>>,
+    pub pk: Point<E>,
+}
+
+/// Commit R = sum(r_i * G_i), respond z_i = r_i - c * x_i, check sum(z_i * G_i) + c * pk = R
+pub struct RepresentationSigma<E: Curve> {
+    _phantom: PhantomData<fn(E)>,
+}
+
+impl<E: Curve> SigmaProtocol<E> for RepresentationSigma<E> {
+    type Statement = RepresentationStatement<E>;
+    type Witness = Vec<Scalar<E>>;
+    type Commitment = Point<E>;
+    type ProverState = Vec<Scalar<E>>;
+    type Response = Vec<Scalar<E>>;
+
+    fn commit(statement: &RepresentationStatement<E>, _secrets: &Vec<Scalar<E>>, rng: &mut (impl RngCore + CryptoRng)) -> (Point<E>, Vec<Scalar<E>>) {
+        let t_rands: Vec<Scalar<E>> = statement.bases.iter().map(|_| random_scalar(rng)).collect();
+        (multi_scalar_mul(&t_rands, &statement.bases), t_rands)
+    }
+
+    fn respond(_statement: &RepresentationStatement<E>, secrets: &Vec<Scalar<E>>, t_rands: Vec<Scalar<E>>, challenge: &Scalar<E>) -> Result<Vec<Scalar<E>>, ProofError> {
+        if secrets.len() != t_rands.len() {
+            return Err(ProofError::InvalidWitness);
+        }
+        Ok(t_rands.into_iter()
+            .zip(secrets)
+            .map(|(t_rand, secret)| t_rand - challenge * secret)
+            .collect())
+    }
+
+    fn verify(statement: &RepresentationStatement<E>, pk_t_rand_commitment: &Point<E>, challenge: &Scalar<E>, challenge_responses: &Vec<Scalar<E>>) -> Result<(), ProofError> {
+        if challenge_responses.len() != statement.bases.len() {
+            return Err(ProofError::MalformedProof);
+        }
+        let pk_verifier = multi_scalar_mul(challenge_responses, &statement.bases) + challenge * &statement.pk;
+
+        if &pk_verifier == pk_t_rand_commitment {
+            Ok(())
+        } else {
+            Err(ProofError::InvalidResponse)
+        }
+    }
+
+    fn simulate(statement: &RepresentationStatement<E>, challenge: &Scalar<E>, rng: &mut (impl RngCore + CryptoRng)) -> (Point<E>, Vec<Scalar<E>>) {
+        let challenge_responses: Vec<Scalar<E>> = statement.bases.iter().map(|_| random_scalar(rng)).collect();
+        let pk_t_rand_commitment = multi_scalar_mul(&challenge_responses, &statement.bases) + challenge * &statement.pk;
+        (pk_t_rand_commitment, challenge_responses)
+    }
+
+    fn append_statement<H: Digest + Clone>(statement: &RepresentationStatement<E>, transcript: &mut Transcript<H>) {
+        transcript.append_points(b"bases", &statement.bases);
+        transcript.append_point(b"pk", &statement.pk);
+    }
+
+    fn append_commitment<H: Digest + Clone>(pk_t_rand_commitment: &Point<E>, transcript: &mut Transcript<H>) {
+        transcript.append_point(b"pk_t_rand_commitment", pk_t_rand_commitment);
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> RepresentationProof<E, H> {
+    /// Proves knowledge of the secrets with pk = sum(secrets[i] * bases[i]). Fails with
+    /// [ProofError::InvalidWitness] if there isn't one secret per base.
+    pub fn prove(secrets: &[Scalar<E>], bases: &[Point<E>]) -> Result<RepresentationProof<E, H>, ProofError> {
+        Self::prove_with_rng(secrets, bases, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(secrets: &[Scalar<E>], bases: &[Point<E>], rng: &mut (impl RngCore + CryptoRng)) -> Result<RepresentationProof<E, H>, ProofError> {
+        if bases.is_empty() || secrets.len() != bases.len() {
+            return Err(ProofError::InvalidWitness);
+        }
+        let statement = RepresentationStatement {
+            bases: bases.to_vec(),
+            pk: multi_scalar_mul(secrets, bases),
+        };
+        let proof = FiatShamir::<E, RepresentationSigma<E>, H>::prove_with_rng(&statement, &secrets.to_vec(), &mut Transcript::new(TRANSCRIPT_LABEL), rng)?;
+        Ok(RepresentationProof {
+            pk: statement.pk,
+            pk_t_rand_commitment: proof.commitment,
+            challenge_responses: proof.response,
+            hash_choice: PhantomData,
+        })
+    }
+
+    /// The verifier supplies the bases, they are not taken from the proof
+    pub fn verify(proof: &RepresentationProof<E, H>, bases: &[Point<E>]) -> Result<(), ProofError> {
+        if bases.is_empty() {
+            return Err(ProofError::MalformedProof);
+        }
+        let statement = RepresentationStatement {
+            bases: bases.to_vec(),
+            pk: proof.pk.clone(),
+        };
+        let sigma_proof = FiatShamirProof::<E, RepresentationSigma<E>> {
+            commitment: proof.pk_t_rand_commitment.clone(),
+            response: proof.challenge_responses.clone(),
+        };
+        FiatShamir::<E, RepresentationSigma<E>, H>::verify(&statement, &sigma_proof, &mut Transcript::new(TRANSCRIPT_LABEL))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::commitments::pedersen::hash_to_point;
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::sigma_dlog::{DLogProof, DLogSigma, DLogStatement};
+    use crate::cryptology_primitives::proofs::sigma_protocol::SigmaProtocol;
+    use crate::cryptology_primitives::proofs::sigma_representation::{RepresentationProof, RepresentationSigma, RepresentationStatement};
+
+    fn bases(n: usize) -> Vec<Point<Secp256k1>> {
+        (0..n as u64).map(|i| hash_to_point(&[b"test_base".as_slice(), &i.to_be_bytes()].concat())).collect()
+    }
+
+    #[test]
+    fn test_representation_proof() {
+        for n in [1, 2, 3, 8, 16] {
+            let bases = bases(n);
+            let secrets: Vec<Scalar<Secp256k1>> = (0..n).map(|_| Scalar::random()).collect();
+            let proof = RepresentationProof::<Secp256k1, sha3::Keccak256>::prove(&secrets, &bases).unwrap();
+            assert!(RepresentationProof::verify(&proof, &bases).is_ok());
+
+            let proof_json = serde_json::to_string(&proof).unwrap();
+            let decoded: RepresentationProof<Secp256k1, sha3::Keccak256> = serde_json::from_str(&proof_json).unwrap();
+            assert!(RepresentationProof::verify(&decoded, &bases).is_ok());
+
+            // the proof is bound to the bases and their order
+            let mut reversed = bases.clone();
+            reversed.reverse();
+            if n > 1 {
+                assert_eq!(RepresentationProof::verify(&proof, &reversed), Err(ProofError::InvalidResponse));
+            }
+            assert_eq!(RepresentationProof::verify(&proof, &bases[..n - 1]), Err(ProofError::MalformedProof));
+        }
+    }
+
+    #[test]
+    fn test_representation_proof_single_base_is_dlog() {
+        let sk = Scalar::<Secp256k1>::random();
+        let generator = Point::<Secp256k1>::generator().to_point();
+
+        let proof = RepresentationProof::<Secp256k1, sha3::Keccak256>::prove(std::slice::from_ref(&sk), std::slice::from_ref(&generator)).unwrap();
+        let dlog_proof = DLogProof::<Secp256k1, sha3::Keccak256>::prove(&sk);
+        assert_eq!(proof.pk, dlog_proof.pk);
+        assert!(RepresentationProof::verify(&proof, std::slice::from_ref(&generator)).is_ok());
+
+        // with one base the two protocols accept the same transcripts
+        let statement = RepresentationStatement { bases: vec![generator.clone()], pk: dlog_proof.pk.clone() };
+        let dlog_statement = DLogStatement { base: generator, pk: dlog_proof.pk.clone() };
+        let challenge = Scalar::random();
+        let (commitment, response) = DLogSigma::simulate(&dlog_statement, &challenge, &mut rand::thread_rng());
+        assert!(RepresentationSigma::verify(&statement, &commitment, &challenge, &vec![response.clone()]).is_ok());
+        let (commitment, responses) = RepresentationSigma::simulate(&statement, &challenge, &mut rand::thread_rng());
+        assert!(DLogSigma::verify(&dlog_statement, &commitment, &challenge, &responses[0]).is_ok());
+    }
+
+    #[test]
+    fn test_representation_proof_wrong_witness() {
+        let bases = bases(4);
+        let secrets: Vec<Scalar<Secp256k1>> = (0..4).map(|_| Scalar::random()).collect();
+        assert_eq!(RepresentationProof::<Secp256k1, sha3::Keccak256>::prove(&secrets[..3], &bases).unwrap_err(), ProofError::InvalidWitness);
+
+        let mut proof = RepresentationProof::<Secp256k1, sha3::Keccak256>::prove(&secrets, &bases).unwrap();
+        proof.pk = &proof.pk + &bases[0];
+        assert_eq!(RepresentationProof::verify(&proof, &bases), Err(ProofError::InvalidResponse));
+    }
+}
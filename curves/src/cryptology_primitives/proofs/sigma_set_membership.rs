@@ -0,0 +1,217 @@
+//! Proof that a Pedersen commitment C = m * G + r * H opens to a member of a small public set
+//! {s_1, ..., s_n}, without revealing which one, e.g. that a committed vote or flag is 0 or 1.
+//!
+//! C opens to s_i exactly when C - s_i * G = r * H, so this is an n-way OR of discrete log proofs
+//! with base H. The prover simulates every branch but the true one with a challenge of its
+//! choice, the branch challenges must sum up to the Fiat-Shamir challenge, so at most n - 1 of
+//! them are free.
+//! <<Proofs of Partial Knowledge and Simplified Design of Witness Hiding Protocols>> Cramer, Damgard, Schoenmakers
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::commitments::pedersen::{generator_h, PedersenCommitment, PedersenOpening};
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::sigma_dlog::{DLogSigma, DLogStatement};
+use crate::cryptology_primitives::proofs::sigma_protocol::SigmaProtocol;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::rng::random_scalar;
+
+const TRANSCRIPT_LABEL: &[u8] = b"sigma_set_membership";
+
+/// One (commitment, challenge, response) per set member, in the order of the set
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetMembershipProof<E: Curve, H: Digest + Clone> {
+    pub t_rand_commitments: Vec<Point<E>>,
+    pub challenges: Vec<Scalar<E>>,
+    pub challenge_responses: Vec<Scalar<E>>,
+    pub hash_choice: PhantomData<fn(H)>,
+}
+
+impl<E: Curve, H: Digest + Clone> SetMembershipProof<E, H> {
+    /// Fails with [ProofError::InvalidWitness] if the opening doesn't open the commitment to a member of the set
+    pub fn prove(commitment: &PedersenCommitment<E>, opening: &PedersenOpening<E>, set: &[Scalar<E>]) -> Result<SetMembershipProof<E, H>, ProofError> {
+        Self::prove_with_rng(commitment, opening, set, &mut rand::thread_rng())
+    }
+
+    pub fn prove_with_rng(
+        commitment: &PedersenCommitment<E>,
+        opening: &PedersenOpening<E>,
+        set: &[Scalar<E>],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<SetMembershipProof<E, H>, ProofError> {
+        if commitment.verify(opening).is_err() {
+            return Err(ProofError::InvalidWitness);
+        }
+        let index = set.iter()
+            .position(|member| member == &opening.message)
+            .ok_or(ProofError::InvalidWitness)?;
+        Ok(Self::prove_internal(commitment, &opening.blinding_factor, set, index, rng))
+    }
+
+    pub fn verify(proof: &SetMembershipProof<E, H>, commitment: &PedersenCommitment<E>, set: &[Scalar<E>]) -> Result<(), ProofError> {
+        if set.is_empty()
+            || proof.t_rand_commitments.len() != set.len()
+            || proof.challenges.len() != set.len()
+            || proof.challenge_responses.len() != set.len() {
+            return Err(ProofError::MalformedProof);
+        }
+
+        let statements = Self::statements(commitment, set);
+        let challenge = Self::challenge(commitment, set, &proof.t_rand_commitments);
+        let challenge_sum = proof.challenges.iter().fold(Scalar::zero(), |sum, c| sum + c);
+        if challenge_sum != challenge {
+            return Err(ProofError::HashMismatch);
+        }
+
+        for (i, statement) in statements.iter().enumerate() {
+            DLogSigma::verify(statement, &proof.t_rand_commitments[i], &proof.challenges[i], &proof.challenge_responses[i])?;
+        }
+        Ok(())
+    }
+
+    /// Proves the commitment opens to 0 or 1
+    pub fn prove_bit(commitment: &PedersenCommitment<E>, opening: &PedersenOpening<E>) -> Result<SetMembershipProof<E, H>, ProofError> {
+        Self::prove(commitment, opening, &Self::bit_set())
+    }
+
+    pub fn prove_bit_with_rng(commitment: &PedersenCommitment<E>, opening: &PedersenOpening<E>, rng: &mut (impl RngCore + CryptoRng)) -> Result<SetMembershipProof<E, H>, ProofError> {
+        Self::prove_with_rng(commitment, opening, &Self::bit_set(), rng)
+    }
+
+    pub fn verify_bit(proof: &SetMembershipProof<E, H>, commitment: &PedersenCommitment<E>) -> Result<(), ProofError> {
+        Self::verify(proof, commitment, &Self::bit_set())
+    }
+
+    fn bit_set() -> [Scalar<E>; 2] {
+        [Scalar::zero(), Scalar::from(1)]
+    }
+
+    /// `index` is the branch the prover knows the blinding factor for, every other branch is simulated
+    fn prove_internal(
+        commitment: &PedersenCommitment<E>,
+        blinding_factor: &Scalar<E>,
+        set: &[Scalar<E>],
+        index: usize,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> SetMembershipProof<E, H> {
+        let statements = Self::statements(commitment, set);
+
+        let mut t_rand_commitments = Vec::with_capacity(set.len());
+        let mut challenges = Vec::with_capacity(set.len());
+        let mut challenge_responses = Vec::with_capacity(set.len());
+        let mut t_rand = Scalar::zero();
+        for (i, statement) in statements.iter().enumerate() {
+            if i == index {
+                let (t_rand_commitment, state) = DLogSigma::commit(statement, blinding_factor, rng);
+                t_rand = state;
+                t_rand_commitments.push(t_rand_commitment);
+                challenges.push(Scalar::zero());
+                challenge_responses.push(Scalar::zero());
+            } else {
+                let simulated_challenge = random_scalar(rng);
+                let (t_rand_commitment, challenge_response) = DLogSigma::simulate(statement, &simulated_challenge, rng);
+                t_rand_commitments.push(t_rand_commitment);
+                challenges.push(simulated_challenge);
+                challenge_responses.push(challenge_response);
+            }
+        }
+
+        let challenge = Self::challenge(commitment, set, &t_rand_commitments);
+        let simulated_sum = challenges.iter().fold(Scalar::zero(), |sum, c| sum + c);
+        challenges[index] = challenge - simulated_sum;
+        challenge_responses[index] = DLogSigma::respond(&statements[index], blinding_factor, t_rand, &challenges[index])
+            .expect("a dlog response can't fail");
+
+        SetMembershipProof {
+            t_rand_commitments,
+            challenges,
+            challenge_responses,
+            hash_choice: PhantomData,
+        }
+    }
+
+    /// Branch i claims knowledge of log_H(C - s_i * G)
+    fn statements(commitment: &PedersenCommitment<E>, set: &[Scalar<E>]) -> Vec<DLogStatement<E>> {
+        let h = generator_h::<E>();
+        set.iter()
+            .map(|member| DLogStatement {
+                base: h.clone(),
+                pk: &commitment.commitment - Point::generator() * member,
+            })
+            .collect()
+    }
+
+    fn challenge(commitment: &PedersenCommitment<E>, set: &[Scalar<E>], t_rand_commitments: &[Point<E>]) -> Scalar<E> {
+        let mut transcript = Transcript::<H>::new(TRANSCRIPT_LABEL);
+        transcript.append_point(b"g", &Point::<E>::generator().to_point());
+        transcript.append_point(b"h", &generator_h::<E>());
+        transcript.append_point(b"commitment", &commitment.commitment);
+        transcript.append_u64(b"set", set.len() as u64);
+        for member in set {
+            transcript.append_scalar(b"set", member);
+        }
+        transcript.append_points(b"t_rand_commitments", t_rand_commitments);
+        transcript.challenge_scalar(b"challenge")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Ed25519, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::commitments::pedersen::PedersenCommitment;
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::sigma_set_membership::SetMembershipProof;
+
+    type Proof = SetMembershipProof<Secp256k1, sha3::Keccak256>;
+
+    #[test]
+    fn test_bit_proof() {
+        for bit in 0..2u16 {
+            let (commitment, opening) = PedersenCommitment::commit_random(&Scalar::from(bit));
+            let proof = Proof::prove_bit(&commitment, &opening).unwrap();
+            assert!(Proof::verify_bit(&proof, &commitment).is_ok());
+
+            let proof_json = serde_json::to_string(&proof).unwrap();
+            let decoded: Proof = serde_json::from_str(&proof_json).unwrap();
+            assert!(Proof::verify_bit(&decoded, &commitment).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_set_membership_proof() {
+        let set: Vec<Scalar<Ed25519>> = [3u16, 7, 11, 19, 100].iter().map(|s| Scalar::from(*s)).collect();
+        for member in &set {
+            let (commitment, opening) = PedersenCommitment::commit_random(member);
+            let proof = SetMembershipProof::<Ed25519, sha3::Keccak256>::prove(&commitment, &opening, &set).unwrap();
+            assert!(SetMembershipProof::verify(&proof, &commitment, &set).is_ok());
+
+            // the proof is bound to the set
+            let mut other_set = set.clone();
+            other_set[4] = Scalar::from(101);
+            assert_eq!(SetMembershipProof::verify(&proof, &commitment, &other_set), Err(ProofError::HashMismatch));
+            assert_eq!(SetMembershipProof::verify(&proof, &commitment, &set[..4]), Err(ProofError::MalformedProof));
+        }
+    }
+
+    #[test]
+    fn test_set_membership_rejects_out_of_set_opening() {
+        let (commitment, opening) = PedersenCommitment::commit_random(&Scalar::from(2));
+        assert_eq!(Proof::prove_bit(&commitment, &opening).unwrap_err(), ProofError::InvalidWitness);
+
+        let (_, other_opening) = PedersenCommitment::commit_random(&Scalar::from(1));
+        assert_eq!(Proof::prove_bit(&commitment, &other_opening).unwrap_err(), ProofError::InvalidWitness);
+
+        // a cheating prover claiming either branch with the blinding factor of a commitment to 2
+        for index in 0..2 {
+            let forged = Proof::prove_internal(&commitment, &opening.blinding_factor, &Proof::bit_set(), index, &mut rand::thread_rng());
+            assert_eq!(Proof::verify_bit(&forged, &commitment), Err(ProofError::InvalidResponse));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! A Fiat-Shamir transcript in the style of merlin: prover and verifier append the same labeled
+//! values in the same order, then draw challenges from the running hash.
+//!
+//! Every label and message is length prefixed, and the transcript starts with a domain-separation
+//! label, so no two different sequences of appends hash the same. Drawn challenges are appended
+//! back, so later challenges depend on earlier ones.
+//! https://merlin.cool
+
+use curv::arithmetic::{BitManipulation, Converter};
+use curv::BigInt;
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+
+const DOMAIN_SEPARATOR_LABEL: &[u8] = b"dom-sep";
+
+#[derive(Clone)]
+pub struct Transcript<H: Digest + Clone> {
+    hasher: H,
+}
+
+impl<H: Digest + Clone> Transcript<H> {
+    pub fn new(label: &[u8]) -> Transcript<H> {
+        let mut transcript = Transcript { hasher: H::new() };
+        transcript.append_bytes(DOMAIN_SEPARATOR_LABEL, label);
+        transcript
+    }
+
+    pub fn append_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+        self.hasher.update((label.len() as u64).to_be_bytes());
+        self.hasher.update(label);
+        self.hasher.update((bytes.len() as u64).to_be_bytes());
+        self.hasher.update(bytes);
+    }
+
+    /// compressed encoding. curv encodes the identity per curve (33 zero bytes on secp256k1, the
+    /// point (0, 1) on Ed25519), so it's appended as an empty message instead, which no other
+    /// point encodes to
+    pub fn append_point<E: Curve>(&mut self, label: &[u8], point: &Point<E>) {
+        if point.is_zero() {
+            self.append_bytes(label, &[]);
+        } else {
+            self.append_bytes(label, &point.to_bytes(true));
+        }
+    }
+
+    pub fn append_points<E: Curve>(&mut self, label: &[u8], points: &[Point<E>]) {
+        self.append_u64(label, points.len() as u64);
+        for point in points {
+            self.append_point(label, point);
+        }
+    }
+
+    pub fn append_scalar<E: Curve>(&mut self, label: &[u8], scalar: &Scalar<E>) {
+        self.append_bytes(label, &scalar.to_bytes());
+    }
+
+    pub fn append_u64(&mut self, label: &[u8], value: u64) {
+        self.append_bytes(label, &value.to_be_bytes());
+    }
+
+    /// `len` pseudo random bytes bound to everything appended so far
+    pub fn challenge_bytes(&mut self, label: &[u8], len: usize) -> Vec<u8> {
+        self.append_u64(label, len as u64);
+        let mut output = Vec::with_capacity(len);
+        let mut block = 0u64;
+        while output.len() < len {
+            let digest = self.hasher.clone()
+                .chain(block.to_be_bytes())
+                .finalize();
+            output.extend_from_slice(&digest);
+            block += 1;
+        }
+        output.truncate(len);
+        self.append_bytes(label, &output);
+        output
+    }
+
+    /// Reduces 128 bits more than the group order modulo the order, the bias is negligible
+    pub fn challenge_scalar<E: Curve>(&mut self, label: &[u8]) -> Scalar<E> {
+        let order_bytes = Scalar::<E>::group_order().bit_length().div_ceil(8);
+        let bytes = self.challenge_bytes(label, order_bytes + 16);
+        Scalar::from_bigint(&BigInt::from_bytes(&bytes))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+
+    use crate::cryptology_primitives::proofs::transcript::Transcript;
+
+    #[test]
+    fn test_transcript_challenges() {
+        let point = Point::<Secp256k1>::generator().to_point();
+        let mut prover = Transcript::<sha3::Keccak256>::new(b"test");
+        let mut verifier = Transcript::<sha3::Keccak256>::new(b"test");
+        for transcript in [&mut prover, &mut verifier] {
+            transcript.append_point(b"point", &point);
+            transcript.append_scalar(b"scalar", &Scalar::<Secp256k1>::from(7));
+        }
+        let c1: Scalar<Secp256k1> = prover.challenge_scalar(b"c");
+        assert_eq!(c1, verifier.challenge_scalar(b"c"));
+
+        // the second challenge depends on the first
+        let c2: Scalar<Secp256k1> = prover.challenge_scalar(b"c");
+        assert_ne!(c1, c2);
+
+        // other domain, other order, or a value moved between label and message
+        let mut other_domain = Transcript::<sha3::Keccak256>::new(b"other");
+        other_domain.append_point(b"point", &point);
+        other_domain.append_scalar(b"scalar", &Scalar::<Secp256k1>::from(7));
+        assert_ne!(c1, other_domain.challenge_scalar(b"c"));
+
+        let mut a = Transcript::<sha3::Keccak256>::new(b"test");
+        a.append_bytes(b"ab", b"c");
+        let mut b = Transcript::<sha3::Keccak256>::new(b"test");
+        b.append_bytes(b"a", b"bc");
+        assert_ne!(a.challenge_bytes(b"c", 32), b.challenge_bytes(b"c", 32));
+    }
+
+    #[test]
+    fn test_identity_appends_an_empty_message() {
+        let mut identity = Transcript::<sha3::Keccak256>::new(b"test");
+        identity.append_point(b"point", &Point::<Secp256k1>::zero());
+        let mut empty = Transcript::<sha3::Keccak256>::new(b"test");
+        empty.append_bytes(b"point", &[]);
+        let mut zeros = Transcript::<sha3::Keccak256>::new(b"test");
+        zeros.append_bytes(b"point", &[0u8; 33]);
+
+        let c = identity.challenge_bytes(b"c", 32);
+        assert_eq!(c, empty.challenge_bytes(b"c", 32));
+        assert_ne!(c, zeros.challenge_bytes(b"c", 32));
+    }
+}
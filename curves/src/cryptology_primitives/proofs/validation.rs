@@ -0,0 +1,54 @@
+//! Checks on points received from a prover. `Point<E>` from curv already refuses to deserialize a
+//! point outside the prime order subgroup, but the identity is a valid `Point<E>` and the subgroup
+//! check is only a debug assertion in `Point::from_raw_unchecked`, so verifiers check both explicitly.
+//!
+//! On curves with a cofactor (Ed25519 has 8) a point P + T with T of small order passes the
+//! curve equation, and would let a prover smuggle the small order component through a proof.
+//! The prime order check q * P = 0 rejects those. On prime order curves every point other than
+//! the identity has order q, so the scalar multiplication is skipped there.
+
+use curv::elliptic::curves::{Curve, ECPoint, Point, Ristretto, Secp256k1, Secp256r1};
+
+use crate::cryptology_primitives::errors::ProofError;
+
+/// false for the curves known to have prime order, true for any other curve
+pub fn has_cofactor<E: Curve>() -> bool {
+    !matches!(E::CURVE_NAME, Secp256k1::CURVE_NAME | Secp256r1::CURVE_NAME | Ristretto::CURVE_NAME)
+}
+
+/// point != 0, and q * point = 0 on curves with a cofactor
+pub fn ensure_prime_order<E: Curve>(point: &Point<E>) -> Result<(), ProofError> {
+    if point.is_zero() {
+        return Err(ProofError::MalformedPoint);
+    }
+    if has_cofactor::<E>() && !point.as_raw().check_point_order_equals_group_order() {
+        return Err(ProofError::MalformedPoint);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Bls12_381_1, Ed25519, Point, Ristretto, Scalar, Secp256k1, Secp256r1};
+
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::proofs::validation::{ensure_prime_order, has_cofactor};
+
+    #[test]
+    fn test_has_cofactor() {
+        assert!(!has_cofactor::<Secp256k1>());
+        assert!(!has_cofactor::<Secp256r1>());
+        assert!(!has_cofactor::<Ristretto>());
+        assert!(has_cofactor::<Ed25519>());
+        assert!(has_cofactor::<Bls12_381_1>());
+    }
+
+    #[test]
+    fn test_ensure_prime_order() {
+        assert!(ensure_prime_order(&(Point::<Secp256k1>::generator() * Scalar::random())).is_ok());
+        assert!(ensure_prime_order(&(Point::<Secp256r1>::generator() * Scalar::random())).is_ok());
+        assert!(ensure_prime_order(&(Point::<Ed25519>::generator() * Scalar::random())).is_ok());
+        assert_eq!(ensure_prime_order(&Point::<Secp256k1>::zero()), Err(ProofError::MalformedPoint));
+        assert_eq!(ensure_prime_order(&Point::<Ed25519>::zero()), Err(ProofError::MalformedPoint));
+    }
+}
@@ -0,0 +1,49 @@
+//! Randomness for the `*_with_rng` variants: callers pass any cryptographically secure RNG, e.g. a
+//! hardware one, or a seeded one to reproduce test vectors. The plain variants use `thread_rng`.
+
+use curv::arithmetic::Converter;
+use curv::BigInt;
+use curv::elliptic::curves::{Curve, Scalar};
+use rand::{CryptoRng, RngCore};
+
+/// Reduces 128 bits more than the group order modulo the order, the bias is negligible
+pub fn random_scalar<E: Curve>(rng: &mut (impl RngCore + CryptoRng)) -> Scalar<E> {
+    let order_bytes = Scalar::<E>::group_order().to_bytes().len();
+    let mut bytes = vec![0u8; order_bytes + 16];
+    rng.fill_bytes(&mut bytes);
+    Scalar::from_bigint(&BigInt::from_bytes(&bytes))
+}
+
+/// Uniform in [0, 2^bit_size)
+pub fn sample_bits(bit_size: usize, rng: &mut (impl RngCore + CryptoRng)) -> BigInt {
+    let mut bytes = vec![0u8; bit_size.div_ceil(8)];
+    rng.fill_bytes(&mut bytes);
+    if !bit_size.is_multiple_of(8) {
+        bytes[0] &= (1u8 << (bit_size % 8)) - 1;
+    }
+    BigInt::from_bytes(&bytes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::arithmetic::BitManipulation;
+    use curv::elliptic::curves::Secp256k1;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::cryptology_primitives::rng::{random_scalar, sample_bits};
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let first = random_scalar::<Secp256k1>(&mut rng);
+        let second = random_scalar::<Secp256k1>(&mut rng);
+        assert_ne!(first, second);
+        assert_eq!(first, random_scalar(&mut ChaCha20Rng::seed_from_u64(7)));
+
+        for bit_size in [1, 7, 8, 255] {
+            assert!(sample_bits(bit_size, &mut rng).bit_length() <= bit_size);
+        }
+    }
+}
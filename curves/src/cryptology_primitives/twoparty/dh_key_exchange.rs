@@ -3,7 +3,13 @@
 //! Both parties can compute a joint secret: C= aB = bA = ab*G
 //! which cannot be computed by a man in the middle attacker.
 
+use curv::cryptographic_primitives::hashing::Digest;
 use curv::elliptic::curves::{Point, Curve, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::proofs::sigma_dleq::{DLEqProof, DLEqStatement};
+use crate::cryptology_primitives::rng::random_scalar;
 
 
 #[derive(Debug, Clone)]
@@ -12,12 +18,12 @@ pub struct EcKeyPair<E: Curve> {
     secret_share: Scalar<E>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Party1FirstMessage<E: Curve> {
     pub public_share: Point<E>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Party2FirstMessage<E: Curve> {
     pub public_share: Point<E>,
 }
@@ -25,9 +31,13 @@ pub struct Party2FirstMessage<E: Curve> {
 
 impl<E: Curve> Party1FirstMessage<E> {
     pub fn first() -> (Party1FirstMessage<E>, EcKeyPair<E>) {
+        Self::first_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn first_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> (Party1FirstMessage<E>, EcKeyPair<E>) {
         let generator = Point::<E>::generator();
 
-        let secret_share = Scalar::<E>::random();
+        let secret_share = random_scalar::<E>(rng);
 
         let public_share = &secret_share * generator;
 
@@ -52,9 +62,13 @@ impl<E: Curve> Party1FirstMessage<E> {
 
 impl<E: Curve> Party2FirstMessage<E> {
     pub fn first() -> (Party2FirstMessage<E>, EcKeyPair<E>) {
+        Self::first_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn first_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> (Party2FirstMessage<E>, EcKeyPair<E>) {
         let generator = Point::<E>::generator();
 
-        let secret_share = Scalar::<E>::random();
+        let secret_share = random_scalar::<E>(rng);
 
         let public_share = &secret_share * generator;
 
@@ -81,11 +95,24 @@ pub fn compute_pubkey<E: Curve>(local_share: &EcKeyPair<E>, peer_public_share: &
     &local_share.secret_share * peer_public_share
 }
 
+/// The joint point together with a proof that it was computed with the secret behind the local
+/// public share, i.e. log_G(public_share) = log_peer(joint point)
+pub fn compute_pubkey_with_proof<E: Curve, H: Digest + Clone>(
+    local_share: &EcKeyPair<E>,
+    peer_public_share: &Point<E>,
+) -> (Point<E>, DLEqProof<E, H>) {
+    let statement = DLEqStatement::new(&local_share.secret_share, &Point::<E>::generator().to_point(), peer_public_share);
+    let proof = DLEqProof::prove(&local_share.secret_share, &statement);
+    (statement.pk2, proof)
+}
+
 #[cfg(test)]
 mod tests {
     use curv::elliptic::curves::{Point, Secp256k1};
     use curv::elliptic::curves::{Scalar};
     use crate::cryptology_primitives::twoparty::dh_key_exchange::{compute_pubkey, Party1FirstMessage, Party2FirstMessage};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
 
     #[test]
     fn test_dh_key_exchange_random_secret() {
@@ -130,4 +157,14 @@ mod tests {
         println!("{:?}", uncompressed_pk.as_ref());
         println!("{}", uncompressed_pk.as_ref().len());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_dh_key_exchange_seeded_rng() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let (one_first_msg, one_key_pair) = Party1FirstMessage::<Secp256k1>::first_with_rng(&mut rng);
+        let (two_first_msg, two_key_pair) = Party2FirstMessage::<Secp256k1>::first_with_rng(&mut rng);
+        assert_eq!(hex::encode(one_first_msg.public_share.to_bytes(true)), "0387180939c8998088e610e361f774ff82f86e60128f011dcd95f1f94bb86d8196");
+        assert_eq!(hex::encode(two_first_msg.public_share.to_bytes(true)), "033004ba072b6f913ae74239245bf7a87adb31ca1979c14994ee4c5600e2db8da1");
+        assert_eq!(compute_pubkey(&one_key_pair, &two_first_msg.public_share), compute_pubkey(&two_key_pair, &one_first_msg.public_share));
+    }
+}
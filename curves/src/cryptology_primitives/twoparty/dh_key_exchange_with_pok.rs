@@ -7,59 +7,221 @@
 //! Alice or Bob that can bias the result
 //! https://eprint.iacr.org/2017/552.pdf protocol 3.1 first 3 steps.
 //! <<Fast Secure Two-Party ECDSA Signing!>> Lindell
+//!
+//! Both DLog proofs are bound to the session id and to the index of the proving party, so a proof
+//! can't be replayed into another run of the protocol or reflected back to its sender.
+//!
+//! [Party1] and [Party2] run the same exchange as typestate machines: every step consumes the
+//! previous state, so messages can only be handled in protocol order and a [CommWitness] can only
+//! be opened once.
+//!
+//! ```text
+//! party one                                  party two
+//! Party1::commit             --- com(Q1) --->
+//!                            <--- Q2, PoK --- Party2::receive_commitment
+//! receive_peer_proof         --- Q1, PoK ---> receive_decommitment
+//! receive_peer_confirmation  <--- ack ---
+//! ```
+//!
+//! ```
+//! use curv::elliptic::curves::{Scalar, Secp256k1};
+//! use curves::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{Party1, Party2};
+//! use sha3::Keccak256;
+//!
+//! let party_one = Party1::<Secp256k1>::new(Scalar::random(), b"session");
+//! let party_two = Party2::<Secp256k1>::new(Scalar::random(), b"session");
+//!
+//! let (p1_first_msg, party_one) = party_one.commit::<Keccak256>();
+//! let (p2_first_msg, party_two) = party_two.receive_commitment::<Keccak256>(p1_first_msg);
+//! let (p1_second_msg, party_one) = party_one.receive_peer_proof(&p2_first_msg).unwrap();
+//! let (p2_second_msg, party_two) = party_two.receive_decommitment(&p1_second_msg).unwrap();
+//! let party_one = party_one.receive_peer_confirmation(&p2_second_msg);
+//! assert_eq!(party_one.public_key(), party_two.public_key());
+//! ```
+//!
+//! Party one can't skip the peer's proof and confirm right after committing:
+//!
+//! ```compile_fail,E0599
+//! use curv::elliptic::curves::{Scalar, Secp256k1};
+//! use curves::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{Party1, Party2SecondMessage};
+//! use sha3::Keccak256;
+//!
+//! let (_, party_one) = Party1::<Secp256k1>::new(Scalar::random(), b"session").commit::<Keccak256>();
+//! party_one.receive_peer_confirmation(&Party2SecondMessage {});
+//! ```
+//!
+//! nor open its commitment twice:
+//!
+//! ```compile_fail,E0382
+//! use curv::elliptic::curves::{Scalar, Secp256k1};
+//! use curves::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{Party1, Party2};
+//! use sha3::Keccak256;
+//!
+//! let (p1_first_msg, party_one) = Party1::<Secp256k1>::new(Scalar::random(), b"session").commit::<Keccak256>();
+//! let (p2_first_msg, _) = Party2::<Secp256k1>::new(Scalar::random(), b"session").receive_commitment::<Keccak256>(p1_first_msg);
+//! let _ = party_one.receive_peer_proof(&p2_first_msg);
+//! let _ = party_one.receive_peer_proof(&p2_first_msg);
+//! ```
+//!
+//! and party two can't take a decommitment before it has seen a commitment:
+//!
+//! ```compile_fail,E0599
+//! use curv::elliptic::curves::{Scalar, Secp256k1};
+//! use curves::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{Party1SecondMessage, Party2};
+//! use sha3::Keccak256;
+//!
+//! fn decommitted(party_two: Party2<Secp256k1>, message: &Party1SecondMessage<Secp256k1, Keccak256>) {
+//!     let _ = party_two.receive_decommitment(message);
+//! }
+//! ```
+//!
+//! The states serialize, so a session can be resumed after a restart. A serialized state is single
+//! use: the stored copy has to be deleted or marked consumed once it is resumed, and never restored
+//! a second time. The type system only sees the deserialized value, so restoring a
+//! [Party1AwaitingPeerProof] twice would open the same [CommWitness] against two different peer
+//! proofs, which lets party two pick its share after seeing Q1 and bias the joint key.
 
-use curv::arithmetic::{Converter, Samplable};
+use curv::arithmetic::Converter;
 use curv::BigInt;
 use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
 use curv::cryptographic_primitives::commitments::traits::Commitment;
 use curv::elliptic::curves::{Curve, Point, Scalar};
 use curv::cryptographic_primitives::hashing::Digest;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use crate::cryptology_primitives::errors::ProofError;
+use crate::cryptology_primitives::proofs::context::ProofContext;
 use crate::cryptology_primitives::proofs::sigma_dlog::DLogProof;
+use crate::cryptology_primitives::rng::sample_bits;
 
 const SECURITY_BITS: usize = 256;
 
+const PROOF_LABEL: &[u8] = b"dh_key_exchange_with_pok";
+const PARTY_ONE_INDEX: u16 = 1;
+const PARTY_TWO_INDEX: u16 = 2;
+
+#[derive(Serialize, Deserialize)]
 pub struct EcKeyPair<E: Curve> {
     pub public_share: Point<E>,
     secret_share: Scalar<E>,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
 pub struct CommWitness<E: Curve, H: Digest + Clone> {
     pub pk_commitment_blind_factor: BigInt,
     pub zk_pok_blind_factor: BigInt,
     pub dlog_proof: DLogProof<E, H>,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Party1FirstMessage {
     pub pk_commitment: BigInt,
     pub zk_pok_commitment: BigInt,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
 pub struct Party2FirstMessage<E: Curve, H: Digest + Clone> {
     pub dlog_proof: DLogProof<E, H>,
 }
 
 
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
 pub struct Party1SecondMessage<E: Curve, H: Digest + Clone> {
     pub comm_witness: CommWitness<E, H>,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Party2SecondMessage {}
 
+/// Party one before the exchange
+#[derive(Serialize, Deserialize)]
+pub struct Party1<E: Curve> {
+    session_id: Vec<u8>,
+    secret_share: Scalar<E>,
+}
+
+/// Party one after committing to Q1, waiting for party two's proof
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+pub struct Party1AwaitingPeerProof<E: Curve, H: Digest + Clone> {
+    session_id: Vec<u8>,
+    comm_witness: CommWitness<E, H>,
+    key_pair: EcKeyPair<E>,
+}
+
+/// Party one after checking party two's proof and opening its commitment
+#[derive(Serialize, Deserialize)]
+pub struct Party1Decommitted<E: Curve> {
+    key_pair: EcKeyPair<E>,
+    peer_public_share: Point<E>,
+}
+
+/// Party two before the exchange
+#[derive(Serialize, Deserialize)]
+pub struct Party2<E: Curve> {
+    session_id: Vec<u8>,
+    secret_share: Scalar<E>,
+}
+
+/// Party two after receiving party one's commitment and sending its proof
+#[derive(Serialize, Deserialize)]
+pub struct Party2AwaitingDecommitment<E: Curve> {
+    session_id: Vec<u8>,
+    party_one_first_message: Party1FirstMessage,
+    key_pair: EcKeyPair<E>,
+}
+
+/// A party's key pair and the peer's public share, once the exchange is over
+#[derive(Serialize, Deserialize)]
+pub struct KeyExchangeDone<E: Curve> {
+    pub key_pair: EcKeyPair<E>,
+    pub peer_public_share: Point<E>,
+}
+
+
+impl<E: Curve> EcKeyPair<E> {
+    pub(crate) fn from_secret_share(secret_share: Scalar<E>) -> EcKeyPair<E> {
+        EcKeyPair {
+            public_share: Point::generator() * &secret_share,
+            secret_share,
+        }
+    }
+
+    pub(crate) fn secret_share(&self) -> &Scalar<E> {
+        &self.secret_share
+    }
+}
 
 impl Party1FirstMessage {
-    pub fn create_commitments_with_secret<E: Curve, H: Digest + Clone>(secret_share: Scalar<E>) -> (Party1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+    #[cfg(test)]
+    pub(crate) fn create_commitments_with_secret<E: Curve, H: Digest + Clone>(
+        secret_share: Scalar<E>,
+        session_id: &[u8],
+    ) -> (Party1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+        Self::create_commitments_with_secret_with_rng(secret_share, session_id, &mut rand::thread_rng())
+    }
+
+    pub(crate) fn create_commitments_with_secret_with_rng<E: Curve, H: Digest + Clone>(
+        secret_share: Scalar<E>,
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (Party1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
         let generator = Point::<E>::generator();
         let public_share = generator * &secret_share;
 
-        let dlog_proof = DLogProof::prove(&secret_share);
+        let ctx = ProofContext::new(PROOF_LABEL, session_id, PARTY_ONE_INDEX).to_bytes();
+        let dlog_proof = DLogProof::prove_with_context_with_rng(&secret_share, &ctx, rng);
 
-        let pk_commitment_blind_factor = BigInt::sample(SECURITY_BITS);
+        let pk_commitment_blind_factor = sample_bits(SECURITY_BITS, rng);
         let pk_commitment = HashCommitment::<H>::create_commitment_with_user_defined_randomness(
             &BigInt::from_bytes(&dlog_proof.pk.to_bytes(true)),
             &pk_commitment_blind_factor,
         );
 
-        let zk_pok_blind_factor = BigInt::sample(SECURITY_BITS);
+        let zk_pok_blind_factor = sample_bits(SECURITY_BITS, rng);
         let zk_pok_commitment = HashCommitment::<H>::create_commitment_with_user_defined_randomness(
             &BigInt::from_bytes(&dlog_proof.pk_t_rand_commitment.to_bytes(true)),
             &zk_pok_blind_factor,
@@ -82,10 +244,20 @@ impl Party1FirstMessage {
 }
 
 impl<E: Curve, H: Digest + Clone> Party2FirstMessage<E, H> {
-    pub fn create_dlog_proof_with_secret(secret_share: Scalar<E>) -> (Party2FirstMessage<E, H>, EcKeyPair<E>) {
+    #[cfg(test)]
+    pub(crate) fn create_dlog_proof_with_secret(secret_share: Scalar<E>, session_id: &[u8]) -> (Party2FirstMessage<E, H>, EcKeyPair<E>) {
+        Self::create_dlog_proof_with_secret_with_rng(secret_share, session_id, &mut rand::thread_rng())
+    }
+
+    pub(crate) fn create_dlog_proof_with_secret_with_rng(
+        secret_share: Scalar<E>,
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (Party2FirstMessage<E, H>, EcKeyPair<E>) {
         let generator = Point::<E>::generator();
         let public_share = generator * &secret_share;
-        let dlog_proof = DLogProof::prove(&secret_share);
+        let ctx = ProofContext::new(PROOF_LABEL, session_id, PARTY_TWO_INDEX).to_bytes();
+        let dlog_proof = DLogProof::prove_with_context_with_rng(&secret_share, &ctx, rng);
 
         let ec_key_pair = EcKeyPair {
             public_share,
@@ -101,51 +273,164 @@ impl<E: Curve, H: Digest + Clone> Party2FirstMessage<E, H> {
 }
 
 impl<E: Curve, H: Digest + Clone> Party1SecondMessage<E, H> {
-    pub fn verify_and_decomit(
+    pub(crate) fn verify_and_decomit(
         comm_witness: CommWitness<E, H>,
         proof: &DLogProof<E, H>,
-    ) -> Result<Party1SecondMessage<E, H>, String> {
-        DLogProof::verify(proof)?;
+        session_id: &[u8],
+    ) -> Result<Party1SecondMessage<E, H>, ProofError> {
+        let ctx = ProofContext::new(PROOF_LABEL, session_id, PARTY_TWO_INDEX).to_bytes();
+        DLogProof::verify_with_context(proof, &ctx)?;
         Ok(Party1SecondMessage { comm_witness })
     }
 }
 
 impl Party2SecondMessage {
-    pub fn verify_commitments_and_dlog_proof<E: Curve, H: Digest + Clone>(
+    pub(crate) fn verify_commitments_and_dlog_proof<E: Curve, H: Digest + Clone>(
         party_one_first_message: &Party1FirstMessage,
         party_one_second_message: &Party1SecondMessage<E, H>,
-    ) -> Result<Party2SecondMessage, String> {
+        session_id: &[u8],
+    ) -> Result<Party2SecondMessage, ProofError> {
         let party_one_dlog_proof = &party_one_second_message.comm_witness.dlog_proof;
 
         let party_one_public_share = &party_one_dlog_proof.pk;
         if party_one_public_share.is_zero() {
-            return Err("party one use zero as public share".to_string());
+            return Err(ProofError::IdentityPublicKey);
         }
 
         let party_one_pk_commitment = &party_one_first_message.pk_commitment;
         let party_one_zk_pok_commitment = &party_one_first_message.zk_pok_commitment;
 
 
-        let mut flag = true;
         if party_one_pk_commitment != &HashCommitment::<H>::create_commitment_with_user_defined_randomness(
             &BigInt::from_bytes(&party_one_public_share.to_bytes(true)),
             &party_one_second_message.comm_witness.pk_commitment_blind_factor,
         ) {
-            flag = false
+            return Err(ProofError::HashMismatch);
         };
 
         if party_one_zk_pok_commitment != &HashCommitment::<H>::create_commitment_with_user_defined_randomness(
             &BigInt::from_bytes(&party_one_dlog_proof.pk_t_rand_commitment.to_bytes(true)),
             &party_one_second_message.comm_witness.zk_pok_blind_factor,
         ) {
-            flag = false
+            return Err(ProofError::HashMismatch);
         };
-        assert!(flag);
-        DLogProof::verify(party_one_dlog_proof)?;
+        let ctx = ProofContext::new(PROOF_LABEL, session_id, PARTY_ONE_INDEX).to_bytes();
+        DLogProof::verify_with_context(party_one_dlog_proof, &ctx)?;
         Ok(Party2SecondMessage {})
     }
 }
 
+impl<E: Curve> Party1<E> {
+    pub fn new(secret_share: Scalar<E>, session_id: &[u8]) -> Party1<E> {
+        Party1 {
+            session_id: session_id.to_vec(),
+            secret_share,
+        }
+    }
+
+    /// Commits to Q1 = secret_share * G
+    pub fn commit<H: Digest + Clone>(self) -> (Party1FirstMessage, Party1AwaitingPeerProof<E, H>) {
+        self.commit_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn commit_with_rng<H: Digest + Clone>(
+        self,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (Party1FirstMessage, Party1AwaitingPeerProof<E, H>) {
+        let (first_message, comm_witness, key_pair) =
+            Party1FirstMessage::create_commitments_with_secret_with_rng(self.secret_share, &self.session_id, rng);
+        let state = Party1AwaitingPeerProof {
+            session_id: self.session_id,
+            comm_witness,
+            key_pair,
+        };
+        (first_message, state)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> Party1AwaitingPeerProof<E, H> {
+    /// Checks party two's proof and opens the commitment, the session ends on failure
+    pub fn receive_peer_proof(
+        self,
+        party_two_first_message: &Party2FirstMessage<E, H>,
+    ) -> Result<(Party1SecondMessage<E, H>, Party1Decommitted<E>), ProofError> {
+        let proof = &party_two_first_message.dlog_proof;
+        let second_message = Party1SecondMessage::verify_and_decomit(self.comm_witness, proof, &self.session_id)?;
+        let state = Party1Decommitted {
+            key_pair: self.key_pair,
+            peer_public_share: proof.pk.clone(),
+        };
+        Ok((second_message, state))
+    }
+}
+
+impl<E: Curve> Party1Decommitted<E> {
+    /// Party two accepted the decommitment
+    pub fn receive_peer_confirmation(self, _party_two_second_message: &Party2SecondMessage) -> KeyExchangeDone<E> {
+        KeyExchangeDone {
+            key_pair: self.key_pair,
+            peer_public_share: self.peer_public_share,
+        }
+    }
+}
+
+impl<E: Curve> Party2<E> {
+    pub fn new(secret_share: Scalar<E>, session_id: &[u8]) -> Party2<E> {
+        Party2 {
+            session_id: session_id.to_vec(),
+            secret_share,
+        }
+    }
+
+    /// Keeps party one's commitment and proves knowledge of Q2 = secret_share * G
+    pub fn receive_commitment<H: Digest + Clone>(
+        self,
+        party_one_first_message: Party1FirstMessage,
+    ) -> (Party2FirstMessage<E, H>, Party2AwaitingDecommitment<E>) {
+        self.receive_commitment_with_rng(party_one_first_message, &mut rand::thread_rng())
+    }
+
+    pub fn receive_commitment_with_rng<H: Digest + Clone>(
+        self,
+        party_one_first_message: Party1FirstMessage,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (Party2FirstMessage<E, H>, Party2AwaitingDecommitment<E>) {
+        let (first_message, key_pair) =
+            Party2FirstMessage::create_dlog_proof_with_secret_with_rng(self.secret_share, &self.session_id, rng);
+        let state = Party2AwaitingDecommitment {
+            session_id: self.session_id,
+            party_one_first_message,
+            key_pair,
+        };
+        (first_message, state)
+    }
+}
+
+impl<E: Curve> Party2AwaitingDecommitment<E> {
+    /// Checks party one's decommitment against its commitment and its proof
+    pub fn receive_decommitment<H: Digest + Clone>(
+        self,
+        party_one_second_message: &Party1SecondMessage<E, H>,
+    ) -> Result<(Party2SecondMessage, KeyExchangeDone<E>), ProofError> {
+        let second_message = Party2SecondMessage::verify_commitments_and_dlog_proof(
+            &self.party_one_first_message,
+            party_one_second_message,
+            &self.session_id,
+        )?;
+        let done = KeyExchangeDone {
+            key_pair: self.key_pair,
+            peer_public_share: party_one_second_message.comm_witness.dlog_proof.pk.clone(),
+        };
+        Ok((second_message, done))
+    }
+}
+
+impl<E: Curve> KeyExchangeDone<E> {
+    pub fn public_key(&self) -> Point<E> {
+        compute_pubkey(&self.key_pair, &self.peer_public_share)
+    }
+}
+
 pub fn compute_pubkey<E: Curve>(
     local_share: &EcKeyPair<E>,
     peer_public_share: &Point<E>,
@@ -155,9 +440,18 @@ pub fn compute_pubkey<E: Curve>(
 
 #[cfg(test)]
 mod tests {
+    use curv::arithmetic::Converter;
+    use curv::BigInt;
     use curv::elliptic::curves::{Scalar, Secp256k1};
-    use crate::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{compute_pubkey, Party1FirstMessage, Party1SecondMessage, Party2FirstMessage, Party2SecondMessage};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use crate::cryptology_primitives::errors::ProofError;
+    use crate::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{
+        compute_pubkey, Party1, Party1AwaitingPeerProof, Party1Decommitted, Party1FirstMessage, Party1SecondMessage, Party2,
+        Party2AwaitingDecommitment, Party2FirstMessage, Party2SecondMessage,
+    };
 
+    const SESSION_ID: &[u8] = b"test session";
 
     #[test]
     fn test_ecdh_with_pok() {
@@ -166,13 +460,13 @@ mod tests {
         let p2_secret = Scalar::random();
 
         let (p1_first_msg, comm_witness, p1_key_pair) =
-            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, sha3::Keccak256>(p1_secret);
+            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, sha3::Keccak256>(p1_secret, SESSION_ID);
         let (p2_first_msg, p2_key_pair) =
-            Party2FirstMessage::<Secp256k1, sha3::Keccak256>::create_dlog_proof_with_secret(p2_secret);
+            Party2FirstMessage::<Secp256k1, sha3::Keccak256>::create_dlog_proof_with_secret(p2_secret, SESSION_ID);
 
-        let p1_second_msg = Party1SecondMessage::verify_and_decomit(comm_witness, &p2_first_msg.dlog_proof)
+        let p1_second_msg = Party1SecondMessage::verify_and_decomit(comm_witness, &p2_first_msg.dlog_proof, SESSION_ID)
             .expect("failed to verify and decommit");
-        let _p2_second_msg = Party2SecondMessage::verify_commitments_and_dlog_proof(&p1_first_msg, &p1_second_msg)
+        let _p2_second_msg = Party2SecondMessage::verify_commitments_and_dlog_proof(&p1_first_msg, &p1_second_msg, SESSION_ID)
             .expect("failed to verify commitments and dlog_proof");
 
         assert_eq!(
@@ -180,4 +474,103 @@ mod tests {
             compute_pubkey(&p2_key_pair, &p1_second_msg.comm_witness.dlog_proof.pk)
         )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ecdh_with_pok_wrong_decommitment() {
+        let (p1_first_msg, mut comm_witness, _p1_key_pair) =
+            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, sha3::Keccak256>(Scalar::random(), SESSION_ID);
+        let (p2_first_msg, _p2_key_pair) =
+            Party2FirstMessage::<Secp256k1, sha3::Keccak256>::create_dlog_proof_with_secret(Scalar::random(), SESSION_ID);
+
+        comm_witness.pk_commitment_blind_factor += BigInt::from(1);
+        let p1_second_msg = Party1SecondMessage::verify_and_decomit(comm_witness, &p2_first_msg.dlog_proof, SESSION_ID)
+            .expect("failed to verify and decommit");
+        assert_eq!(
+            Party2SecondMessage::verify_commitments_and_dlog_proof(&p1_first_msg, &p1_second_msg, SESSION_ID).err(),
+            Some(ProofError::HashMismatch)
+        );
+    }
+
+    #[test]
+    fn test_ecdh_with_pok_rejects_replayed_proof() {
+        let (_p1_first_msg, comm_witness, _p1_key_pair) =
+            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, sha3::Keccak256>(Scalar::random(), SESSION_ID);
+        let (p2_first_msg, _p2_key_pair) =
+            Party2FirstMessage::<Secp256k1, sha3::Keccak256>::create_dlog_proof_with_secret(Scalar::random(), b"old session");
+
+        assert_eq!(
+            Party1SecondMessage::verify_and_decomit(comm_witness, &p2_first_msg.dlog_proof, SESSION_ID).err(),
+            Some(ProofError::InvalidResponse)
+        );
+
+        // party one's own proof reflected back as party two's
+        let (_p1_first_msg, comm_witness, _p1_key_pair) =
+            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, sha3::Keccak256>(Scalar::random(), SESSION_ID);
+        let (_, other_witness, _) =
+            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, sha3::Keccak256>(Scalar::random(), SESSION_ID);
+        assert_eq!(
+            Party1SecondMessage::verify_and_decomit(comm_witness, &other_witness.dlog_proof, SESSION_ID).err(),
+            Some(ProofError::InvalidResponse)
+        );
+    }
+
+    #[test]
+    fn test_ecdh_with_pok_seeded_rng() {
+        let run = |seed: u64| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            let (first_msg, comm_witness, _) = Party1FirstMessage::create_commitments_with_secret_with_rng::<Secp256k1, sha3::Keccak256>(
+                Scalar::from(1u64), SESSION_ID, &mut rng,
+            );
+            let (party_two_first_msg, _) = Party2FirstMessage::<Secp256k1, sha3::Keccak256>::create_dlog_proof_with_secret_with_rng(
+                Scalar::from(2u64), SESSION_ID, &mut rng,
+            );
+            (first_msg, comm_witness, party_two_first_msg)
+        };
+        let (first_msg, comm_witness, party_two_first_msg) = run(7);
+        assert_eq!(hex::encode(first_msg.pk_commitment.to_bytes()), "6f2faa7310a924dae235fe477eb18e3c22eb6a3e4fb985f9735efd1d1f8f01e6");
+
+        let (same_first_msg, same_comm_witness, same_party_two_first_msg) = run(7);
+        assert_eq!(same_first_msg.zk_pok_commitment, first_msg.zk_pok_commitment);
+        assert_eq!(same_comm_witness.dlog_proof.challenge_response, comm_witness.dlog_proof.challenge_response);
+        assert_eq!(same_party_two_first_msg.dlog_proof.challenge_response, party_two_first_msg.dlog_proof.challenge_response);
+        assert_ne!(run(8).0.pk_commitment, first_msg.pk_commitment);
+
+        let party_one_second_msg = Party1SecondMessage::verify_and_decomit(comm_witness, &party_two_first_msg.dlog_proof, SESSION_ID).unwrap();
+        assert!(Party2SecondMessage::verify_commitments_and_dlog_proof(&first_msg, &party_one_second_msg, SESSION_ID).is_ok());
+    }
+
+    #[test]
+    fn test_state_machines_resume_after_restart() {
+        let (p1_first_msg, p1_state) = Party1::<Secp256k1>::new(Scalar::random(), SESSION_ID).commit::<sha3::Keccak256>();
+        // party two only gets to prove once it holds party one's commitment
+        let (p2_first_msg, p2_state) = Party2::<Secp256k1>::new(Scalar::random(), SESSION_ID).receive_commitment::<sha3::Keccak256>(p1_first_msg);
+
+        let p1_state: Party1AwaitingPeerProof<Secp256k1, sha3::Keccak256> =
+            serde_json::from_str(&serde_json::to_string(&p1_state).unwrap()).unwrap();
+        let (p1_second_msg, p1_state) = p1_state.receive_peer_proof(&p2_first_msg).unwrap();
+
+        let p2_state: Party2AwaitingDecommitment<Secp256k1> = serde_json::from_str(&serde_json::to_string(&p2_state).unwrap()).unwrap();
+        let (p2_second_msg, p2_done) = p2_state.receive_decommitment(&p1_second_msg).unwrap();
+
+        let p1_state: Party1Decommitted<Secp256k1> = serde_json::from_str(&serde_json::to_string(&p1_state).unwrap()).unwrap();
+        let p1_done = p1_state.receive_peer_confirmation(&p2_second_msg);
+
+        assert_eq!(p1_done.public_key(), p2_done.public_key());
+        assert_eq!(p1_done.peer_public_share, p2_done.key_pair.public_share);
+        assert_eq!(p2_done.peer_public_share, p1_done.key_pair.public_share);
+    }
+
+    #[test]
+    fn test_state_machines_reject_wrong_session() {
+        let (p1_first_msg, p1_state) = Party1::<Secp256k1>::new(Scalar::random(), SESSION_ID).commit::<sha3::Keccak256>();
+        let (other_first_msg, _) = Party1::<Secp256k1>::new(Scalar::random(), SESSION_ID).commit::<sha3::Keccak256>();
+        let (p2_first_msg, _) = Party2::<Secp256k1>::new(Scalar::random(), b"old session").receive_commitment::<sha3::Keccak256>(p1_first_msg);
+        assert_eq!(p1_state.receive_peer_proof(&p2_first_msg).err(), Some(ProofError::InvalidResponse));
+
+        // a decommitment that doesn't open the commitment party two holds
+        let (_, p1_state) = Party1::<Secp256k1>::new(Scalar::random(), SESSION_ID).commit::<sha3::Keccak256>();
+        let (p2_first_msg, p2_state) = Party2::<Secp256k1>::new(Scalar::random(), SESSION_ID).receive_commitment::<sha3::Keccak256>(other_first_msg);
+        let (p1_second_msg, _) = p1_state.receive_peer_proof(&p2_first_msg).unwrap();
+        assert_eq!(p2_state.receive_decommitment(&p1_second_msg).err(), Some(ProofError::HashMismatch));
+    }
+}
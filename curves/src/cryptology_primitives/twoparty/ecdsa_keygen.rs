@@ -0,0 +1,426 @@
+//! Key generation of two-party ECDSA, https://eprint.iacr.org/2017/552.pdf protocol 3.1
+//! <<Fast Secure Two-Party ECDSA Signing>> Lindell
+//!
+//! The parties run [dh_key_exchange_with_pok](super::dh_key_exchange_with_pok) on their shares
+//! x1, x2, the joint public key is Q = x1 * x2 * G. Party one then sends a Paillier encryption
+//! c_key = Enc(x1) and proves that
+//! - its Paillier key is well formed, [CorrectKeyProof]
+//! - x1 < q / 3, so no Paillier computation during signing wraps around N, [PaillierRangeProof]
+//! - c_key really encrypts the discrete log of its public share, [pdl](crate::cryptology_primitives::proofs::pdl)
+//!
+//! ```text
+//! party one                                               party two
+//! KeyGenParty1FirstMessage::create    --- com(Q1) --->
+//!                                     <--- Q2, PoK ---    KeyGenParty2FirstMessage::create
+//! KeyGenParty1SecondMessage::create   --- Q1, PoK, ek, c_key, proofs --->
+//!                                     <--- c', com(a, b) ---  KeyGenParty2SecondMessage::create
+//! KeyGenParty1ThirdMessage::create    --- com(Q^) --->
+//!                                     <--- a, b ---       Party2KeyGenState::third_message
+//! KeyGenParty1FourthMessage::create   --- Q^ --->         Party2KeyShare::create
+//! ```
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::traits::{BitManipulation, Samplable};
+use crate::cryptology_primitives::encryption::paillier::{Ciphertext, DecryptionKey, EncryptionKey, Paillier, Randomness};
+use crate::cryptology_primitives::errors::ProtocolError;
+use crate::cryptology_primitives::proofs::context::ProofContext;
+use crate::cryptology_primitives::proofs::paillier_correct_key::CorrectKeyProof;
+use crate::cryptology_primitives::proofs::paillier_range::PaillierRangeProof;
+use crate::cryptology_primitives::proofs::pdl::{
+    PDLProverFirstMessage, PDLProverSecondMessage, PDLProverState, PDLStatement, PDLVerifierFirstMessage,
+    PDLVerifierSecondMessage, PDLVerifierState,
+};
+use crate::cryptology_primitives::rng::random_scalar;
+use crate::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{
+    compute_pubkey, CommWitness, EcKeyPair, Party1FirstMessage, Party1SecondMessage, Party2FirstMessage,
+    Party2SecondMessage,
+};
+
+const KEYGEN_LABEL: &[u8] = b"two_party_ecdsa_keygen";
+const PARTY_ONE_INDEX: u16 = 1;
+
+/// Bit size of party one's Paillier modulus, party two rejects smaller ones. Signing encrypts
+/// values up to about q^3, N must be far larger.
+pub const PAILLIER_KEY_SIZE: usize = 2048;
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyGenParty1FirstMessage {
+    pub ecdh_first_message: Party1FirstMessage,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+pub struct KeyGenParty2FirstMessage<E: Curve, H: Digest + Clone> {
+    pub ecdh_first_message: Party2FirstMessage<E, H>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+pub struct KeyGenParty1SecondMessage<E: Curve, H: Digest + Clone> {
+    pub ecdh_second_message: Party1SecondMessage<E, H>,
+    pub ek: EncryptionKey,
+    pub c_key: Ciphertext,
+    pub correct_key_proof: CorrectKeyProof<H>,
+    pub range_proof: PaillierRangeProof<H>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyGenParty2SecondMessage {
+    pub pdl_first_message: PDLVerifierFirstMessage,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyGenParty1ThirdMessage {
+    pub pdl_first_message: PDLProverFirstMessage,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyGenParty2ThirdMessage<E: Curve> {
+    pub pdl_second_message: PDLVerifierSecondMessage<E>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct KeyGenParty1FourthMessage<E: Curve> {
+    pub pdl_second_message: PDLProverSecondMessage<E>,
+}
+
+/// Party one's share: x1 and the Paillier key c_key is encrypted under
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Party1KeyShare<E: Curve> {
+    pub public_key: Point<E>,
+    pub(crate) x1: Scalar<E>,
+    pub(crate) dk: DecryptionKey,
+    pub(crate) ek: EncryptionKey,
+    pub(crate) c_key: Ciphertext,
+}
+
+/// Party two's share: x2 and c_key = Enc(x1)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Party2KeyShare<E: Curve> {
+    pub public_key: Point<E>,
+    pub(crate) x2: Scalar<E>,
+    pub(crate) ek: EncryptionKey,
+    pub(crate) c_key: Ciphertext,
+}
+
+/// Party two between accepting party one's proofs and the end of the PDL proof
+pub struct Party2KeyGenState<E: Curve, H: Digest + Clone> {
+    key_share: Party2KeyShare<E>,
+    pdl_state: PDLVerifierState<E, H>,
+}
+
+impl KeyGenParty1FirstMessage {
+    /// Samples x1 below q / 3 and commits to Q1 = x1 * G
+    pub fn create<E: Curve, H: Digest + Clone>(session_id: &[u8]) -> (KeyGenParty1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+        Self::create_with_rng(session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (KeyGenParty1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+        let x1 = NativeBigInt::sample_below_with_rng(&x1_range::<E>(), rng).to_scalar();
+        let (ecdh_first_message, comm_witness, key_pair) =
+            Party1FirstMessage::create_commitments_with_secret_with_rng(x1, &ecdh_session_id(session_id), rng);
+        (KeyGenParty1FirstMessage { ecdh_first_message }, comm_witness, key_pair)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> KeyGenParty2FirstMessage<E, H> {
+    pub fn create(session_id: &[u8]) -> (KeyGenParty2FirstMessage<E, H>, EcKeyPair<E>) {
+        Self::create_with_rng(session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng(session_id: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> (KeyGenParty2FirstMessage<E, H>, EcKeyPair<E>) {
+        let x2 = random_scalar(rng);
+        let (ecdh_first_message, key_pair) =
+            Party2FirstMessage::create_dlog_proof_with_secret_with_rng(x2, &ecdh_session_id(session_id), rng);
+        (KeyGenParty2FirstMessage { ecdh_first_message }, key_pair)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> KeyGenParty1SecondMessage<E, H> {
+    /// Checks party two's proof, decommits Q1 and sends c_key = Enc(x1) under a fresh Paillier
+    /// key with its proofs. Party one's key share is complete at this point.
+    pub fn create(
+        comm_witness: CommWitness<E, H>,
+        key_pair: &EcKeyPair<E>,
+        party_two_first_message: &KeyGenParty2FirstMessage<E, H>,
+        session_id: &[u8],
+    ) -> Result<(KeyGenParty1SecondMessage<E, H>, Party1KeyShare<E>), ProtocolError> {
+        Self::create_with_rng(comm_witness, key_pair, party_two_first_message, session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng(
+        comm_witness: CommWitness<E, H>,
+        key_pair: &EcKeyPair<E>,
+        party_two_first_message: &KeyGenParty2FirstMessage<E, H>,
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<(KeyGenParty1SecondMessage<E, H>, Party1KeyShare<E>), ProtocolError> {
+        let party_two_proof = &party_two_first_message.ecdh_first_message.dlog_proof;
+        let ecdh_second_message = Party1SecondMessage::verify_and_decomit(comm_witness, party_two_proof, &ecdh_session_id(session_id))?;
+
+        let (ek, dk) = Paillier::keypair_with_rng(PAILLIER_KEY_SIZE, rng);
+        let x1 = NativeBigInt::from_scalar(key_pair.secret_share());
+        let randomness = Randomness::sample_with_rng(&ek, rng);
+        let c_key = Paillier::encrypt_with_randomness(&ek, &x1, &randomness);
+
+        let ctx = ProofContext::new(KEYGEN_LABEL, session_id, PARTY_ONE_INDEX).to_bytes();
+        let correct_key_proof = CorrectKeyProof::prove(&dk, &ctx);
+        let range_proof = PaillierRangeProof::prove_with_rng(&ek, &c_key, &x1_range::<E>(), &x1, &randomness, &ctx, rng)?;
+
+        let key_share = Party1KeyShare {
+            public_key: compute_pubkey(key_pair, &party_two_proof.pk),
+            x1: key_pair.secret_share().clone(),
+            dk,
+            ek: ek.clone(),
+            c_key: c_key.clone(),
+        };
+        let message = KeyGenParty1SecondMessage {
+            ecdh_second_message,
+            ek,
+            c_key,
+            correct_key_proof,
+            range_proof,
+        };
+        Ok((message, key_share))
+    }
+}
+
+impl KeyGenParty2SecondMessage {
+    /// Checks party one's decommitment and proofs, then starts the PDL proof of c_key
+    pub fn create<E: Curve, H: Digest + Clone>(
+        key_pair: &EcKeyPair<E>,
+        party_one_first_message: &KeyGenParty1FirstMessage,
+        party_one_second_message: &KeyGenParty1SecondMessage<E, H>,
+        session_id: &[u8],
+    ) -> Result<(KeyGenParty2SecondMessage, Party2KeyGenState<E, H>), ProtocolError> {
+        Self::create_with_rng(key_pair, party_one_first_message, party_one_second_message, session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        key_pair: &EcKeyPair<E>,
+        party_one_first_message: &KeyGenParty1FirstMessage,
+        party_one_second_message: &KeyGenParty1SecondMessage<E, H>,
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<(KeyGenParty2SecondMessage, Party2KeyGenState<E, H>), ProtocolError> {
+        let KeyGenParty1SecondMessage { ecdh_second_message, ek, c_key, correct_key_proof, range_proof } = party_one_second_message;
+        Party2SecondMessage::verify_commitments_and_dlog_proof(
+            &party_one_first_message.ecdh_first_message,
+            ecdh_second_message,
+            &ecdh_session_id(session_id),
+        )?;
+
+        if ek.n.bit_length() < PAILLIER_KEY_SIZE {
+            return Err(ProtocolError::SmallPaillierModulus);
+        }
+        let ctx = ProofContext::new(KEYGEN_LABEL, session_id, PARTY_ONE_INDEX).to_bytes();
+        correct_key_proof.verify(ek, &ctx)?;
+        range_proof.verify(ek, c_key, &x1_range::<E>(), &ctx)?;
+
+        let party_one_public_share = &ecdh_second_message.comm_witness.dlog_proof.pk;
+        let statement = PDLStatement {
+            ek: ek.clone(),
+            ciphertext: c_key.clone(),
+            q1: party_one_public_share.clone(),
+        };
+        let (pdl_first_message, pdl_state) = PDLVerifierFirstMessage::create_with_rng(&statement, rng);
+
+        let key_share = Party2KeyShare {
+            public_key: compute_pubkey(key_pair, party_one_public_share),
+            x2: key_pair.secret_share().clone(),
+            ek: ek.clone(),
+            c_key: c_key.clone(),
+        };
+        Ok((KeyGenParty2SecondMessage { pdl_first_message }, Party2KeyGenState { key_share, pdl_state }))
+    }
+}
+
+impl KeyGenParty1ThirdMessage {
+    pub fn create<E: Curve, H: Digest + Clone>(
+        key_share: &Party1KeyShare<E>,
+        party_two_second_message: &KeyGenParty2SecondMessage,
+    ) -> (KeyGenParty1ThirdMessage, PDLProverState<E, H>) {
+        Self::create_with_rng(key_share, party_two_second_message, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        key_share: &Party1KeyShare<E>,
+        party_two_second_message: &KeyGenParty2SecondMessage,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (KeyGenParty1ThirdMessage, PDLProverState<E, H>) {
+        let (pdl_first_message, pdl_state) =
+            PDLProverFirstMessage::create_with_rng(&key_share.dk, &party_two_second_message.pdl_first_message, rng);
+        (KeyGenParty1ThirdMessage { pdl_first_message }, pdl_state)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> Party2KeyGenState<E, H> {
+    /// Opens the PDL challenge once party one committed to its answer
+    pub fn third_message(&self) -> KeyGenParty2ThirdMessage<E> {
+        KeyGenParty2ThirdMessage {
+            pdl_second_message: self.pdl_state.second_message(),
+        }
+    }
+}
+
+impl<E: Curve> KeyGenParty1FourthMessage<E> {
+    pub fn create<H: Digest + Clone>(
+        key_share: &Party1KeyShare<E>,
+        pdl_state: PDLProverState<E, H>,
+        party_two_third_message: &KeyGenParty2ThirdMessage<E>,
+    ) -> Result<KeyGenParty1FourthMessage<E>, ProtocolError> {
+        let pdl_second_message = PDLProverSecondMessage::create(pdl_state, &key_share.x1, &party_two_third_message.pdl_second_message)?;
+        Ok(KeyGenParty1FourthMessage { pdl_second_message })
+    }
+}
+
+impl<E: Curve> Party2KeyShare<E> {
+    /// Party two's share, once party one's answer to the PDL challenge checks out
+    pub fn create<H: Digest + Clone>(
+        state: Party2KeyGenState<E, H>,
+        party_one_third_message: &KeyGenParty1ThirdMessage,
+        party_one_fourth_message: &KeyGenParty1FourthMessage<E>,
+    ) -> Result<Party2KeyShare<E>, ProtocolError> {
+        state.pdl_state.verify(&party_one_third_message.pdl_first_message, &party_one_fourth_message.pdl_second_message)?;
+        Ok(state.key_share)
+    }
+}
+
+/// x1 is proven to be below q / 3
+fn x1_range<E: Curve>() -> NativeBigInt {
+    NativeBigInt::group_order::<E>() / NativeBigInt::from(3u32)
+}
+
+/// The key exchange of keygen and of signing run with distinct session ids, so no proof of one
+/// is accepted in the other
+fn ecdh_session_id(session_id: &[u8]) -> Vec<u8> {
+    ProofContext::new(KEYGEN_LABEL, session_id, 0).to_bytes()
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::OnceLock;
+
+    use curv::cryptographic_primitives::hashing::Digest;
+    use curv::elliptic::curves::{Curve, Point, Scalar, Secp256k1};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use sha3::Keccak256;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::cryptology_primitives::encryption::paillier::Paillier;
+    use crate::cryptology_primitives::errors::{ProofError, ProtocolError};
+    use crate::cryptology_primitives::twoparty::ecdsa_keygen::{
+        KeyGenParty1FirstMessage, KeyGenParty1FourthMessage, KeyGenParty1SecondMessage, KeyGenParty1ThirdMessage,
+        KeyGenParty2FirstMessage, KeyGenParty2SecondMessage, Party1KeyShare, Party2KeyShare,
+    };
+
+    const SESSION_ID: &[u8] = b"test session";
+
+    /// Both shares of a fresh key
+    fn keygen<E: Curve, H: Digest + Clone>() -> (Party1KeyShare<E>, Party2KeyShare<E>) {
+        let (p1_first_msg, comm_witness, p1_key_pair) = KeyGenParty1FirstMessage::create::<E, H>(SESSION_ID);
+        let (p2_first_msg, p2_key_pair) = KeyGenParty2FirstMessage::<E, H>::create(SESSION_ID);
+
+        let (p1_second_msg, p1_key_share) = KeyGenParty1SecondMessage::create(comm_witness, &p1_key_pair, &p2_first_msg, SESSION_ID).unwrap();
+        let (p2_second_msg, p2_state) = KeyGenParty2SecondMessage::create(&p2_key_pair, &p1_first_msg, &p1_second_msg, SESSION_ID).unwrap();
+
+        let (p1_third_msg, p1_pdl_state) = KeyGenParty1ThirdMessage::create::<E, H>(&p1_key_share, &p2_second_msg);
+        let p2_third_msg = p2_state.third_message();
+        let p1_fourth_msg = KeyGenParty1FourthMessage::create(&p1_key_share, p1_pdl_state, &p2_third_msg).unwrap();
+        let p2_key_share = Party2KeyShare::create(p2_state, &p1_third_msg, &p1_fourth_msg).unwrap();
+        (p1_key_share, p2_key_share)
+    }
+
+    /// Key generation takes a while in debug builds, the tests of this module and of signing
+    /// share one key
+    pub(crate) fn key_shares() -> &'static (Party1KeyShare<Secp256k1>, Party2KeyShare<Secp256k1>) {
+        static KEY_SHARES: OnceLock<(Party1KeyShare<Secp256k1>, Party2KeyShare<Secp256k1>)> = OnceLock::new();
+        KEY_SHARES.get_or_init(keygen::<Secp256k1, Keccak256>)
+    }
+
+    #[test]
+    fn test_keygen() {
+        let (p1_key_share, p2_key_share) = key_shares();
+        assert_eq!(p1_key_share.public_key, p2_key_share.public_key);
+        assert_eq!(p1_key_share.public_key, Point::generator() * (&p1_key_share.x1 * &p2_key_share.x2));
+        assert_eq!(p1_key_share.ek, p2_key_share.ek);
+        assert_eq!(Paillier::decrypt(&p1_key_share.dk, &p2_key_share.c_key), NativeBigInt::from_scalar(&p1_key_share.x1));
+
+        let p1_json = serde_json::to_string(p1_key_share).unwrap();
+        let decoded: Party1KeyShare<Secp256k1> = serde_json::from_str(&p1_json).unwrap();
+        assert_eq!(decoded.x1, p1_key_share.x1);
+    }
+
+    #[test]
+    fn test_keygen_with_seeded_rng() {
+        // the Paillier key is drawn from the caller's rng too
+        let party_one_key_share = |seed| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            let (_, comm_witness, p1_key_pair) = KeyGenParty1FirstMessage::create_with_rng::<Secp256k1, Keccak256>(SESSION_ID, &mut rng);
+            let (p2_first_msg, _) = KeyGenParty2FirstMessage::<Secp256k1, Keccak256>::create_with_rng(SESSION_ID, &mut rng);
+            KeyGenParty1SecondMessage::create_with_rng(comm_witness, &p1_key_pair, &p2_first_msg, SESSION_ID, &mut rng).unwrap().1
+        };
+        let key_share = party_one_key_share(7);
+        let again = party_one_key_share(7);
+        assert_eq!(key_share.ek, again.ek);
+        assert_eq!(key_share.x1, again.x1);
+    }
+
+    #[test]
+    fn test_keygen_rejects_malformed_messages() {
+        let (p1_first_msg, comm_witness, p1_key_pair) = KeyGenParty1FirstMessage::create::<Secp256k1, Keccak256>(SESSION_ID);
+        let (p2_first_msg, p2_key_pair) = KeyGenParty2FirstMessage::<Secp256k1, Keccak256>::create(SESSION_ID);
+        let (mut p1_second_msg, p1_key_share) =
+            KeyGenParty1SecondMessage::create(comm_witness, &p1_key_pair, &p2_first_msg, SESSION_ID).unwrap();
+
+        // proofs made for another session
+        assert_eq!(
+            KeyGenParty2SecondMessage::create(&p2_key_pair, &p1_first_msg, &p1_second_msg, b"other session").err(),
+            Some(ProtocolError::Proof(ProofError::InvalidResponse))
+        );
+
+        // c_key swapped for an encryption of another small value: the range proof catches it
+        let c_key = p1_second_msg.c_key.clone();
+        p1_second_msg.c_key = Paillier::encrypt(&p1_second_msg.ek, &NativeBigInt::from(7u32));
+        assert_eq!(
+            KeyGenParty2SecondMessage::create(&p2_key_pair, &p1_first_msg, &p1_second_msg, SESSION_ID).err(),
+            Some(ProtocolError::Proof(ProofError::InvalidResponse))
+        );
+        p1_second_msg.c_key = c_key;
+
+        // a Paillier modulus too short to hold the signing computations
+        let ek = p1_second_msg.ek.clone();
+        p1_second_msg.ek = Paillier::keypair(1024).0;
+        assert_eq!(
+            KeyGenParty2SecondMessage::create(&p2_key_pair, &p1_first_msg, &p1_second_msg, SESSION_ID).err(),
+            Some(ProtocolError::SmallPaillierModulus)
+        );
+        p1_second_msg.ek = ek;
+
+        // party one can't answer the PDL challenge for another x1, nor open another Q^
+        let (p2_second_msg, p2_state) = KeyGenParty2SecondMessage::create(&p2_key_pair, &p1_first_msg, &p1_second_msg, SESSION_ID).unwrap();
+        let mut cheating_key_share = p1_key_share.clone();
+        cheating_key_share.x1 = &cheating_key_share.x1 + Scalar::from(1);
+        let (p1_third_msg, p1_pdl_state) = KeyGenParty1ThirdMessage::create::<Secp256k1, Keccak256>(&cheating_key_share, &p2_second_msg);
+        assert_eq!(
+            KeyGenParty1FourthMessage::create(&cheating_key_share, p1_pdl_state.clone(), &p2_state.third_message()).err(),
+            Some(ProtocolError::Proof(ProofError::InvalidResponse))
+        );
+        let mut p1_fourth_msg = KeyGenParty1FourthMessage::create(&p1_key_share, p1_pdl_state, &p2_state.third_message()).unwrap();
+        p1_fourth_msg.pdl_second_message.q_hat = Point::generator() * &cheating_key_share.x1;
+        assert_eq!(
+            Party2KeyShare::create(p2_state, &p1_third_msg, &p1_fourth_msg).err(),
+            Some(ProtocolError::Proof(ProofError::HashMismatch))
+        );
+    }
+}
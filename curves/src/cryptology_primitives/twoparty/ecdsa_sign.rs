@@ -0,0 +1,354 @@
+//! Signing with the shares of [ecdsa_keygen](super::ecdsa_keygen),
+//! https://eprint.iacr.org/2017/552.pdf protocol 4.1 <<Fast Secure Two-Party ECDSA Signing>> Lindell
+//!
+//! The nonce k = k1 * k2 is exchanged like the key, with [dh_key_exchange_with_pok](super::dh_key_exchange_with_pok),
+//! so both parties learn R = k1 * k2 * G and r = R.x mod q. Party two then computes under
+//! Paillier, with a random rho in Z_q^2 to hide its part,
+//! c3 = Enc(rho * q + k2^-1 * m) * c_key^(k2^-1 * r * x2) = Enc(rho * q + k2^-1 * (m + r * x1 * x2))
+//! and party one finishes with s = k1^-1 * Dec(c3) mod q. Party one verifies the signature before
+//! returning it, a malicious party two can make signing fail but learns nothing from it.
+//!
+//! ```text
+//! party one                                            party two
+//! SignParty1FirstMessage::create     --- com(R1) --->
+//!                                    <--- R2, PoK ---   SignParty2FirstMessage::create
+//! SignParty1SecondMessage::create    --- R1, PoK --->
+//!                                    <--- c3 ---        SignParty2SecondMessage::create
+//! Signature::compute
+//! ```
+//!
+//! Signatures are low-s normalized and carry the recovery id, as Bitcoin and Ethereum expect.
+
+use curv::arithmetic::BitManipulation;
+use curv::BigInt;
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::traits::Samplable;
+use crate::cryptology_primitives::encryption::paillier::{Ciphertext, Paillier};
+use crate::cryptology_primitives::errors::{ProofError, ProtocolError};
+use crate::cryptology_primitives::proofs::context::ProofContext;
+use crate::cryptology_primitives::rng::random_scalar;
+use crate::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{
+    compute_pubkey, CommWitness, EcKeyPair, Party1FirstMessage, Party1SecondMessage, Party2FirstMessage,
+    Party2SecondMessage,
+};
+use crate::cryptology_primitives::twoparty::ecdsa_keygen::{Party1KeyShare, Party2KeyShare};
+
+const SIGN_LABEL: &[u8] = b"two_party_ecdsa_sign";
+
+#[derive(Serialize, Deserialize)]
+pub struct SignParty1FirstMessage {
+    pub ecdh_first_message: Party1FirstMessage,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+pub struct SignParty2FirstMessage<E: Curve, H: Digest + Clone> {
+    pub ecdh_first_message: Party2FirstMessage<E, H>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+pub struct SignParty1SecondMessage<E: Curve, H: Digest + Clone> {
+    pub ecdh_second_message: Party1SecondMessage<E, H>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SignParty2SecondMessage {
+    pub c3: Ciphertext,
+}
+
+/// (r, s) with s <= q / 2, and the recovery id: bit 0 is the parity of R.y, bit 1 is set if
+/// R.x was reduced modulo q
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Signature<E: Curve> {
+    pub r: Scalar<E>,
+    pub s: Scalar<E>,
+    pub recovery_id: u8,
+}
+
+impl SignParty1FirstMessage {
+    /// Samples the nonce share k1 and commits to R1 = k1 * G
+    pub fn create<E: Curve, H: Digest + Clone>(session_id: &[u8]) -> (SignParty1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+        Self::create_with_rng(session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (SignParty1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+        let k1 = random_scalar(rng);
+        let (ecdh_first_message, comm_witness, nonce_pair) =
+            Party1FirstMessage::create_commitments_with_secret_with_rng(k1, &ecdh_session_id(session_id), rng);
+        (SignParty1FirstMessage { ecdh_first_message }, comm_witness, nonce_pair)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> SignParty2FirstMessage<E, H> {
+    /// Samples the nonce share k2, sends R2 = k2 * G with its proof
+    pub fn create(session_id: &[u8]) -> (SignParty2FirstMessage<E, H>, EcKeyPair<E>) {
+        Self::create_with_rng(session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng(session_id: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> (SignParty2FirstMessage<E, H>, EcKeyPair<E>) {
+        let k2 = random_scalar(rng);
+        let (ecdh_first_message, nonce_pair) =
+            Party2FirstMessage::create_dlog_proof_with_secret_with_rng(k2, &ecdh_session_id(session_id), rng);
+        (SignParty2FirstMessage { ecdh_first_message }, nonce_pair)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> SignParty1SecondMessage<E, H> {
+    /// Checks party two's proof of k2 and decommits R1
+    pub fn create(
+        comm_witness: CommWitness<E, H>,
+        party_two_first_message: &SignParty2FirstMessage<E, H>,
+        session_id: &[u8],
+    ) -> Result<SignParty1SecondMessage<E, H>, ProtocolError> {
+        let ecdh_second_message = Party1SecondMessage::verify_and_decomit(
+            comm_witness,
+            &party_two_first_message.ecdh_first_message.dlog_proof,
+            &ecdh_session_id(session_id),
+        )?;
+        Ok(SignParty1SecondMessage { ecdh_second_message })
+    }
+}
+
+impl SignParty2SecondMessage {
+    /// Checks party one's decommitment of R1 and computes c3 for `message_hash`, the hash of the
+    /// message as an integer, reduced modulo q like ECDSA does
+    pub fn create<E: Curve, H: Digest + Clone>(
+        key_share: &Party2KeyShare<E>,
+        nonce_pair: &EcKeyPair<E>,
+        party_one_first_message: &SignParty1FirstMessage,
+        party_one_second_message: &SignParty1SecondMessage<E, H>,
+        message_hash: &BigInt,
+        session_id: &[u8],
+    ) -> Result<SignParty2SecondMessage, ProtocolError> {
+        Self::create_with_rng(
+            key_share,
+            nonce_pair,
+            party_one_first_message,
+            party_one_second_message,
+            message_hash,
+            session_id,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        key_share: &Party2KeyShare<E>,
+        nonce_pair: &EcKeyPair<E>,
+        party_one_first_message: &SignParty1FirstMessage,
+        party_one_second_message: &SignParty1SecondMessage<E, H>,
+        message_hash: &BigInt,
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<SignParty2SecondMessage, ProtocolError> {
+        let ecdh_second_message = &party_one_second_message.ecdh_second_message;
+        Party2SecondMessage::verify_commitments_and_dlog_proof(
+            &party_one_first_message.ecdh_first_message,
+            ecdh_second_message,
+            &ecdh_session_id(session_id),
+        )?;
+        let r_point = compute_pubkey(nonce_pair, &ecdh_second_message.comm_witness.dlog_proof.pk);
+        let r = x_coordinate_mod_q(&r_point)?;
+
+        let k2_inv = nonce_pair.secret_share().invert().ok_or(ProofError::IdentityPublicKey)?;
+        let m = Scalar::<E>::from_bigint(message_hash);
+        let q = NativeBigInt::group_order::<E>();
+        let rho = NativeBigInt::sample_below_with_rng(&(&q * &q), rng);
+
+        let masked = rho * &q + NativeBigInt::from_scalar(&(&k2_inv * &m));
+        let c1 = Paillier::encrypt_with_rng(&key_share.ek, &masked, rng);
+        let v = NativeBigInt::from_scalar(&(&k2_inv * &r * &key_share.x2));
+        let c2 = Paillier::mul(&key_share.ek, &key_share.c_key, &v);
+        Ok(SignParty2SecondMessage {
+            c3: Paillier::add(&key_share.ek, &c1, &c2),
+        })
+    }
+}
+
+impl<E: Curve> Signature<E> {
+    /// Party one's final step: s = k1^-1 * Dec(c3) mod q, normalized to low-s. Fails with
+    /// [ProtocolError::InvalidSignature] if party two's c3 doesn't give a valid signature.
+    pub fn compute<H: Digest + Clone>(
+        key_share: &Party1KeyShare<E>,
+        nonce_pair: &EcKeyPair<E>,
+        party_two_first_message: &SignParty2FirstMessage<E, H>,
+        party_two_second_message: &SignParty2SecondMessage,
+        message_hash: &BigInt,
+    ) -> Result<Signature<E>, ProtocolError> {
+        let r_point = compute_pubkey(nonce_pair, &party_two_first_message.ecdh_first_message.dlog_proof.pk);
+        let r = x_coordinate_mod_q(&r_point)?;
+
+        let k1_inv = nonce_pair.secret_share().invert().ok_or(ProofError::IdentityPublicKey)?;
+        let s_prime = Paillier::decrypt(&key_share.dk, &party_two_second_message.c3).to_scalar::<E>();
+        let s = k1_inv * s_prime;
+
+        let y_is_odd = r_point.y_coord().ok_or(ProofError::MalformedPoint)?.test_bit(0);
+        let x_overflows = r_point.x_coord().ok_or(ProofError::MalformedPoint)? >= *Scalar::<E>::group_order();
+        let s_is_high = s.to_bigint() > Scalar::<E>::group_order() / BigInt::from(2);
+        let signature = Signature {
+            r,
+            s: if s_is_high { -s } else { s },
+            recovery_id: ((y_is_odd ^ s_is_high) as u8) | ((x_overflows as u8) << 1),
+        };
+        signature.verify(&key_share.public_key, message_hash)?;
+        Ok(signature)
+    }
+
+    /// Plain ECDSA verification: R' = m * s^-1 * G + r * s^-1 * Q, accept if R'.x mod q == r
+    pub fn verify(&self, public_key: &Point<E>, message_hash: &BigInt) -> Result<(), ProtocolError> {
+        if self.r.is_zero() {
+            return Err(ProtocolError::InvalidSignature);
+        }
+        let s_inv = self.s.invert().ok_or(ProtocolError::InvalidSignature)?;
+        let m = Scalar::<E>::from_bigint(message_hash);
+        let r_point = Point::generator() * (&m * &s_inv) + public_key * (&self.r * &s_inv);
+        match x_coordinate_mod_q(&r_point) {
+            Ok(r) if r == self.r => Ok(()),
+            _ => Err(ProtocolError::InvalidSignature),
+        }
+    }
+}
+
+fn x_coordinate_mod_q<E: Curve>(point: &Point<E>) -> Result<Scalar<E>, ProtocolError> {
+    let x = point.x_coord().ok_or(ProofError::IdentityPublicKey)?;
+    let r = Scalar::from_bigint(&x);
+    if r.is_zero() {
+        return Err(ProtocolError::InvalidSignature);
+    }
+    Ok(r)
+}
+
+/// Distinct from the key generation's key exchange
+fn ecdh_session_id(session_id: &[u8]) -> Vec<u8> {
+    ProofContext::new(SIGN_LABEL, session_id, 0).to_bytes()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::arithmetic::Converter;
+    use curv::BigInt;
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+    use ethers::types::{Signature as EthSignature, U256};
+    use secp256k1::ecdsa::{RecoverableSignature, RecoveryId, Signature as Secp256k1Signature};
+    use secp256k1::{Message, PublicKey};
+    use sha3::{Digest, Keccak256};
+
+    use crate::cryptology_primitives::encryption::paillier::Ciphertext;
+    use crate::cryptology_primitives::errors::{ProofError, ProtocolError};
+    use crate::cryptology_primitives::twoparty::ecdsa_keygen::tests::key_shares;
+    use crate::cryptology_primitives::twoparty::ecdsa_keygen::{Party1KeyShare, Party2KeyShare};
+    use crate::cryptology_primitives::twoparty::ecdsa_sign::{
+        SignParty1FirstMessage, SignParty1SecondMessage, SignParty2FirstMessage, SignParty2SecondMessage, Signature,
+    };
+
+    const SESSION_ID: &[u8] = b"test signing session";
+
+    fn sign(
+        p1_key_share: &Party1KeyShare<Secp256k1>,
+        p2_key_share: &Party2KeyShare<Secp256k1>,
+        message_hash: &BigInt,
+    ) -> Result<Signature<Secp256k1>, ProtocolError> {
+        let (p1_first_msg, comm_witness, p1_nonce_pair) = SignParty1FirstMessage::create::<Secp256k1, Keccak256>(SESSION_ID);
+        let (p2_first_msg, p2_nonce_pair) = SignParty2FirstMessage::<Secp256k1, Keccak256>::create(SESSION_ID);
+        let p1_second_msg = SignParty1SecondMessage::create(comm_witness, &p2_first_msg, SESSION_ID)?;
+        let p2_second_msg =
+            SignParty2SecondMessage::create(p2_key_share, &p2_nonce_pair, &p1_first_msg, &p1_second_msg, message_hash, SESSION_ID)?;
+        Signature::compute(p1_key_share, &p1_nonce_pair, &p2_first_msg, &p2_second_msg, message_hash)
+    }
+
+    fn secp256k1_message(message_hash: &BigInt) -> Message {
+        let bytes = message_hash.to_bytes();
+        let mut padded = [0u8; 32];
+        padded[32 - bytes.len()..].copy_from_slice(&bytes);
+        Message::from_slice(&padded).unwrap()
+    }
+
+    #[test]
+    fn test_sign_verifies_with_secp256k1() {
+        let (p1_key_share, p2_key_share) = key_shares();
+        let public_key = PublicKey::from_slice(&p1_key_share.public_key.to_bytes(false)).unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+
+        for i in 0..4u8 {
+            let message_hash = BigInt::from_bytes(&Keccak256::digest(&[i]));
+            let signature = sign(p1_key_share, p2_key_share, &message_hash).unwrap();
+            assert!(signature.verify(&p2_key_share.public_key, &message_hash).is_ok());
+
+            let mut compact = signature.r.to_bytes().to_vec();
+            compact.extend_from_slice(&signature.s.to_bytes());
+            let message = secp256k1_message(&message_hash);
+            // from_compact accepts high s too, verify_ecdsa only accepts normalized signatures
+            let secp_signature = Secp256k1Signature::from_compact(&compact).unwrap();
+            assert!(secp.verify_ecdsa(&message, &secp_signature, &public_key).is_ok());
+
+            let recovery_id = RecoveryId::from_i32(signature.recovery_id as i32).unwrap();
+            let recoverable = RecoverableSignature::from_compact(&compact, recovery_id).unwrap();
+            assert_eq!(secp.recover_ecdsa(&message, &recoverable).unwrap(), public_key);
+
+            let other_hash = BigInt::from_bytes(&Keccak256::digest(&[i, 0]));
+            assert_eq!(signature.verify(&p1_key_share.public_key, &other_hash), Err(ProtocolError::InvalidSignature));
+            assert!(secp.verify_ecdsa(&secp256k1_message(&other_hash), &secp_signature, &public_key).is_err());
+        }
+    }
+
+    #[test]
+    fn test_sign_recovers_ethereum_address() {
+        let (p1_key_share, p2_key_share) = key_shares();
+        let uncompressed = p1_key_share.public_key.to_bytes(false);
+        let address = ethers::types::Address::from_slice(&Keccak256::digest(&uncompressed[1..])[12..]);
+
+        // EIP-191 personal message, as signed by eth_sign
+        let message = "two-party signed message";
+        let message_hash = BigInt::from_bytes(ethers::utils::hash_message(message).as_bytes());
+        let signature = sign(p1_key_share, p2_key_share, &message_hash).unwrap();
+        let eth_signature = EthSignature {
+            r: U256::from_big_endian(&signature.r.to_bytes()),
+            s: U256::from_big_endian(&signature.s.to_bytes()),
+            v: 27 + signature.recovery_id as u64,
+        };
+        assert_eq!(eth_signature.recover(message).unwrap(), address);
+        assert!(eth_signature.verify(message, address).is_ok());
+        assert_ne!(eth_signature.recover("another message").unwrap(), address);
+    }
+
+    #[test]
+    fn test_sign_rejects_malicious_party_two() {
+        let (p1_key_share, p2_key_share) = key_shares();
+        let message_hash = BigInt::from_bytes(&Keccak256::digest(b"message"));
+
+        // party two signing with another share or a tampered c3: party one won't output it
+        let mut wrong_share = p2_key_share.clone();
+        wrong_share.x2 = &wrong_share.x2 + Scalar::from(1);
+        assert_eq!(sign(p1_key_share, &wrong_share, &message_hash).unwrap_err(), ProtocolError::InvalidSignature);
+
+        let (p1_first_msg, comm_witness, p1_nonce_pair) = SignParty1FirstMessage::create::<Secp256k1, Keccak256>(SESSION_ID);
+        let (p2_first_msg, p2_nonce_pair) = SignParty2FirstMessage::<Secp256k1, Keccak256>::create(SESSION_ID);
+        let p1_second_msg = SignParty1SecondMessage::create(comm_witness, &p2_first_msg, SESSION_ID).unwrap();
+        let mut p2_second_msg =
+            SignParty2SecondMessage::create(p2_key_share, &p2_nonce_pair, &p1_first_msg, &p1_second_msg, &message_hash, SESSION_ID).unwrap();
+        p2_second_msg.c3 = Ciphertext { c: &p2_second_msg.c3.c + &p2_key_share.ek.n };
+        assert_eq!(
+            Signature::compute(p1_key_share, &p1_nonce_pair, &p2_first_msg, &p2_second_msg, &message_hash).unwrap_err(),
+            ProtocolError::InvalidSignature
+        );
+
+        // a nonce proof from another session is rejected
+        let (_, comm_witness, _) = SignParty1FirstMessage::create::<Secp256k1, Keccak256>(SESSION_ID);
+        let (p2_first_msg, _) = SignParty2FirstMessage::<Secp256k1, Keccak256>::create(b"other session");
+        assert_eq!(SignParty1SecondMessage::create(comm_witness, &p2_first_msg, SESSION_ID).err(), Some(ProtocolError::Proof(ProofError::InvalidResponse)));
+
+        // a forged signature for the joint key
+        let forged = Signature::<Secp256k1> { r: Scalar::random(), s: Scalar::random(), recovery_id: 0 };
+        assert_eq!(forged.verify(&p1_key_share.public_key, &message_hash), Err(ProtocolError::InvalidSignature));
+        assert_eq!(forged.verify(&(Point::generator() * Scalar::random()), &message_hash), Err(ProtocolError::InvalidSignature));
+    }
+}
@@ -0,0 +1,322 @@
+//! Wire format of the two-party protocol messages. Every message travels in an [Envelope] with
+//! the protocol it belongs to, the envelope version, the session id, the round and the index of
+//! its sender, encoded as JSON or bincode.
+//!
+//! Each message type knows its protocol, round and sender through [ProtocolMessage], so
+//! [Envelope::new] fills the header in and [Envelope::open] rejects a message that was sent in
+//! another session or doesn't fit where the receiver is in the protocol, before any protocol code
+//! sees it.
+//!
+//! Protocol ids are fixed once assigned, new protocols are appended to [ProtocolId].
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::Curve;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::errors::EnvelopeError;
+use crate::cryptology_primitives::twoparty::{dh_key_exchange, dh_key_exchange_with_pok, ecdsa_keygen, ecdsa_sign, key_refresh, musig2};
+
+pub const ENVELOPE_VERSION: u16 = 1;
+
+pub const PARTY_ONE_INDEX: u16 = 1;
+pub const PARTY_TWO_INDEX: u16 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtocolId {
+    DhKeyExchange,
+    DhKeyExchangeWithPok,
+    EcdsaKeyGen,
+    EcdsaSign,
+    KeyRefresh,
+    MuSig2,
+}
+
+/// Where a message type sits in its protocol
+pub trait ProtocolMessage {
+    const PROTOCOL: ProtocolId;
+    const ROUND: u16;
+    const SENDER: u16;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<M> {
+    pub version: u16,
+    pub protocol: ProtocolId,
+    pub session_id: Vec<u8>,
+    pub round: u16,
+    pub sender: u16,
+    pub message: M,
+}
+
+/// The version comes first in both encodings, so it can be checked before the rest is decoded
+#[derive(Deserialize)]
+struct EnvelopeVersion {
+    version: u16,
+}
+
+impl<M: ProtocolMessage> Envelope<M> {
+    pub fn new(session_id: &[u8], message: M) -> Envelope<M> {
+        Envelope {
+            version: ENVELOPE_VERSION,
+            protocol: M::PROTOCOL,
+            session_id: session_id.to_vec(),
+            round: M::ROUND,
+            sender: M::SENDER,
+            message,
+        }
+    }
+
+    /// The message, if the header matches the session and what the message type expects
+    pub fn open(self, session_id: &[u8]) -> Result<M, EnvelopeError> {
+        if self.version != ENVELOPE_VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(self.version));
+        }
+        if self.protocol != M::PROTOCOL {
+            return Err(EnvelopeError::ProtocolMismatch);
+        }
+        if self.session_id != session_id {
+            return Err(EnvelopeError::SessionMismatch);
+        }
+        if self.round != M::ROUND {
+            return Err(EnvelopeError::UnexpectedRound);
+        }
+        if self.sender != M::SENDER {
+            return Err(EnvelopeError::UnexpectedSender);
+        }
+        Ok(self.message)
+    }
+}
+
+impl<M: Serialize + DeserializeOwned> Envelope<M> {
+    pub fn to_json(&self) -> Result<String, EnvelopeError> {
+        serde_json::to_string(self).map_err(|err| EnvelopeError::Encoding(err.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Envelope<M>, EnvelopeError> {
+        let EnvelopeVersion { version } = serde_json::from_str(json).map_err(|err| EnvelopeError::Encoding(err.to_string()))?;
+        check_version(version)?;
+        serde_json::from_str(json).map_err(|err| EnvelopeError::Encoding(err.to_string()))
+    }
+
+    pub fn to_bincode(&self) -> Result<Vec<u8>, EnvelopeError> {
+        bincode::serialize(self).map_err(|err| EnvelopeError::Encoding(err.to_string()))
+    }
+
+    pub fn from_bincode(bytes: &[u8]) -> Result<Envelope<M>, EnvelopeError> {
+        let EnvelopeVersion { version } = bincode::deserialize(bytes).map_err(|err| EnvelopeError::Encoding(err.to_string()))?;
+        check_version(version)?;
+        bincode::deserialize(bytes).map_err(|err| EnvelopeError::Encoding(err.to_string()))
+    }
+}
+
+fn check_version(version: u16) -> Result<(), EnvelopeError> {
+    if version != ENVELOPE_VERSION {
+        return Err(EnvelopeError::UnsupportedVersion(version));
+    }
+    Ok(())
+}
+
+macro_rules! protocol_message {
+    ($protocol:ident, $round:literal, $sender:ident, [$($generics:tt)*] $message:ty) => {
+        impl<$($generics)*> ProtocolMessage for $message {
+            const PROTOCOL: ProtocolId = ProtocolId::$protocol;
+            const ROUND: u16 = $round;
+            const SENDER: u16 = $sender;
+        }
+    };
+}
+
+protocol_message!(DhKeyExchange, 1, PARTY_ONE_INDEX, [E: Curve] dh_key_exchange::Party1FirstMessage<E>);
+protocol_message!(DhKeyExchange, 1, PARTY_TWO_INDEX, [E: Curve] dh_key_exchange::Party2FirstMessage<E>);
+
+protocol_message!(DhKeyExchangeWithPok, 1, PARTY_ONE_INDEX, [] dh_key_exchange_with_pok::Party1FirstMessage);
+protocol_message!(DhKeyExchangeWithPok, 1, PARTY_TWO_INDEX, [E: Curve, H: Digest + Clone] dh_key_exchange_with_pok::Party2FirstMessage<E, H>);
+protocol_message!(DhKeyExchangeWithPok, 2, PARTY_ONE_INDEX, [E: Curve, H: Digest + Clone] dh_key_exchange_with_pok::Party1SecondMessage<E, H>);
+protocol_message!(DhKeyExchangeWithPok, 2, PARTY_TWO_INDEX, [] dh_key_exchange_with_pok::Party2SecondMessage);
+
+protocol_message!(EcdsaKeyGen, 1, PARTY_ONE_INDEX, [] ecdsa_keygen::KeyGenParty1FirstMessage);
+protocol_message!(EcdsaKeyGen, 1, PARTY_TWO_INDEX, [E: Curve, H: Digest + Clone] ecdsa_keygen::KeyGenParty2FirstMessage<E, H>);
+protocol_message!(EcdsaKeyGen, 2, PARTY_ONE_INDEX, [E: Curve, H: Digest + Clone] ecdsa_keygen::KeyGenParty1SecondMessage<E, H>);
+protocol_message!(EcdsaKeyGen, 2, PARTY_TWO_INDEX, [] ecdsa_keygen::KeyGenParty2SecondMessage);
+protocol_message!(EcdsaKeyGen, 3, PARTY_ONE_INDEX, [] ecdsa_keygen::KeyGenParty1ThirdMessage);
+protocol_message!(EcdsaKeyGen, 3, PARTY_TWO_INDEX, [E: Curve] ecdsa_keygen::KeyGenParty2ThirdMessage<E>);
+protocol_message!(EcdsaKeyGen, 4, PARTY_ONE_INDEX, [E: Curve] ecdsa_keygen::KeyGenParty1FourthMessage<E>);
+
+protocol_message!(EcdsaSign, 1, PARTY_ONE_INDEX, [] ecdsa_sign::SignParty1FirstMessage);
+protocol_message!(EcdsaSign, 1, PARTY_TWO_INDEX, [E: Curve, H: Digest + Clone] ecdsa_sign::SignParty2FirstMessage<E, H>);
+protocol_message!(EcdsaSign, 2, PARTY_ONE_INDEX, [E: Curve, H: Digest + Clone] ecdsa_sign::SignParty1SecondMessage<E, H>);
+protocol_message!(EcdsaSign, 2, PARTY_TWO_INDEX, [] ecdsa_sign::SignParty2SecondMessage);
+
+protocol_message!(KeyRefresh, 1, PARTY_ONE_INDEX, [] key_refresh::RefreshParty1FirstMessage);
+protocol_message!(KeyRefresh, 1, PARTY_TWO_INDEX, [E: Curve, H: Digest + Clone] key_refresh::RefreshParty2FirstMessage<E, H>);
+protocol_message!(KeyRefresh, 2, PARTY_ONE_INDEX, [E: Curve, H: Digest + Clone] key_refresh::RefreshParty1SecondMessage<E, H>);
+
+protocol_message!(MuSig2, 1, PARTY_ONE_INDEX, [] musig2::Party1FirstMessage);
+protocol_message!(MuSig2, 1, PARTY_TWO_INDEX, [] musig2::Party2FirstMessage);
+protocol_message!(MuSig2, 2, PARTY_ONE_INDEX, [] musig2::Party1SecondMessage);
+protocol_message!(MuSig2, 2, PARTY_TWO_INDEX, [] musig2::Party2SecondMessage);
+
+
+#[cfg(test)]
+mod tests {
+    use curv::arithmetic::Converter;
+    use curv::BigInt;
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use sha3::{Digest, Keccak256};
+
+    use crate::cryptology_primitives::errors::EnvelopeError;
+    use crate::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{
+        EcKeyPair, Party1FirstMessage, Party1SecondMessage, Party2FirstMessage, Party2SecondMessage,
+    };
+    use crate::cryptology_primitives::twoparty::envelope::{Envelope, ProtocolId, ProtocolMessage, ENVELOPE_VERSION};
+    use crate::cryptology_primitives::twoparty::{dh_key_exchange, ecdsa_keygen, ecdsa_sign, key_refresh, musig2};
+
+    const SESSION_ID: &[u8] = b"test session";
+
+    /// Sends a message through both encodings, checks they agree and returns the bincode copy
+    fn transmit<M: ProtocolMessage + Serialize + DeserializeOwned>(message: M) -> M {
+        let envelope = Envelope::new(SESSION_ID, message);
+        let json = envelope.to_json().unwrap();
+        let bytes = envelope.to_bincode().unwrap();
+        let from_json = Envelope::<M>::from_json(&json).unwrap();
+        let from_bincode = Envelope::<M>::from_bincode(&bytes).unwrap();
+        assert_eq!(from_json.to_json().unwrap(), json);
+        assert_eq!(from_bincode.to_bincode().unwrap(), bytes);
+        from_bincode.open(SESSION_ID).unwrap()
+    }
+
+    #[test]
+    fn test_key_exchange_over_envelopes() {
+        let (p1_first_msg, comm_witness, _) =
+            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, Keccak256>(Scalar::random(), SESSION_ID);
+        let (p2_first_msg, _) = Party2FirstMessage::<Secp256k1, Keccak256>::create_dlog_proof_with_secret(Scalar::random(), SESSION_ID);
+        let p1_first_msg = transmit(p1_first_msg);
+        let p2_first_msg = transmit(p2_first_msg);
+
+        let p1_second_msg = Party1SecondMessage::verify_and_decomit(comm_witness, &p2_first_msg.dlog_proof, SESSION_ID).unwrap();
+        let p1_second_msg = transmit(p1_second_msg);
+        let p2_second_msg = Party2SecondMessage::verify_commitments_and_dlog_proof(&p1_first_msg, &p1_second_msg, SESSION_ID).unwrap();
+        transmit(p2_second_msg);
+    }
+
+    #[test]
+    fn test_dh_key_exchange_over_envelopes() {
+        let (p1_first_msg, p1_key_pair) = dh_key_exchange::Party1FirstMessage::<Secp256k1>::first();
+        let (p2_first_msg, p2_key_pair) = dh_key_exchange::Party2FirstMessage::<Secp256k1>::first();
+        let (p1_first_msg, p2_first_msg) = (transmit(p1_first_msg), transmit(p2_first_msg));
+        assert_eq!(
+            dh_key_exchange::compute_pubkey(&p1_key_pair, &p2_first_msg.public_share),
+            dh_key_exchange::compute_pubkey(&p2_key_pair, &p1_first_msg.public_share)
+        );
+    }
+
+    #[test]
+    fn test_ecdsa_over_envelopes() {
+        let (p1_first_msg, comm_witness, p1_key_pair) = ecdsa_keygen::KeyGenParty1FirstMessage::create::<Secp256k1, Keccak256>(SESSION_ID);
+        let (p2_first_msg, p2_key_pair) = ecdsa_keygen::KeyGenParty2FirstMessage::<Secp256k1, Keccak256>::create(SESSION_ID);
+        let (p1_first_msg, p2_first_msg) = (transmit(p1_first_msg), transmit(p2_first_msg));
+
+        // the Paillier key, c_key and the proofs about them
+        let (p1_second_msg, p1_key_share) =
+            ecdsa_keygen::KeyGenParty1SecondMessage::create(comm_witness, &p1_key_pair, &p2_first_msg, SESSION_ID).unwrap();
+        let p1_second_msg = transmit(p1_second_msg);
+        let (p2_second_msg, p2_state) =
+            ecdsa_keygen::KeyGenParty2SecondMessage::create(&p2_key_pair, &p1_first_msg, &p1_second_msg, SESSION_ID).unwrap();
+        let p2_second_msg = transmit(p2_second_msg);
+
+        let (p1_third_msg, p1_pdl_state) = ecdsa_keygen::KeyGenParty1ThirdMessage::create::<Secp256k1, Keccak256>(&p1_key_share, &p2_second_msg);
+        let (p1_third_msg, p2_third_msg) = (transmit(p1_third_msg), transmit(p2_state.third_message()));
+        let p1_fourth_msg = transmit(ecdsa_keygen::KeyGenParty1FourthMessage::create(&p1_key_share, p1_pdl_state, &p2_third_msg).unwrap());
+        let p2_key_share = ecdsa_keygen::Party2KeyShare::create(p2_state, &p1_third_msg, &p1_fourth_msg).unwrap();
+        assert_eq!(p1_key_share.public_key, p2_key_share.public_key);
+
+        let message_hash = BigInt::from_bytes(&Keccak256::digest(b"message"));
+        let (p1_first_msg, comm_witness, p1_nonce_pair) = ecdsa_sign::SignParty1FirstMessage::create::<Secp256k1, Keccak256>(SESSION_ID);
+        let (p2_first_msg, p2_nonce_pair) = ecdsa_sign::SignParty2FirstMessage::<Secp256k1, Keccak256>::create(SESSION_ID);
+        let (p1_first_msg, p2_first_msg) = (transmit(p1_first_msg), transmit(p2_first_msg));
+        let p1_second_msg = transmit(ecdsa_sign::SignParty1SecondMessage::create(comm_witness, &p2_first_msg, SESSION_ID).unwrap());
+        let p2_second_msg = transmit(
+            ecdsa_sign::SignParty2SecondMessage::create(&p2_key_share, &p2_nonce_pair, &p1_first_msg, &p1_second_msg, &message_hash, SESSION_ID)
+                .unwrap(),
+        );
+        let signature =
+            ecdsa_sign::Signature::compute(&p1_key_share, &p1_nonce_pair, &p2_first_msg, &p2_second_msg, &message_hash).unwrap();
+        assert!(signature.verify(&p2_key_share.public_key, &message_hash).is_ok());
+    }
+
+    #[test]
+    fn test_key_refresh_over_envelopes() {
+        let (x1, x2) = (Scalar::<Secp256k1>::random(), Scalar::<Secp256k1>::random());
+        let (q1, q2) = (Point::generator() * &x1, Point::generator() * &x2);
+        let (p1_share, p2_share) = (EcKeyPair::from_secret_share(x1), EcKeyPair::from_secret_share(x2));
+
+        let (p1_first_msg, comm_witness, p1_coin) = key_refresh::RefreshParty1FirstMessage::create::<Secp256k1, Keccak256>(SESSION_ID);
+        let (p2_first_msg, p2_coin) = key_refresh::RefreshParty2FirstMessage::<Secp256k1, Keccak256>::create(SESSION_ID);
+        let (p1_first_msg, p2_first_msg) = (transmit(p1_first_msg), transmit(p2_first_msg));
+        let (p1_second_msg, party_one) =
+            key_refresh::RefreshParty1SecondMessage::create(&p1_share, &q2, comm_witness, &p1_coin, &p2_first_msg, SESSION_ID).unwrap();
+        let p1_second_msg = transmit(p1_second_msg);
+        let party_two =
+            key_refresh::RefreshedShare::party_two(&p2_share, &q1, &p2_coin, &p1_first_msg, &p1_second_msg, SESSION_ID).unwrap();
+        assert_eq!(party_one.peer_public_share, party_two.key_pair.public_share);
+        assert_eq!(party_two.peer_public_share, party_one.key_pair.public_share);
+    }
+
+    #[test]
+    fn test_musig2_over_envelopes() {
+        let party_one = musig2::MuSig2KeyPair::new();
+        let party_two = musig2::MuSig2KeyPair::new();
+        let message = b"message";
+        let key_agg = musig2::KeyAggContext::new([party_one.public_key.clone(), party_two.public_key.clone()]).unwrap();
+        let (p1_first_msg, p1_secret_nonce) = musig2::Party1FirstMessage::first(&party_one, &key_agg, message);
+        let (p2_first_msg, p2_secret_nonce) = musig2::Party2FirstMessage::first(&party_two, &key_agg, message);
+        let (p1_first_msg, p2_first_msg) = (transmit(p1_first_msg), transmit(p2_first_msg));
+
+        let session = musig2::SigningSession::new(&key_agg, &p1_first_msg, &p2_first_msg, message);
+        let p1_second_msg = transmit(musig2::Party1SecondMessage::second(p1_secret_nonce, &party_one, &session).unwrap());
+        let p2_second_msg = transmit(musig2::Party2SecondMessage::second(p2_secret_nonce, &party_two, &session).unwrap());
+        let signature = session.aggregate(&p1_second_msg, &p2_second_msg).unwrap();
+        assert!(signature.verify(&key_agg.x_only_public_key(), message).is_ok());
+    }
+
+    #[test]
+    fn test_envelope_rejects_mismatched_headers() {
+        let (p1_first_msg, _, _) =
+            Party1FirstMessage::create_commitments_with_secret::<Secp256k1, Keccak256>(Scalar::random(), SESSION_ID);
+        let envelope = Envelope::new(SESSION_ID, p1_first_msg);
+        assert_eq!(envelope.protocol, ProtocolId::DhKeyExchangeWithPok);
+        assert_eq!((envelope.round, envelope.sender), (1, 1));
+
+        let reopen = |change: &dyn Fn(&mut Envelope<Party1FirstMessage>)| {
+            let mut changed = Envelope::<Party1FirstMessage>::from_json(&envelope.to_json().unwrap()).unwrap();
+            change(&mut changed);
+            changed.open(SESSION_ID).err()
+        };
+        assert_eq!(reopen(&|_| {}), None);
+        assert_eq!(reopen(&|e| e.protocol = ProtocolId::EcdsaKeyGen), Some(EnvelopeError::ProtocolMismatch));
+        assert_eq!(reopen(&|e| e.session_id = b"other session".to_vec()), Some(EnvelopeError::SessionMismatch));
+        assert_eq!(reopen(&|e| e.round = 2), Some(EnvelopeError::UnexpectedRound));
+        assert_eq!(reopen(&|e| e.sender = 2), Some(EnvelopeError::UnexpectedSender));
+
+        // a future version is rejected before its body is decoded, in both encodings
+        let mut bytes = envelope.to_bincode().unwrap();
+        bytes[..2].copy_from_slice(&(ENVELOPE_VERSION + 1).to_le_bytes());
+        bytes.truncate(4);
+        assert_eq!(
+            Envelope::<Party1FirstMessage>::from_bincode(&bytes).err(),
+            Some(EnvelopeError::UnsupportedVersion(ENVELOPE_VERSION + 1))
+        );
+        let json = format!("{{\"version\":{}}}", ENVELOPE_VERSION + 1);
+        assert_eq!(
+            Envelope::<Party1FirstMessage>::from_json(&json).err(),
+            Some(EnvelopeError::UnsupportedVersion(ENVELOPE_VERSION + 1))
+        );
+
+        assert!(matches!(Envelope::<Party1FirstMessage>::from_bincode(&[1, 0, 9]), Err(EnvelopeError::Encoding(_))));
+        let other_message = Envelope::new(SESSION_ID, Party2SecondMessage {}).to_json().unwrap();
+        assert!(matches!(Envelope::<Party1FirstMessage>::from_json(&other_message), Err(EnvelopeError::Encoding(_))));
+    }
+}
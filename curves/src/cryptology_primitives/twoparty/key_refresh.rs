@@ -0,0 +1,277 @@
+//! Proactive refresh of the shares of [dh_key_exchange_with_pok](super::dh_key_exchange_with_pok):
+//! both parties get new shares with the same joint public key Q = x1 * x2 * G.
+//!
+//! The parties toss a coin by running the key exchange again on fresh secrets r1, r2, so neither
+//! can bias the outcome, and both hash the joint point r1 * r2 * G to a scalar d. The new shares
+//! are x1' = x1 * d and x2' = x2 / d. A share from before the refresh combined with one from after
+//! gives x1 * x2 / d or x1 * x2 * d, not the secret key, so stealing one share at a time from each
+//! party across refreshes is useless.
+//!
+//! ```text
+//! party one                                          party two
+//! RefreshParty1FirstMessage::create  --- com(R1) --->
+//!                                    <--- R2, PoK --- RefreshParty2FirstMessage::create
+//! RefreshParty1SecondMessage::create --- R1, PoK ---> RefreshedShare::party_two
+//!                                    <---- ack -----
+//! ```
+//!
+//! Party one derives its new share before party two has checked the decommitment. Until party
+//! two acknowledges its refresh, party one keeps the old share next to the new one: if party two
+//! rejects the message or it gets lost, party two still holds x2, which only matches the old x1.
+//! Party two switches once [RefreshedShare::party_two] succeeds, and each party deletes its old
+//! share only after both have switched.
+//!
+//! Only the plain shares of the key exchange are refreshed. The two-party ECDSA shares of
+//! [ecdsa_keygen](super::ecdsa_keygen) can't be: party two holds c_key = Enc(x1), and keygen
+//! proved x1 < q/3, a bound x1 * d doesn't keep. Refreshing them would need a new Paillier
+//! ciphertext and range proof for x1 * d, which this module doesn't provide.
+
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Curve, Point, Scalar};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::cryptology_primitives::errors::{ProofError, ProtocolError};
+use crate::cryptology_primitives::proofs::context::ProofContext;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::rng::random_scalar;
+use crate::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{
+    compute_pubkey, CommWitness, EcKeyPair, Party1FirstMessage, Party1SecondMessage, Party2FirstMessage,
+    Party2SecondMessage,
+};
+
+const REFRESH_LABEL: &[u8] = b"two_party_key_refresh";
+
+#[derive(Serialize, Deserialize)]
+pub struct RefreshParty1FirstMessage {
+    pub ecdh_first_message: Party1FirstMessage,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+pub struct RefreshParty2FirstMessage<E: Curve, H: Digest + Clone> {
+    pub ecdh_first_message: Party2FirstMessage<E, H>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: Deserialize<'de>"))]
+pub struct RefreshParty1SecondMessage<E: Curve, H: Digest + Clone> {
+    pub ecdh_second_message: Party1SecondMessage<E, H>,
+}
+
+/// A party's new key pair and its view of the peer's new public share
+pub struct RefreshedShare<E: Curve> {
+    pub key_pair: EcKeyPair<E>,
+    pub peer_public_share: Point<E>,
+}
+
+impl RefreshParty1FirstMessage {
+    /// Commits to R1 = r1 * G for a fresh r1
+    pub fn create<E: Curve, H: Digest + Clone>(session_id: &[u8]) -> (RefreshParty1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+        Self::create_with_rng(session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng<E: Curve, H: Digest + Clone>(
+        session_id: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (RefreshParty1FirstMessage, CommWitness<E, H>, EcKeyPair<E>) {
+        let (ecdh_first_message, comm_witness, coin_key_pair) =
+            Party1FirstMessage::create_commitments_with_secret_with_rng(random_scalar(rng), &ecdh_session_id(session_id), rng);
+        (RefreshParty1FirstMessage { ecdh_first_message }, comm_witness, coin_key_pair)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> RefreshParty2FirstMessage<E, H> {
+    /// R2 = r2 * G for a fresh r2 and its proof
+    pub fn create(session_id: &[u8]) -> (RefreshParty2FirstMessage<E, H>, EcKeyPair<E>) {
+        Self::create_with_rng(session_id, &mut rand::thread_rng())
+    }
+
+    pub fn create_with_rng(session_id: &[u8], rng: &mut (impl RngCore + CryptoRng)) -> (RefreshParty2FirstMessage<E, H>, EcKeyPair<E>) {
+        let (ecdh_first_message, coin_key_pair) =
+            Party2FirstMessage::create_dlog_proof_with_secret_with_rng(random_scalar(rng), &ecdh_session_id(session_id), rng);
+        (RefreshParty2FirstMessage { ecdh_first_message }, coin_key_pair)
+    }
+}
+
+impl<E: Curve, H: Digest + Clone> RefreshParty1SecondMessage<E, H> {
+    /// Checks party two's proof, decommits R1 and refreshes party one's share to x1 * d
+    pub fn create(
+        key_pair: &EcKeyPair<E>,
+        peer_public_share: &Point<E>,
+        comm_witness: CommWitness<E, H>,
+        coin_key_pair: &EcKeyPair<E>,
+        party_two_first_message: &RefreshParty2FirstMessage<E, H>,
+        session_id: &[u8],
+    ) -> Result<(RefreshParty1SecondMessage<E, H>, RefreshedShare<E>), ProtocolError> {
+        let party_two_proof = &party_two_first_message.ecdh_first_message.dlog_proof;
+        let ecdh_second_message = Party1SecondMessage::verify_and_decomit(comm_witness, party_two_proof, &ecdh_session_id(session_id))?;
+
+        let d = refresh_factor::<E, H>(&compute_pubkey(coin_key_pair, &party_two_proof.pk), session_id)?;
+        let d_inv = d.invert().ok_or(ProofError::IdentityPublicKey)?;
+        let refreshed_share = RefreshedShare {
+            key_pair: EcKeyPair::from_secret_share(key_pair.secret_share() * &d),
+            peer_public_share: peer_public_share * d_inv,
+        };
+        Ok((RefreshParty1SecondMessage { ecdh_second_message }, refreshed_share))
+    }
+}
+
+impl<E: Curve> RefreshedShare<E> {
+    /// Checks party one's decommitment and refreshes party two's share to x2 / d
+    pub fn party_two<H: Digest + Clone>(
+        key_pair: &EcKeyPair<E>,
+        peer_public_share: &Point<E>,
+        coin_key_pair: &EcKeyPair<E>,
+        party_one_first_message: &RefreshParty1FirstMessage,
+        party_one_second_message: &RefreshParty1SecondMessage<E, H>,
+        session_id: &[u8],
+    ) -> Result<RefreshedShare<E>, ProtocolError> {
+        let ecdh_second_message = &party_one_second_message.ecdh_second_message;
+        Party2SecondMessage::verify_commitments_and_dlog_proof(
+            &party_one_first_message.ecdh_first_message,
+            ecdh_second_message,
+            &ecdh_session_id(session_id),
+        )?;
+
+        let party_one_coin = &ecdh_second_message.comm_witness.dlog_proof.pk;
+        let d = refresh_factor::<E, H>(&compute_pubkey(coin_key_pair, party_one_coin), session_id)?;
+        let d_inv = d.invert().ok_or(ProofError::IdentityPublicKey)?;
+        Ok(RefreshedShare {
+            key_pair: EcKeyPair::from_secret_share(key_pair.secret_share() * d_inv),
+            peer_public_share: peer_public_share * d,
+        })
+    }
+}
+
+/// d = H(session, r1 * r2 * G), which both parties compute and nobody else knows
+fn refresh_factor<E: Curve, H: Digest + Clone>(joint_coin: &Point<E>, session_id: &[u8]) -> Result<Scalar<E>, ProofError> {
+    let mut transcript = Transcript::<H>::new(REFRESH_LABEL);
+    transcript.append_bytes(b"session_id", session_id);
+    transcript.append_point(b"joint_coin", joint_coin);
+    let d = transcript.challenge_scalar(b"refresh_factor");
+    if d.is_zero() {
+        return Err(ProofError::IdentityPublicKey);
+    }
+    Ok(d)
+}
+
+/// The coin toss runs with its own session ids, so no proof of keygen or signing is accepted in it
+fn ecdh_session_id(session_id: &[u8]) -> Vec<u8> {
+    ProofContext::new(REFRESH_LABEL, session_id, 0).to_bytes()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::BigInt;
+    use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+    use sha3::Keccak256;
+
+    use crate::cryptology_primitives::errors::{ProofError, ProtocolError};
+    use crate::cryptology_primitives::twoparty::dh_key_exchange_with_pok::{compute_pubkey, EcKeyPair};
+    use crate::cryptology_primitives::twoparty::key_refresh::{
+        RefreshParty1FirstMessage, RefreshParty1SecondMessage, RefreshParty2FirstMessage, RefreshedShare,
+    };
+
+    type Party2First = RefreshParty2FirstMessage<Secp256k1, Keccak256>;
+
+    fn refresh(
+        party_one: &RefreshedShare<Secp256k1>,
+        party_two: &RefreshedShare<Secp256k1>,
+        session_id: &[u8],
+    ) -> (RefreshedShare<Secp256k1>, RefreshedShare<Secp256k1>) {
+        let (p1_first_msg, comm_witness, p1_coin) = RefreshParty1FirstMessage::create::<Secp256k1, Keccak256>(session_id);
+        let (p2_first_msg, p2_coin) = Party2First::create(session_id);
+        let (p1_second_msg, new_party_one) = RefreshParty1SecondMessage::create(
+            &party_one.key_pair, &party_one.peer_public_share, comm_witness, &p1_coin, &p2_first_msg, session_id,
+        ).unwrap();
+        let new_party_two = RefreshedShare::party_two(
+            &party_two.key_pair, &party_two.peer_public_share, &p2_coin, &p1_first_msg, &p1_second_msg, session_id,
+        ).unwrap();
+        (new_party_one, new_party_two)
+    }
+
+    fn initial_shares() -> (RefreshedShare<Secp256k1>, RefreshedShare<Secp256k1>) {
+        let (x1, x2) = (Scalar::random(), Scalar::random());
+        (
+            RefreshedShare { peer_public_share: Point::generator() * &x2, key_pair: EcKeyPair::from_secret_share(x1.clone()) },
+            RefreshedShare { peer_public_share: Point::generator() * &x1, key_pair: EcKeyPair::from_secret_share(x2) },
+        )
+    }
+
+    #[test]
+    fn test_refresh_keeps_public_key() {
+        let (party_one, party_two) = initial_shares();
+        let public_key = compute_pubkey(&party_one.key_pair, &party_one.peer_public_share);
+
+        let (new_party_one, new_party_two) = refresh(&party_one, &party_two, b"refresh 1");
+        assert_ne!(new_party_one.key_pair.secret_share(), party_one.key_pair.secret_share());
+        assert_ne!(new_party_two.key_pair.secret_share(), party_two.key_pair.secret_share());
+        // each party's view of the peer's share matches the peer's actual share
+        assert_eq!(new_party_one.peer_public_share, new_party_two.key_pair.public_share);
+        assert_eq!(new_party_two.peer_public_share, new_party_one.key_pair.public_share);
+        assert_eq!(compute_pubkey(&new_party_one.key_pair, &new_party_one.peer_public_share), public_key);
+        assert_eq!(compute_pubkey(&new_party_two.key_pair, &new_party_two.peer_public_share), public_key);
+
+        // an old share with a new one doesn't give the key
+        assert_ne!(compute_pubkey(&party_one.key_pair, &new_party_two.key_pair.public_share), public_key);
+        assert_ne!(compute_pubkey(&new_party_one.key_pair, &party_two.key_pair.public_share), public_key);
+        let old_x1_new_x2 = party_one.key_pair.secret_share() * new_party_two.key_pair.secret_share();
+        assert_ne!(Point::generator() * old_x1_new_x2, public_key);
+
+        // nor does a share from a refresh with one from the next
+        let (newer_party_one, newer_party_two) = refresh(&new_party_one, &new_party_two, b"refresh 2");
+        assert_eq!(compute_pubkey(&newer_party_one.key_pair, &newer_party_two.key_pair.public_share), public_key);
+        assert_ne!(compute_pubkey(&new_party_one.key_pair, &newer_party_two.key_pair.public_share), public_key);
+    }
+
+    #[test]
+    fn test_party_one_keeps_old_share_until_acknowledged() {
+        let (party_one, party_two) = initial_shares();
+        let public_key = compute_pubkey(&party_one.key_pair, &party_one.peer_public_share);
+
+        // party one has refreshed, but its message to party two is tampered with
+        let (p1_first_msg, comm_witness, p1_coin) = RefreshParty1FirstMessage::create::<Secp256k1, Keccak256>(b"session");
+        let (p2_first_msg, p2_coin) = Party2First::create(b"session");
+        let (mut p1_second_msg, new_party_one) = RefreshParty1SecondMessage::create(
+            &party_one.key_pair, &party_one.peer_public_share, comm_witness, &p1_coin, &p2_first_msg, b"session",
+        ).unwrap();
+        let comm_witness = &mut p1_second_msg.ecdh_second_message.comm_witness;
+        comm_witness.pk_commitment_blind_factor = &comm_witness.pk_commitment_blind_factor + BigInt::from(1);
+        assert!(RefreshedShare::party_two(
+            &party_two.key_pair, &party_two.peer_public_share, &p2_coin, &p1_first_msg, &p1_second_msg, b"session",
+        ).is_err());
+
+        // party two still holds x2, only party one's old share matches it
+        let x2 = party_two.key_pair.secret_share();
+        assert_ne!(Point::generator() * (new_party_one.key_pair.secret_share() * x2), public_key);
+        assert_eq!(Point::generator() * (party_one.key_pair.secret_share() * x2), public_key);
+    }
+
+    #[test]
+    fn test_refresh_rejects_mismatched_sessions() {
+        let (party_one, party_two) = initial_shares();
+        let (p1_first_msg, comm_witness, p1_coin) = RefreshParty1FirstMessage::create::<Secp256k1, Keccak256>(b"session");
+        let (p2_first_msg, _) = Party2First::create(b"other session");
+        assert_eq!(
+            RefreshParty1SecondMessage::create(
+                &party_one.key_pair, &party_one.peer_public_share, comm_witness, &p1_coin, &p2_first_msg, b"session",
+            ).err(),
+            Some(ProtocolError::Proof(ProofError::InvalidResponse))
+        );
+
+        // a decommitment to another coin is rejected by party two
+        let (_, comm_witness, p1_coin) = RefreshParty1FirstMessage::create::<Secp256k1, Keccak256>(b"session");
+        let (p2_first_msg, p2_coin) = Party2First::create(b"session");
+        let (p1_second_msg, _) = RefreshParty1SecondMessage::create(
+            &party_one.key_pair, &party_one.peer_public_share, comm_witness, &p1_coin, &p2_first_msg, b"session",
+        ).unwrap();
+        assert_eq!(
+            RefreshedShare::party_two(
+                &party_two.key_pair, &party_two.peer_public_share, &p2_coin, &p1_first_msg, &p1_second_msg, b"session",
+            ).err(),
+            Some(ProtocolError::Proof(ProofError::HashMismatch))
+        );
+    }
+}
@@ -1,2 +1,7 @@
 pub mod dh_key_exchange;
-pub mod dh_key_exchange_with_pok;
\ No newline at end of file
+pub mod dh_key_exchange_with_pok;
+pub mod ecdsa_keygen;
+pub mod ecdsa_sign;
+pub mod envelope;
+pub mod key_refresh;
+pub mod musig2;
\ No newline at end of file
@@ -0,0 +1,523 @@
+//! Two-party MuSig2, BIP340 Schnorr signatures under an aggregate key, as specified by BIP327
+//! https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki
+//!
+//! Key aggregation: Q = a_1 * P_1 + a_2 * P_2 with a_i = H(L, P_i), L the hash of both keys, and
+//! a_2 = 1 for the second key. The output is an ordinary BIP340 public key, the x coordinate of Q.
+//!
+//! Signing takes two rounds. Each party sends two nonces R_i1, R_i2 and, once it has the peer's,
+//! both compute R = R_1 + b * R_2 from the aggregate nonces R_1 = R_11 + R_21, R_2 = R_12 + R_22
+//! with b = H(R_1, R_2, Q, m). The second nonce is what makes a concurrent session useless to an
+//! attacker. Each party then sends s_i = k_i1 + b * k_i2 + e * a_i * x_i and s = s_1 + s_2.
+//!
+//! ```text
+//! party one                                        party two
+//! Party1FirstMessage::first   --- R_11, R_12 --->
+//!                             <--- R_21, R_22 ---  Party2FirstMessage::first
+//! SigningSession::new                              SigningSession::new
+//! Party1SecondMessage::second --- s_1 --->
+//!                             <--- s_2 ---         Party2SecondMessage::second
+//! SigningSession::aggregate                        SigningSession::aggregate
+//! ```
+//!
+//! A [SecretNonce] must sign a single message: it isn't `Clone` and signing consumes it.
+
+use curv::arithmetic::{BitManipulation, Converter};
+use curv::BigInt;
+use curv::cryptographic_primitives::hashing::Digest;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::cryptology_primitives::errors::{ProofError, ProtocolError};
+use crate::cryptology_primitives::rng::random_scalar;
+
+#[derive(Debug, Clone)]
+pub struct MuSig2KeyPair {
+    pub public_key: Point<Secp256k1>,
+    secret_key: Scalar<Secp256k1>,
+}
+
+/// The aggregate key of an ordered pair of public keys
+#[derive(Debug, Clone)]
+pub struct KeyAggContext {
+    pub aggregate_key: Point<Secp256k1>,
+    public_keys: [Point<Secp256k1>; 2],
+    key_list_hash: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicNonce {
+    pub r1: Point<Secp256k1>,
+    pub r2: Point<Secp256k1>,
+}
+
+/// k_1, k_2 and the public key they were generated for
+pub struct SecretNonce {
+    k1: Scalar<Secp256k1>,
+    k2: Scalar<Secp256k1>,
+    public_key: Point<Secp256k1>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Party1FirstMessage {
+    pub public_nonce: PublicNonce,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Party2FirstMessage {
+    pub public_nonce: PublicNonce,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Party1SecondMessage {
+    pub partial_signature: Scalar<Secp256k1>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Party2SecondMessage {
+    pub partial_signature: Scalar<Secp256k1>,
+}
+
+/// What both parties derive from the key, both nonces and the message
+#[derive(Debug, Clone)]
+pub struct SigningSession {
+    key_agg: KeyAggContext,
+    public_nonces: [PublicNonce; 2],
+    nonce_coefficient: Scalar<Secp256k1>,
+    final_nonce: Point<Secp256k1>,
+    challenge: Scalar<Secp256k1>,
+}
+
+/// A BIP340 signature: the x coordinate of R, which has an even y, and s
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchnorrSignature {
+    pub r: [u8; 32],
+    pub s: Scalar<Secp256k1>,
+}
+
+impl MuSig2KeyPair {
+    pub fn new() -> MuSig2KeyPair {
+        Self::new_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn new_with_rng(rng: &mut (impl RngCore + CryptoRng)) -> MuSig2KeyPair {
+        Self::from_secret_key(random_scalar(rng))
+    }
+
+    pub fn from_secret_key(secret_key: Scalar<Secp256k1>) -> MuSig2KeyPair {
+        MuSig2KeyPair {
+            public_key: Point::generator() * &secret_key,
+            secret_key,
+        }
+    }
+}
+
+impl Default for MuSig2KeyPair {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyAggContext {
+    /// KeyAgg of BIP327 for `[party one's key, party two's key]`, the order matters
+    pub fn new(public_keys: [Point<Secp256k1>; 2]) -> Result<KeyAggContext, ProtocolError> {
+        if public_keys.iter().any(|public_key| public_key.is_zero()) {
+            return Err(ProofError::IdentityPublicKey.into());
+        }
+        let encoded: Vec<u8> = public_keys.iter().flat_map(|public_key| public_key.to_bytes(true).to_vec()).collect();
+        let key_list_hash = tagged_hash(b"KeyAgg list", &[&encoded]);
+        let mut key_agg = KeyAggContext {
+            aggregate_key: Point::zero(),
+            public_keys,
+            key_list_hash,
+        };
+        key_agg.aggregate_key = key_agg.public_keys.iter()
+            .map(|public_key| public_key * key_agg.coefficient(public_key))
+            .fold(Point::zero(), |sum, term| sum + term);
+        if key_agg.aggregate_key.is_zero() {
+            return Err(ProofError::IdentityPublicKey.into());
+        }
+        Ok(key_agg)
+    }
+
+    /// The BIP340 public key, x(Q)
+    pub fn x_only_public_key(&self) -> [u8; 32] {
+        x_bytes(&self.aggregate_key)
+    }
+
+    /// a_i, 1 for the second distinct key of the list
+    fn coefficient(&self, public_key: &Point<Secp256k1>) -> Scalar<Secp256k1> {
+        let second_key = self.public_keys.iter().find(|key| *key != &self.public_keys[0]);
+        if second_key == Some(public_key) {
+            return Scalar::from(1);
+        }
+        hash_to_scalar(tagged_hash(b"KeyAgg coefficient", &[&self.key_list_hash, &public_key.to_bytes(true)]))
+    }
+}
+
+impl SecretNonce {
+    /// NonceGen of BIP327 with the secret key, the aggregate key and the message bound in
+    fn generate(
+        key_pair: &MuSig2KeyPair,
+        key_agg: &KeyAggContext,
+        message: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (SecretNonce, PublicNonce) {
+        let mut rand_prime = [0u8; 32];
+        rng.fill_bytes(&mut rand_prime);
+        let aux = tagged_hash(b"MuSig/aux", &[&rand_prime]);
+        let secret_key = key_pair.secret_key.to_bytes();
+        let rand: Vec<u8> = secret_key.iter().zip(aux.iter()).map(|(a, b)| a ^ b).collect();
+
+        let public_key = key_pair.public_key.to_bytes(true);
+        let aggregate_key = key_agg.x_only_public_key();
+        let nonce = |i: u8| {
+            let k: Scalar<Secp256k1> = hash_to_scalar(tagged_hash(b"MuSig/nonce", &[
+                &rand,
+                &[public_key.len() as u8],
+                &public_key,
+                &[aggregate_key.len() as u8],
+                &aggregate_key,
+                &[1],
+                &(message.len() as u64).to_be_bytes(),
+                message,
+                &0u32.to_be_bytes(),
+                &[i],
+            ]));
+            k
+        };
+        let (k1, k2) = (nonce(0), nonce(1));
+        let public_nonce = PublicNonce {
+            r1: Point::generator() * &k1,
+            r2: Point::generator() * &k2,
+        };
+        let secret_nonce = SecretNonce {
+            k1,
+            k2,
+            public_key: key_pair.public_key.clone(),
+        };
+        (secret_nonce, public_nonce)
+    }
+}
+
+impl Party1FirstMessage {
+    /// Fresh nonces to sign `message` under `key_agg`
+    pub fn first(key_pair: &MuSig2KeyPair, key_agg: &KeyAggContext, message: &[u8]) -> (Party1FirstMessage, SecretNonce) {
+        Self::first_with_rng(key_pair, key_agg, message, &mut rand::thread_rng())
+    }
+
+    pub fn first_with_rng(
+        key_pair: &MuSig2KeyPair,
+        key_agg: &KeyAggContext,
+        message: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (Party1FirstMessage, SecretNonce) {
+        let (secret_nonce, public_nonce) = SecretNonce::generate(key_pair, key_agg, message, rng);
+        (Party1FirstMessage { public_nonce }, secret_nonce)
+    }
+}
+
+impl Party2FirstMessage {
+    /// Fresh nonces to sign `message` under `key_agg`
+    pub fn first(key_pair: &MuSig2KeyPair, key_agg: &KeyAggContext, message: &[u8]) -> (Party2FirstMessage, SecretNonce) {
+        Self::first_with_rng(key_pair, key_agg, message, &mut rand::thread_rng())
+    }
+
+    pub fn first_with_rng(
+        key_pair: &MuSig2KeyPair,
+        key_agg: &KeyAggContext,
+        message: &[u8],
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> (Party2FirstMessage, SecretNonce) {
+        let (secret_nonce, public_nonce) = SecretNonce::generate(key_pair, key_agg, message, rng);
+        (Party2FirstMessage { public_nonce }, secret_nonce)
+    }
+}
+
+impl SigningSession {
+    /// Aggregates the nonces, b = H(R_1, R_2, x(Q), m), R = R_1 + b * R_2 and e = H(x(R), x(Q), m)
+    pub fn new(
+        key_agg: &KeyAggContext,
+        party_one_first_message: &Party1FirstMessage,
+        party_two_first_message: &Party2FirstMessage,
+        message: &[u8],
+    ) -> SigningSession {
+        let public_nonces = [party_one_first_message.public_nonce.clone(), party_two_first_message.public_nonce.clone()];
+        let aggregate_r1 = &public_nonces[0].r1 + &public_nonces[1].r1;
+        let aggregate_r2 = &public_nonces[0].r2 + &public_nonces[1].r2;
+
+        let nonce_coefficient = hash_to_scalar(tagged_hash(b"MuSig/noncecoef", &[
+            &compressed_bytes(&aggregate_r1),
+            &compressed_bytes(&aggregate_r2),
+            &key_agg.x_only_public_key(),
+            message,
+        ]));
+        let mut final_nonce = aggregate_r1 + aggregate_r2 * &nonce_coefficient;
+        if final_nonce.is_zero() {
+            final_nonce = Point::generator().to_point();
+        }
+        let challenge = challenge(&x_bytes(&final_nonce), &key_agg.x_only_public_key(), message);
+        SigningSession {
+            key_agg: key_agg.clone(),
+            public_nonces,
+            nonce_coefficient,
+            final_nonce,
+            challenge,
+        }
+    }
+
+    /// s = k_1 + b * k_2 + e * a * x, with the nonces negated if R has an odd y and the key if Q has
+    fn partial_sign(&self, secret_nonce: SecretNonce, key_pair: &MuSig2KeyPair) -> Result<Scalar<Secp256k1>, ProofError> {
+        if secret_nonce.public_key != key_pair.public_key || !self.key_agg.public_keys.contains(&key_pair.public_key) {
+            return Err(ProofError::InvalidWitness);
+        }
+        let (k1, k2) = if has_even_y(&self.final_nonce) {
+            (secret_nonce.k1, secret_nonce.k2)
+        } else {
+            (-secret_nonce.k1, -secret_nonce.k2)
+        };
+        let d = self.key_sign() * &key_pair.secret_key;
+        let a = self.key_agg.coefficient(&key_pair.public_key);
+        Ok(k1 + &self.nonce_coefficient * k2 + &self.challenge * a * d)
+    }
+
+    /// s_i * G == R_i1 + b * R_i2 + e * a_i * P_i, with the signs of [SigningSession::partial_sign]
+    pub fn verify_partial_signature(
+        &self,
+        partial_signature: &Scalar<Secp256k1>,
+        public_nonce: &PublicNonce,
+        public_key: &Point<Secp256k1>,
+    ) -> Result<(), ProtocolError> {
+        let mut nonce = &public_nonce.r1 + &public_nonce.r2 * &self.nonce_coefficient;
+        if !has_even_y(&self.final_nonce) {
+            nonce = -nonce;
+        }
+        let key_term = public_key * (self.key_sign() * &self.challenge * self.key_agg.coefficient(public_key));
+        if Point::generator() * partial_signature != nonce + key_term {
+            return Err(ProofError::InvalidResponse.into());
+        }
+        Ok(())
+    }
+
+    /// Checks both partial signatures and adds them up
+    pub fn aggregate(
+        &self,
+        party_one_second_message: &Party1SecondMessage,
+        party_two_second_message: &Party2SecondMessage,
+    ) -> Result<SchnorrSignature, ProtocolError> {
+        let partial_signatures = [&party_one_second_message.partial_signature, &party_two_second_message.partial_signature];
+        for (i, partial_signature) in partial_signatures.iter().enumerate() {
+            self.verify_partial_signature(partial_signature, &self.public_nonces[i], &self.key_agg.public_keys[i])?;
+        }
+        Ok(SchnorrSignature {
+            r: x_bytes(&self.final_nonce),
+            s: partial_signatures[0] + partial_signatures[1],
+        })
+    }
+
+    /// 1 if Q has an even y, -1 otherwise: x(Q) stands for the even one of Q and -Q
+    fn key_sign(&self) -> Scalar<Secp256k1> {
+        if has_even_y(&self.key_agg.aggregate_key) { Scalar::from(1) } else { -Scalar::from(1) }
+    }
+}
+
+impl Party1SecondMessage {
+    pub fn second(secret_nonce: SecretNonce, key_pair: &MuSig2KeyPair, session: &SigningSession) -> Result<Party1SecondMessage, ProtocolError> {
+        let partial_signature = session.partial_sign(secret_nonce, key_pair)?;
+        Ok(Party1SecondMessage { partial_signature })
+    }
+}
+
+impl Party2SecondMessage {
+    pub fn second(secret_nonce: SecretNonce, key_pair: &MuSig2KeyPair, session: &SigningSession) -> Result<Party2SecondMessage, ProtocolError> {
+        let partial_signature = session.partial_sign(secret_nonce, key_pair)?;
+        Ok(Party2SecondMessage { partial_signature })
+    }
+}
+
+impl SchnorrSignature {
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.r);
+        bytes[32..].copy_from_slice(&self.s.to_bytes());
+        bytes
+    }
+
+    /// Plain BIP340 verification against an x-only public key
+    pub fn verify(&self, x_only_public_key: &[u8; 32], message: &[u8]) -> Result<(), ProtocolError> {
+        let public_key = lift_x(x_only_public_key)?;
+        let e = challenge(&self.r, x_only_public_key, message);
+        let r_point = Point::generator() * &self.s - public_key * e;
+        if r_point.is_zero() || !has_even_y(&r_point) || x_bytes(&r_point) != self.r {
+            return Err(ProtocolError::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+/// e = H_BIP0340/challenge(x(R) || x(Q) || m)
+fn challenge(r: &[u8; 32], x_only_public_key: &[u8; 32], message: &[u8]) -> Scalar<Secp256k1> {
+    hash_to_scalar(tagged_hash(b"BIP0340/challenge", &[r, x_only_public_key, message]))
+}
+
+/// SHA256(SHA256(tag) || SHA256(tag) || chunks)
+fn tagged_hash(tag: &[u8], chunks: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    let mut hasher = Sha256::new().chain(tag_hash).chain(tag_hash);
+    for chunk in chunks {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+fn hash_to_scalar(hash: [u8; 32]) -> Scalar<Secp256k1> {
+    Scalar::from_bigint(&BigInt::from_bytes(&hash))
+}
+
+fn has_even_y(point: &Point<Secp256k1>) -> bool {
+    point.y_coord().is_some_and(|y| !y.test_bit(0))
+}
+
+fn x_bytes(point: &Point<Secp256k1>) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&point.to_bytes(true)[1..]);
+    bytes
+}
+
+/// 33 bytes, all zero for the identity
+fn compressed_bytes(point: &Point<Secp256k1>) -> Vec<u8> {
+    if point.is_zero() {
+        return vec![0u8; 33];
+    }
+    point.to_bytes(true).to_vec()
+}
+
+/// The point with x coordinate x and an even y
+fn lift_x(x: &[u8; 32]) -> Result<Point<Secp256k1>, ProofError> {
+    let mut bytes = [2u8; 33];
+    bytes[1..].copy_from_slice(x);
+    Point::from_bytes(&bytes).map_err(|_| ProofError::MalformedPoint)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Point, Scalar};
+    use rand::RngCore;
+    use secp256k1::schnorr::Signature as Bip340Signature;
+    use secp256k1::{KeyPair, Message, XOnlyPublicKey};
+
+    use crate::cryptology_primitives::errors::{ProofError, ProtocolError};
+    use crate::cryptology_primitives::twoparty::musig2::{
+        KeyAggContext, MuSig2KeyPair, Party1FirstMessage, Party1SecondMessage, Party2FirstMessage, Party2SecondMessage,
+        SchnorrSignature, SigningSession,
+    };
+
+    fn sign(party_one: &MuSig2KeyPair, party_two: &MuSig2KeyPair, message: &[u8]) -> (KeyAggContext, SchnorrSignature) {
+        let key_agg = KeyAggContext::new([party_one.public_key.clone(), party_two.public_key.clone()]).unwrap();
+        let (p1_first_msg, p1_secret_nonce) = Party1FirstMessage::first(party_one, &key_agg, message);
+        let (p2_first_msg, p2_secret_nonce) = Party2FirstMessage::first(party_two, &key_agg, message);
+
+        let p1_session = SigningSession::new(&key_agg, &p1_first_msg, &p2_first_msg, message);
+        let p2_session = SigningSession::new(&key_agg, &p1_first_msg, &p2_first_msg, message);
+        let p1_second_msg = Party1SecondMessage::second(p1_secret_nonce, party_one, &p1_session).unwrap();
+        let p2_second_msg = Party2SecondMessage::second(p2_secret_nonce, party_two, &p2_session).unwrap();
+
+        let signature = p1_session.aggregate(&p1_second_msg, &p2_second_msg).unwrap();
+        assert_eq!(p2_session.aggregate(&p1_second_msg, &p2_second_msg).unwrap(), signature);
+        (key_agg, signature)
+    }
+
+    #[test]
+    fn test_musig2_verifies_with_bip340() {
+        let secp = secp256k1::Secp256k1::verification_only();
+        // enough runs that Q and R both come out with odd y now and then
+        for _ in 0..8 {
+            let mut message = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut message);
+            let (key_agg, signature) = sign(&MuSig2KeyPair::new(), &MuSig2KeyPair::new(), &message);
+
+            let x_only_public_key = key_agg.x_only_public_key();
+            assert!(signature.verify(&x_only_public_key, &message).is_ok());
+
+            let public_key = XOnlyPublicKey::from_slice(&x_only_public_key).unwrap();
+            let bip340_signature = Bip340Signature::from_slice(&signature.to_bytes()).unwrap();
+            assert!(secp.verify_schnorr(&bip340_signature, &Message::from_slice(&message).unwrap(), &public_key).is_ok());
+
+            message[0] ^= 1;
+            assert_eq!(signature.verify(&x_only_public_key, &message), Err(ProtocolError::InvalidSignature));
+            assert!(secp.verify_schnorr(&bip340_signature, &Message::from_slice(&message).unwrap(), &public_key).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verifier_accepts_single_signer_bip340() {
+        // the verifier used above agrees with libsecp256k1's signer
+        let secp = secp256k1::Secp256k1::new();
+        for _ in 0..8 {
+            let key_pair = KeyPair::new(&secp, &mut rand::thread_rng());
+            let message = Message::from_slice(&[7u8; 32]).unwrap();
+            let signature = secp.sign_schnorr(&message, &key_pair);
+            let bytes = signature.as_ref();
+            let parsed = SchnorrSignature {
+                r: bytes[..32].try_into().unwrap(),
+                s: Scalar::from_bytes(&bytes[32..]).unwrap(),
+            };
+            let x_only_public_key = key_pair.x_only_public_key().0.serialize();
+            assert!(parsed.verify(&x_only_public_key, &[7u8; 32]).is_ok());
+            assert!(parsed.verify(&x_only_public_key, &[8u8; 32]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_key_aggregation() {
+        let party_one = MuSig2KeyPair::new();
+        let party_two = MuSig2KeyPair::new();
+        let key_agg = KeyAggContext::new([party_one.public_key.clone(), party_two.public_key.clone()]).unwrap();
+        let swapped = KeyAggContext::new([party_two.public_key.clone(), party_one.public_key.clone()]).unwrap();
+        assert_ne!(key_agg.aggregate_key, swapped.aggregate_key);
+        // not the plain sum, which the owner of the second key could cancel out with P_2 = P - P_1
+        assert_ne!(key_agg.aggregate_key, &party_one.public_key + &party_two.public_key);
+
+        // both parties holding the same key still works
+        let (key_agg, signature) = sign(&party_one, &party_one.clone(), b"same key");
+        assert!(signature.verify(&key_agg.x_only_public_key(), b"same key").is_ok());
+
+        assert_eq!(
+            KeyAggContext::new([Point::zero(), party_two.public_key]).err(),
+            Some(ProtocolError::Proof(ProofError::IdentityPublicKey))
+        );
+    }
+
+    #[test]
+    fn test_musig2_rejects_bad_partial_signatures() {
+        let party_one = MuSig2KeyPair::new();
+        let party_two = MuSig2KeyPair::new();
+        let message = b"message";
+        let key_agg = KeyAggContext::new([party_one.public_key.clone(), party_two.public_key.clone()]).unwrap();
+        let (p1_first_msg, p1_secret_nonce) = Party1FirstMessage::first(&party_one, &key_agg, message);
+        let (p2_first_msg, p2_secret_nonce) = Party2FirstMessage::first(&party_two, &key_agg, message);
+        let session = SigningSession::new(&key_agg, &p1_first_msg, &p2_first_msg, message);
+
+        // a nonce made for party two's key can't be used with another key
+        let outsider = MuSig2KeyPair::new();
+        let (_, outsider_nonce) = Party2FirstMessage::first(&outsider, &key_agg, message);
+        assert_eq!(Party2SecondMessage::second(outsider_nonce, &outsider, &session).err(), Some(ProtocolError::Proof(ProofError::InvalidWitness)));
+        let (_, other_nonce) = Party2FirstMessage::first(&party_two, &key_agg, message);
+        assert_eq!(Party2SecondMessage::second(other_nonce, &party_one, &session).err(), Some(ProtocolError::Proof(ProofError::InvalidWitness)));
+
+        let p1_second_msg = Party1SecondMessage::second(p1_secret_nonce, &party_one, &session).unwrap();
+        let mut p2_second_msg = Party2SecondMessage::second(p2_secret_nonce, &party_two, &session).unwrap();
+        p2_second_msg.partial_signature = &p2_second_msg.partial_signature + Scalar::from(1);
+        assert_eq!(session.aggregate(&p1_second_msg, &p2_second_msg).err(), Some(ProtocolError::Proof(ProofError::InvalidResponse)));
+
+        // a session over another message gives a signature that doesn't verify for this one
+        let other_session = SigningSession::new(&key_agg, &p1_first_msg, &p2_first_msg, b"other message");
+        let p1_partial = &p1_second_msg.partial_signature;
+        assert_eq!(
+            other_session.verify_partial_signature(p1_partial, &p1_first_msg.public_nonce, &party_one.public_key),
+            Err(ProtocolError::Proof(ProofError::InvalidResponse))
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use std::error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VdfError {
+    /// input, output or proof belong to a different RSA group than the verifier's
+    ModulusMismatch,
+    /// pi^l * x^r != y
+    InvalidProof,
+}
+
+impl Display for VdfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VdfError::ModulusMismatch => write!(f, "vdf: element of a different group"),
+            VdfError::InvalidProof => write!(f, "vdf: proof does not verify"),
+        }
+    }
+}
+
+impl error::Error for VdfError {}
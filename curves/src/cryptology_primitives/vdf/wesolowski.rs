@@ -0,0 +1,195 @@
+//! Wesolowski's verifiable delay function over an RSA group.
+//! Evaluating y = x^(2^t) takes t sequential squarings for anyone who doesn't know the order of
+//! the group, while the proof lets a verifier check y with two small exponentiations.
+//!
+//! The prover gets a prime challenge l = H(N, x, y, t) and sends pi = x^floor(2^t / l),
+//! the verifier computes r = 2^t mod l and checks pi^l * x^r = y.
+//! https://eprint.iacr.org/2018/623.pdf <<Efficient verifiable delay functions>> Wesolowski
+//!
+//! The modulus must come from a setup whose factorization nobody knows, the holder of phi(N)
+//! can compute x^(2^t mod phi(N)) directly.
+
+use std::marker::PhantomData;
+
+use curv::cryptographic_primitives::hashing::Digest;
+use num_traits::One;
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::primes::hash_to_prime;
+use crate::arithmetic::traits::{Converter, Modulo};
+use crate::cryptology_primitives::hidden_order::rsa_group::HiddenOrderGroup;
+use crate::cryptology_primitives::proofs::transcript::Transcript;
+use crate::cryptology_primitives::vdf::errors::VdfError;
+
+const INPUT_SEED_PREFIX: &[u8] = b"wesolowski vdf input";
+const TRANSCRIPT_LABEL: &[u8] = b"wesolowski_vdf";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VdfSolution {
+    pub output: HiddenOrderGroup,
+    pub proof: HiddenOrderGroup,
+}
+
+pub struct WesolowskiVdf<H: Digest + Clone> {
+    pub modulus: NativeBigInt,
+    pub iterations: u64,
+    hash_choice: PhantomData<fn(H)>,
+}
+
+impl<H: Digest + Clone> WesolowskiVdf<H> {
+    pub fn new(modulus: NativeBigInt, iterations: u64) -> WesolowskiVdf<H> {
+        WesolowskiVdf {
+            modulus,
+            iterations,
+            hash_choice: PhantomData,
+        }
+    }
+
+    /// Maps a seed, e.g. the previous beacon output, to the VDF input x
+    pub fn input(&self, seed: &[u8]) -> HiddenOrderGroup {
+        let bytes = [INPUT_SEED_PREFIX, seed].concat();
+        HiddenOrderGroup::hash_to_group::<H>(&self.modulus, &bytes)
+    }
+
+    /// y = x^(2^t) by t sequential squarings
+    pub fn evaluate(&self, x: &HiddenOrderGroup) -> HiddenOrderGroup {
+        let mut y = x.clone();
+        for _ in 0..self.iterations {
+            y = y.op(&y);
+        }
+        y
+    }
+
+    /// pi = x^floor(2^t / l), computed bit by bit with long division so that 2^t is never
+    /// materialized: each step doubles the remainder and emits the next quotient bit.
+    pub fn prove(&self, x: &HiddenOrderGroup, y: &HiddenOrderGroup) -> HiddenOrderGroup {
+        let challenge = self.challenge(x, y);
+        let two = NativeBigInt::from(2u32);
+
+        let mut pi = HiddenOrderGroup::identity(&self.modulus);
+        let mut remainder = NativeBigInt::one();
+        for _ in 0..self.iterations {
+            let doubled = &remainder * &two;
+            pi = pi.op(&pi);
+            if doubled >= challenge {
+                pi = pi.op(x);
+                remainder = doubled - &challenge;
+            } else {
+                remainder = doubled;
+            }
+        }
+        pi
+    }
+
+    /// Evaluates the VDF on the input derived from `seed` and proves the output
+    pub fn solve(&self, seed: &[u8]) -> VdfSolution {
+        let x = self.input(seed);
+        let output = self.evaluate(&x);
+        let proof = self.prove(&x, &output);
+        VdfSolution { output, proof }
+    }
+
+    pub fn verify(&self, x: &HiddenOrderGroup, solution: &VdfSolution) -> Result<(), VdfError> {
+        if x.modulus() != &self.modulus
+            || solution.output.modulus() != &self.modulus
+            || solution.proof.modulus() != &self.modulus {
+            return Err(VdfError::ModulusMismatch);
+        }
+
+        let challenge = self.challenge(x, &solution.output);
+        let r = NativeBigInt::mod_pow(&NativeBigInt::from(2u32), &NativeBigInt::from(self.iterations), &challenge);
+        let expected = solution.proof.exp(&challenge).op(&x.exp(&r));
+
+        if expected == solution.output {
+            Ok(())
+        } else {
+            Err(VdfError::InvalidProof)
+        }
+    }
+
+    pub fn verify_seed(&self, seed: &[u8], solution: &VdfSolution) -> Result<(), VdfError> {
+        self.verify(&self.input(seed), solution)
+    }
+
+    /// Fiat-Shamir challenge, a prime l = H(N, x, y, t)
+    fn challenge(&self, x: &HiddenOrderGroup, y: &HiddenOrderGroup) -> NativeBigInt {
+        let mut transcript = Transcript::<H>::new(TRANSCRIPT_LABEL);
+        transcript.append_bytes(b"modulus", &self.modulus.to_bytes());
+        transcript.append_bytes(b"x", &x.to_bytes());
+        transcript.append_bytes(b"y", &y.to_bytes());
+        transcript.append_u64(b"iterations", self.iterations);
+        hash_to_prime::<H>(&transcript.challenge_bytes(b"challenge", H::output_size()))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::arithmetic::traits::Modulo;
+    use crate::cryptology_primitives::hidden_order::rsa_group::{HiddenOrderGroup, RsaKeyPair};
+    use crate::cryptology_primitives::vdf::errors::VdfError;
+    use crate::cryptology_primitives::vdf::wesolowski::{VdfSolution, WesolowskiVdf};
+
+    type Vdf = WesolowskiVdf<sha3::Keccak256>;
+
+    const ITERATIONS: u64 = 1000;
+
+    #[test]
+    fn test_vdf_evaluate_prove_verify() {
+        let key_pair = RsaKeyPair::generate(512);
+        let vdf = Vdf::new(key_pair.modulus.clone(), ITERATIONS);
+
+        let solution = vdf.solve(b"previous beacon output");
+        assert!(vdf.verify_seed(b"previous beacon output", &solution).is_ok());
+
+        // the factorization is a trapdoor: x^(2^t mod phi(N)) skips the sequential squarings
+        let x = vdf.input(b"previous beacon output");
+        let shortcut = NativeBigInt::mod_pow(&NativeBigInt::from(2u32), &NativeBigInt::from(ITERATIONS), &key_pair.phi());
+        assert_eq!(x.exp(&shortcut), solution.output);
+    }
+
+    #[test]
+    fn test_vdf_rejects_tampered_output() {
+        let key_pair = RsaKeyPair::generate(512);
+        let vdf = Vdf::new(key_pair.modulus.clone(), ITERATIONS);
+        let x = vdf.input(b"seed");
+        let solution = vdf.solve(b"seed");
+
+        let g = HiddenOrderGroup::hash_to_group::<sha3::Keccak256>(&key_pair.modulus, b"tamper");
+        let tampered_output = VdfSolution {
+            output: solution.output.op(&g),
+            proof: solution.proof.clone(),
+        };
+        assert_eq!(vdf.verify(&x, &tampered_output), Err(VdfError::InvalidProof));
+
+        let tampered_proof = VdfSolution {
+            output: solution.output.clone(),
+            proof: solution.proof.op(&g),
+        };
+        assert_eq!(vdf.verify(&x, &tampered_proof), Err(VdfError::InvalidProof));
+
+        // an honest proof for a different output doesn't carry over
+        let wrong_output = x.exp(&NativeBigInt::from(3u32));
+        let forged = VdfSolution {
+            proof: vdf.prove(&x, &solution.output),
+            output: wrong_output,
+        };
+        assert_eq!(vdf.verify(&x, &forged), Err(VdfError::InvalidProof));
+    }
+
+    #[test]
+    fn test_vdf_rejects_wrong_parameters() {
+        let key_pair = RsaKeyPair::generate(512);
+        let vdf = Vdf::new(key_pair.modulus.clone(), ITERATIONS);
+        let solution = vdf.solve(b"seed");
+
+        assert_eq!(vdf.verify_seed(b"other seed", &solution), Err(VdfError::InvalidProof));
+
+        let fewer_iterations = Vdf::new(key_pair.modulus.clone(), ITERATIONS - 1);
+        assert_eq!(fewer_iterations.verify_seed(b"seed", &solution), Err(VdfError::InvalidProof));
+
+        let other_group = Vdf::new(RsaKeyPair::generate(512).modulus.clone(), ITERATIONS);
+        assert_eq!(other_group.verify_seed(b"seed", &solution), Err(VdfError::ModulusMismatch));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod msm;
+pub mod per_curve;
+pub mod weierstrass;
@@ -0,0 +1,186 @@
+//! Multi-scalar multiplication sum(k_i * P_i) with Pippenger's bucket method.
+//!
+//! Scalars are cut into windows of c bits. For each window, every point is added once into the
+//! bucket of its digit, and the buckets are combined with a running sum, so a window costs about
+//! n + 2^(c+1) additions instead of n scalar multiplications. Not constant time, only for public
+//! values such as proof verification.
+//!
+//! The buckets only pay off with cheap additions. curv adds secp256k1 points in affine
+//! coordinates, one field inversion per addition, so secp256k1 runs on k256's projective points
+//! and Ed25519 on curve25519-dalek's multiscalar multiplication. Other curves use curv's points.
+
+use curv::arithmetic::Converter;
+use curv::elliptic::curves::{Curve, Ed25519, Point, Scalar, Secp256k1};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::traits::VartimeMultiscalarMul;
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+
+pub fn multi_scalar_mul<E: Curve>(scalars: &[Scalar<E>], points: &[Point<E>]) -> Point<E> {
+    assert_eq!(scalars.len(), points.len(), "one scalar per point");
+    if scalars.is_empty() {
+        return Point::zero();
+    }
+
+    match E::CURVE_NAME {
+        Secp256k1::CURVE_NAME => secp256k1_multi_scalar_mul(scalars, points),
+        Ed25519::CURVE_NAME => ed25519_multi_scalar_mul(scalars, points),
+        _ => {
+            let scalars: Vec<_> = scalars.iter().map(little_endian_bytes).collect();
+            pippenger(&scalars, points)
+        }
+    }
+}
+
+fn secp256k1_multi_scalar_mul<E: Curve>(scalars: &[Scalar<E>], points: &[Point<E>]) -> Point<E> {
+    let (scalars, points): (Vec<_>, Vec<_>) = scalars.iter()
+        .zip(points)
+        .filter(|(_, point)| !point.is_zero())
+        .map(|(k, point)| {
+            let encoded = k256::EncodedPoint::from_bytes(&*point.to_bytes(false)).expect("curv encodes valid points");
+            let point = Option::<k256::AffinePoint>::from(k256::AffinePoint::from_encoded_point(&encoded))
+                .expect("curv points are on the curve");
+            (little_endian_bytes(k), k256::ProjectivePoint::from(point))
+        })
+        .unzip();
+    if scalars.is_empty() {
+        return Point::zero();
+    }
+
+    let sum = pippenger(&scalars, &points);
+    if sum == k256::ProjectivePoint::IDENTITY {
+        return Point::zero();
+    }
+    Point::from_bytes(sum.to_affine().to_encoded_point(false).as_bytes()).expect("the sum of secp256k1 points")
+}
+
+fn ed25519_multi_scalar_mul<E: Curve>(scalars: &[Scalar<E>], points: &[Point<E>]) -> Point<E> {
+    let scalars = scalars.iter().map(|k| {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&little_endian_bytes(k)[..32]);
+        curve25519_dalek::scalar::Scalar::from_bytes_mod_order(bytes)
+    });
+    let points = points.iter().map(|point| {
+        CompressedEdwardsY::from_slice(&point.to_bytes(true)).decompress().expect("curv points are on the curve")
+    });
+    let sum = curve25519_dalek::edwards::EdwardsPoint::vartime_multiscalar_mul(scalars, points);
+    Point::from_bytes(sum.compress().as_bytes()).expect("the sum of subgroup points")
+}
+
+/// Little endian, padded to a whole number of 64-bit limbs
+fn little_endian_bytes<E: Curve>(k: &Scalar<E>) -> Vec<u8> {
+    let mut bytes = k.to_bigint().to_bytes();
+    bytes.reverse();
+    bytes.resize(bytes.len().div_ceil(8).max(4) * 8, 0);
+    bytes
+}
+
+/// The group operations the bucket method needs
+trait BucketPoint: Clone {
+    fn identity() -> Self;
+    fn add(&self, other: &Self) -> Self;
+}
+
+impl<E: Curve> BucketPoint for Point<E> {
+    fn identity() -> Self {
+        Point::zero()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+}
+
+impl BucketPoint for k256::ProjectivePoint {
+    fn identity() -> Self {
+        k256::ProjectivePoint::IDENTITY
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self + other
+    }
+}
+
+/// `scalars` as little endian bytes
+fn pippenger<G: BucketPoint>(scalars: &[Vec<u8>], points: &[G]) -> G {
+    let scalar_bits = scalars.iter().map(|k| k.len() * 8).max().unwrap_or(0);
+    let window = window_size(points.len());
+    let digit = |k: &[u8], window_start: usize| {
+        (0..window)
+            .map(|bit| window_start + bit)
+            .filter(|&bit| bit < k.len() * 8 && (k[bit / 8] >> (bit % 8)) & 1 == 1)
+            .fold(0usize, |digit, bit| digit | (1 << (bit - window_start)))
+    };
+
+    let mut result = G::identity();
+    for window_start in (0..scalar_bits).step_by(window).rev() {
+        for _ in 0..window {
+            result = result.add(&result);
+        }
+
+        let mut buckets = vec![G::identity(); (1 << window) - 1];
+        for (k, point) in scalars.iter().zip(points) {
+            let digit = digit(k, window_start);
+            if digit > 0 {
+                buckets[digit - 1] = buckets[digit - 1].add(point);
+            }
+        }
+
+        // sum(d * bucket_d) as a sum of running sums from the top bucket down
+        let mut running_sum = G::identity();
+        let mut window_sum = G::identity();
+        for bucket in buckets.iter().rev() {
+            running_sum = running_sum.add(bucket);
+            window_sum = window_sum.add(&running_sum);
+        }
+        result = result.add(&window_sum);
+    }
+    result
+}
+
+/// roughly log2(n), the usual trade-off between bucket additions and bucket aggregation
+fn window_size(n: usize) -> usize {
+    match n {
+        0..=3 => 1,
+        4..=31 => 3,
+        32..=255 => 5,
+        _ => (usize::BITS - n.leading_zeros()) as usize - 2,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use curv::elliptic::curves::{Ed25519, Point, Scalar, Secp256k1, Secp256r1, Curve};
+
+    use crate::elliptic::msm::multi_scalar_mul;
+
+    fn msm_matches_naive_sum<E: Curve>(n: usize) {
+        let scalars: Vec<_> = (0..n).map(|_| Scalar::<E>::random()).collect();
+        let points: Vec<_> = (0..n).map(|_| Point::<E>::generator() * Scalar::random()).collect();
+        let naive = scalars.iter().zip(&points)
+            .fold(Point::<E>::zero(), |acc, (k, p)| acc + k * p);
+        assert_eq!(multi_scalar_mul(&scalars, &points), naive);
+    }
+
+    #[test]
+    fn test_multi_scalar_mul() {
+        for n in [0, 1, 2, 5, 40, 300] {
+            msm_matches_naive_sum::<Secp256k1>(n);
+        }
+        msm_matches_naive_sum::<Ed25519>(20);
+        msm_matches_naive_sum::<Secp256r1>(20);
+
+        // zero scalars and the identity point
+        let points = vec![Point::<Secp256k1>::zero(), Point::generator().to_point()];
+        let scalars = vec![Scalar::random(), Scalar::zero()];
+        assert!(multi_scalar_mul(&scalars, &points).is_zero());
+        let points = vec![Point::<Ed25519>::zero(), Point::generator().to_point()];
+        let scalars = vec![Scalar::random(), Scalar::zero()];
+        assert!(multi_scalar_mul(&scalars, &points).is_zero());
+
+        // a sum that cancels
+        let point = Point::<Secp256k1>::generator() * Scalar::random();
+        let k = Scalar::random();
+        assert!(multi_scalar_mul(&[k.clone(), -k], &[point.clone(), point]).is_zero());
+    }
+}
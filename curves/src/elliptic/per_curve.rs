@@ -0,0 +1,69 @@
+//! Values computed once per curve, such as generators hashed to the curve.
+//!
+//! A static inside a generic function is shared by every instantiation, so a `OnceLock<Point<E>>`
+//! can't hold a point for each curve. [PerCurve] keys the values by the curve's type instead.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use curv::elliptic::curves::Curve;
+
+type Values = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+
+pub struct PerCurve {
+    values: OnceLock<Mutex<Values>>,
+}
+
+impl PerCurve {
+    pub const fn new() -> PerCurve {
+        PerCurve { values: OnceLock::new() }
+    }
+
+    /// The value for `E`, computed by `init` on the first call. `init` runs without holding the
+    /// lock, if two threads race the first stored value wins.
+    pub fn get_or_init<E: Curve, T: Clone + Send + Sync + 'static>(&self, init: impl FnOnce() -> T) -> T {
+        let values = self.values.get_or_init(|| Mutex::new(HashMap::new()));
+        let cached = |values: &Values| values.get(&TypeId::of::<E>())
+            .map(|value| value.downcast_ref::<T>().expect("one value type per cache").clone());
+
+        if let Some(value) = cached(&values.lock().unwrap()) {
+            return value;
+        }
+        let value = init();
+        let mut values = values.lock().unwrap();
+        values.entry(TypeId::of::<E>()).or_insert_with(|| Box::new(value));
+        cached(&values).expect("inserted above")
+    }
+}
+
+impl Default for PerCurve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use curv::elliptic::curves::{Ed25519, Secp256k1};
+
+    use crate::elliptic::per_curve::PerCurve;
+
+    #[test]
+    fn test_per_curve() {
+        static NAMES: PerCurve = PerCurve::new();
+        let calls = Cell::new(0);
+        let name = |name: &str| {
+            calls.set(calls.get() + 1);
+            name.to_string()
+        };
+
+        assert_eq!(NAMES.get_or_init::<Secp256k1, _>(|| name("secp256k1")), "secp256k1");
+        assert_eq!(NAMES.get_or_init::<Ed25519, _>(|| name("ed25519")), "ed25519");
+        assert_eq!(NAMES.get_or_init::<Secp256k1, _>(|| name("other")), "secp256k1");
+        assert_eq!(calls.get(), 2);
+    }
+}
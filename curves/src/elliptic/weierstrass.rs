@@ -0,0 +1,551 @@
+//! Short Weierstrass curves y^2 = x^3 + a*x + b over a prime field F_p, with the parameters chosen
+//! at runtime and all arithmetic done with [NativeBigInt].
+//!
+//! This is a teaching tool: tiny curves like y^2 = x^3 + 7 over F_97 have few enough points to
+//! enumerate, so edge cases such as the identity, points of order 2 and P + (-P) can be tested
+//! exhaustively. Nothing here is constant time.
+//!
+//! Most protocols in `cryptology_primitives` are generic over curv's `Curve`, whose parameters are
+//! fixed at compile time. [PrimeOrderGroup] is the setting the discrete log protocols need, a
+//! prime order subgroup with a fixed generator, and
+//! [sigma_dlog_weierstrass](crate::cryptology_primitives::proofs::sigma_dlog_weierstrass) runs the
+//! Schnorr proof over it, so its handling of identity and low order points is tested on every
+//! point of a tiny curve.
+//!
+//! Points are available in affine form and in homogeneous projective form (X : Y : Z) with
+//! x = X/Z, y = Y/Z, which avoids a field inversion per addition.
+//! https://hyperelliptic.org/EFD/g1p/auto-shortw-projective.html
+
+use num_traits::{One, Zero};
+
+use crate::arithmetic::big_native::NativeBigInt;
+use crate::arithmetic::primes::MILLER_RABIN_ROUNDS;
+use crate::arithmetic::traits::{BitManipulation, Converter, Modulo, Primes};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeierstrassCurve {
+    pub p: NativeBigInt,
+    pub a: NativeBigInt,
+    pub b: NativeBigInt,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AffinePoint {
+    Identity,
+    Point { x: NativeBigInt, y: NativeBigInt },
+}
+
+/// The subgroup of prime order `order` generated by `generator`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrimeOrderGroup {
+    pub curve: WeierstrassCurve,
+    pub generator: AffinePoint,
+    pub order: NativeBigInt,
+}
+
+/// (X : Y : Z), the identity is any point with Z = 0
+#[derive(Clone, Debug)]
+pub struct ProjectivePoint {
+    pub x: NativeBigInt,
+    pub y: NativeBigInt,
+    pub z: NativeBigInt,
+}
+
+impl WeierstrassCurve {
+    /// Returns None if p is not prime or the curve is singular, 4a^3 + 27b^2 = 0 (mod p)
+    pub fn new(p: NativeBigInt, a: NativeBigInt, b: NativeBigInt) -> Option<WeierstrassCurve> {
+        if p <= NativeBigInt::from(3u32) || !p.is_probable_prime(MILLER_RABIN_ROUNDS) {
+            return None;
+        }
+        let curve = WeierstrassCurve {
+            a: a.modulus(&p),
+            b: b.modulus(&p),
+            p,
+        };
+        let a_cubed = curve.mul(&curve.mul(&curve.a, &curve.a), &curve.a);
+        let b_squared = curve.mul(&curve.b, &curve.b);
+        let discriminant = curve.add_fe(
+            &curve.mul(&NativeBigInt::from(4u32), &a_cubed),
+            &curve.mul(&NativeBigInt::from(27u32), &b_squared),
+        );
+        if discriminant.is_zero() {
+            None
+        } else {
+            Some(curve)
+        }
+    }
+
+    /// Returns None if (x, y) is not on the curve
+    pub fn point(&self, x: &NativeBigInt, y: &NativeBigInt) -> Option<AffinePoint> {
+        let point = AffinePoint::Point {
+            x: x.modulus(&self.p),
+            y: y.modulus(&self.p),
+        };
+        if self.is_on_curve(&point) {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_on_curve(&self, point: &AffinePoint) -> bool {
+        match point {
+            AffinePoint::Identity => true,
+            AffinePoint::Point { x, y } => {
+                let zero = NativeBigInt::zero();
+                x >= &zero && y >= &zero && x < &self.p && y < &self.p && self.mul(y, y) == self.rhs(x)
+            }
+        }
+    }
+
+    /// 0x00 for the identity, 0x04 || x || y with coordinates as wide as p otherwise
+    pub fn encode_point(&self, point: &AffinePoint) -> Vec<u8> {
+        match point {
+            AffinePoint::Identity => vec![0],
+            AffinePoint::Point { x, y } => {
+                let len = self.p.bit_length().div_ceil(8);
+                let mut bytes = vec![4u8];
+                for coordinate in [x, y] {
+                    let coordinate = coordinate.to_bytes();
+                    bytes.extend(std::iter::repeat_n(0u8, len - coordinate.len()));
+                    bytes.extend(coordinate);
+                }
+                bytes
+            }
+        }
+    }
+
+    /// Every point of the curve, identity first. Only meant for tiny fields.
+    pub fn points(&self) -> Vec<AffinePoint> {
+        let mut points = vec![AffinePoint::Identity];
+        let mut x = NativeBigInt::zero();
+        while x < self.p {
+            let rhs = self.rhs(&x);
+            let mut y = NativeBigInt::zero();
+            while y < self.p {
+                if self.mul(&y, &y) == rhs {
+                    points.push(AffinePoint::Point { x: x.clone(), y: y.clone() });
+                }
+                y = y + NativeBigInt::one();
+            }
+            x = x + NativeBigInt::one();
+        }
+        points
+    }
+
+    /// The smallest n > 0 with n*P = O, found by repeated addition. Only meant for tiny fields.
+    pub fn order_of(&self, point: &AffinePoint) -> NativeBigInt {
+        let mut order = NativeBigInt::one();
+        let mut multiple = point.clone();
+        while multiple != AffinePoint::Identity {
+            multiple = self.add(&multiple, point);
+            order = order + NativeBigInt::one();
+        }
+        order
+    }
+
+    pub fn negate(&self, point: &AffinePoint) -> AffinePoint {
+        match point {
+            AffinePoint::Identity => AffinePoint::Identity,
+            AffinePoint::Point { x, y } => AffinePoint::Point {
+                x: x.clone(),
+                y: self.neg(y),
+            },
+        }
+    }
+
+    /// Chord-and-tangent addition
+    pub fn add(&self, lhs: &AffinePoint, rhs: &AffinePoint) -> AffinePoint {
+        let (x1, y1, x2, y2) = match (lhs, rhs) {
+            (AffinePoint::Identity, _) => return rhs.clone(),
+            (_, AffinePoint::Identity) => return lhs.clone(),
+            (AffinePoint::Point { x: x1, y: y1 }, AffinePoint::Point { x: x2, y: y2 }) => (x1, y1, x2, y2),
+        };
+        if x1 == x2 {
+            // P + P, or P + (-P) = O
+            return if y1 == y2 {
+                self.double(lhs)
+            } else {
+                AffinePoint::Identity
+            };
+        }
+        let slope = self.div(&self.sub(y2, y1), &self.sub(x2, x1));
+        self.line_intersection(&slope, x1, y1, x2)
+    }
+
+    pub fn double(&self, point: &AffinePoint) -> AffinePoint {
+        let (x, y) = match point {
+            AffinePoint::Identity => return AffinePoint::Identity,
+            AffinePoint::Point { x, y } => (x, y),
+        };
+        // the tangent at a point of order 2 is vertical
+        if y.is_zero() {
+            return AffinePoint::Identity;
+        }
+        let numerator = self.add_fe(&self.mul(&NativeBigInt::from(3u32), &self.mul(x, x)), &self.a);
+        let slope = self.div(&numerator, &self.mul(&NativeBigInt::from(2u32), y));
+        self.line_intersection(&slope, x, y, x)
+    }
+
+    /// Double-and-add, k is reduced to a non negative value first and P may have any order
+    pub fn scalar_mul(&self, k: &NativeBigInt, point: &AffinePoint) -> AffinePoint {
+        let (k, point) = Self::normalize_scalar(k, point, |p| self.negate(p));
+        let mut result = AffinePoint::Identity;
+        for bit in (0..k.bit_length()).rev() {
+            result = self.double(&result);
+            if k.test_bit(bit) {
+                result = self.add(&result, &point);
+            }
+        }
+        result
+    }
+
+    pub fn to_projective(&self, point: &AffinePoint) -> ProjectivePoint {
+        match point {
+            AffinePoint::Identity => ProjectivePoint {
+                x: NativeBigInt::zero(),
+                y: NativeBigInt::one(),
+                z: NativeBigInt::zero(),
+            },
+            AffinePoint::Point { x, y } => ProjectivePoint {
+                x: x.clone(),
+                y: y.clone(),
+                z: NativeBigInt::one(),
+            },
+        }
+    }
+
+    pub fn to_affine(&self, point: &ProjectivePoint) -> AffinePoint {
+        if point.z.is_zero() {
+            return AffinePoint::Identity;
+        }
+        let z_inv = NativeBigInt::mod_inv(&point.z, &self.p).expect("p is prime");
+        AffinePoint::Point {
+            x: self.mul(&point.x, &z_inv),
+            y: self.mul(&point.y, &z_inv),
+        }
+    }
+
+    /// Y^2 * Z = X^3 + a*X*Z^2 + b*Z^3, the identity (0 : 1 : 0) is the only point with Z = 0
+    pub fn is_on_curve_projective(&self, point: &ProjectivePoint) -> bool {
+        let (x, y, z) = (&point.x, &point.y, &point.z);
+        if z.is_zero() {
+            return x.is_zero() && !y.is_zero();
+        }
+        let zz = self.mul(z, z);
+        let lhs = self.mul(&self.mul(y, y), z);
+        let rhs = self.add_fe(
+            &self.add_fe(&self.mul(&self.mul(x, x), x), &self.mul(&self.mul(&self.a, x), &zz)),
+            &self.mul(&self.b, &self.mul(&zz, z)),
+        );
+        lhs == rhs
+    }
+
+    /// X1*Z2 = X2*Z1 and Y1*Z2 = Y2*Z1
+    pub fn eq_projective(&self, lhs: &ProjectivePoint, rhs: &ProjectivePoint) -> bool {
+        match (lhs.z.is_zero(), rhs.z.is_zero()) {
+            (true, true) => true,
+            (false, false) => {
+                self.mul(&lhs.x, &rhs.z) == self.mul(&rhs.x, &lhs.z)
+                    && self.mul(&lhs.y, &rhs.z) == self.mul(&rhs.y, &lhs.z)
+            }
+            _ => false,
+        }
+    }
+
+    /// add-1998-cmo-2, with the exceptional cases P = O, Q = O, P = Q and P = -Q handled first
+    pub fn add_projective(&self, lhs: &ProjectivePoint, rhs: &ProjectivePoint) -> ProjectivePoint {
+        if lhs.z.is_zero() {
+            return rhs.clone();
+        }
+        if rhs.z.is_zero() {
+            return lhs.clone();
+        }
+        let (x1, y1, z1) = (&lhs.x, &lhs.y, &lhs.z);
+        let (x2, y2, z2) = (&rhs.x, &rhs.y, &rhs.z);
+
+        let y1z2 = self.mul(y1, z2);
+        let x1z2 = self.mul(x1, z2);
+        let z1z2 = self.mul(z1, z2);
+        let u = self.sub(&self.mul(y2, z1), &y1z2);
+        let v = self.sub(&self.mul(x2, z1), &x1z2);
+        if v.is_zero() {
+            return if u.is_zero() {
+                self.double_projective(lhs)
+            } else {
+                self.to_projective(&AffinePoint::Identity)
+            };
+        }
+        let uu = self.mul(&u, &u);
+        let vv = self.mul(&v, &v);
+        let vvv = self.mul(&v, &vv);
+        let r = self.mul(&vv, &x1z2);
+        let a = self.sub(
+            &self.sub(&self.mul(&uu, &z1z2), &vvv),
+            &self.mul(&NativeBigInt::from(2u32), &r),
+        );
+        ProjectivePoint {
+            x: self.mul(&v, &a),
+            y: self.sub(&self.mul(&u, &self.sub(&r, &a)), &self.mul(&vvv, &y1z2)),
+            z: self.mul(&vvv, &z1z2),
+        }
+    }
+
+    /// dbl-2007-bl
+    pub fn double_projective(&self, point: &ProjectivePoint) -> ProjectivePoint {
+        if point.z.is_zero() || point.y.is_zero() {
+            return self.to_projective(&AffinePoint::Identity);
+        }
+        let (x1, y1, z1) = (&point.x, &point.y, &point.z);
+        let two = NativeBigInt::from(2u32);
+
+        let xx = self.mul(x1, x1);
+        let zz = self.mul(z1, z1);
+        let w = self.add_fe(&self.mul(&self.a, &zz), &self.mul(&NativeBigInt::from(3u32), &xx));
+        let s = self.mul(&two, &self.mul(y1, z1));
+        let ss = self.mul(&s, &s);
+        let sss = self.mul(&s, &ss);
+        let r = self.mul(y1, &s);
+        let rr = self.mul(&r, &r);
+        let x1_plus_r = self.add_fe(x1, &r);
+        let b = self.sub(&self.sub(&self.mul(&x1_plus_r, &x1_plus_r), &xx), &rr);
+        let h = self.sub(&self.mul(&w, &w), &self.mul(&two, &b));
+        ProjectivePoint {
+            x: self.mul(&h, &s),
+            y: self.sub(&self.mul(&w, &self.sub(&b, &h)), &self.mul(&two, &rr)),
+            z: sss,
+        }
+    }
+
+    pub fn scalar_mul_projective(&self, k: &NativeBigInt, point: &ProjectivePoint) -> ProjectivePoint {
+        let (k, point) = Self::normalize_scalar(k, point, |p| ProjectivePoint {
+            x: p.x.clone(),
+            y: self.neg(&p.y),
+            z: p.z.clone(),
+        });
+        let mut result = self.to_projective(&AffinePoint::Identity);
+        for bit in (0..k.bit_length()).rev() {
+            result = self.double_projective(&result);
+            if k.test_bit(bit) {
+                result = self.add_projective(&result, &point);
+            }
+        }
+        result
+    }
+
+    /// a negative k multiplies -P by |k|
+    fn normalize_scalar<P: Clone>(k: &NativeBigInt, point: &P, negate: impl Fn(&P) -> P) -> (NativeBigInt, P) {
+        if k < &NativeBigInt::zero() {
+            (NativeBigInt::zero() - k, negate(point))
+        } else {
+            (k.clone(), point.clone())
+        }
+    }
+
+    /// x3 = slope^2 - x1 - x2, y3 = slope * (x1 - x3) - y1
+    fn line_intersection(&self, slope: &NativeBigInt, x1: &NativeBigInt, y1: &NativeBigInt, x2: &NativeBigInt) -> AffinePoint {
+        let x3 = self.sub(&self.sub(&self.mul(slope, slope), x1), x2);
+        let y3 = self.sub(&self.mul(slope, &self.sub(x1, &x3)), y1);
+        AffinePoint::Point { x: x3, y: y3 }
+    }
+
+    /// x^3 + a*x + b
+    fn rhs(&self, x: &NativeBigInt) -> NativeBigInt {
+        let x_cubed = self.mul(&self.mul(x, x), x);
+        self.add_fe(&self.add_fe(&x_cubed, &self.mul(&self.a, x)), &self.b)
+    }
+
+    fn add_fe(&self, a: &NativeBigInt, b: &NativeBigInt) -> NativeBigInt {
+        NativeBigInt::mod_add(a, b, &self.p)
+    }
+
+    fn sub(&self, a: &NativeBigInt, b: &NativeBigInt) -> NativeBigInt {
+        NativeBigInt::mod_sub(a, b, &self.p)
+    }
+
+    fn mul(&self, a: &NativeBigInt, b: &NativeBigInt) -> NativeBigInt {
+        NativeBigInt::mod_mul(a, b, &self.p)
+    }
+
+    fn neg(&self, a: &NativeBigInt) -> NativeBigInt {
+        NativeBigInt::mod_sub(&NativeBigInt::zero(), a, &self.p)
+    }
+
+    fn div(&self, a: &NativeBigInt, b: &NativeBigInt) -> NativeBigInt {
+        let b_inv = NativeBigInt::mod_inv(b, &self.p).expect("division by zero in F_p");
+        self.mul(a, &b_inv)
+    }
+}
+
+impl PrimeOrderGroup {
+    /// Returns None unless `generator` is a point of the curve other than the identity, `order` is
+    /// prime and order * generator = O
+    pub fn new(curve: WeierstrassCurve, generator: AffinePoint, order: NativeBigInt) -> Option<PrimeOrderGroup> {
+        if generator == AffinePoint::Identity || !curve.is_on_curve(&generator) || !order.is_probable_prime(MILLER_RABIN_ROUNDS) {
+            return None;
+        }
+        if curve.scalar_mul(&order, &generator) != AffinePoint::Identity {
+            return None;
+        }
+        Some(PrimeOrderGroup { curve, generator, order })
+    }
+
+    /// On the curve and in the subgroup; the identity is in every subgroup
+    pub fn contains(&self, point: &AffinePoint) -> bool {
+        self.curve.is_on_curve(point) && self.curve.scalar_mul(&self.order, point) == AffinePoint::Identity
+    }
+
+    /// k * generator
+    pub fn mul_generator(&self, k: &NativeBigInt) -> AffinePoint {
+        self.curve.scalar_mul(k, &self.generator)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use num_traits::Zero;
+
+    use crate::arithmetic::big_native::NativeBigInt;
+    use crate::elliptic::weierstrass::{AffinePoint, PrimeOrderGroup, WeierstrassCurve};
+
+    /// y^2 = x^3 + 7 over F_97, the secp256k1 equation on a tiny field
+    fn tiny_secp() -> WeierstrassCurve {
+        WeierstrassCurve::new(NativeBigInt::from(97u32), NativeBigInt::from(0u32), NativeBigInt::from(7u32)).unwrap()
+    }
+
+    /// y^2 = x^3 + 2x + 3 over F_97, a != 0 and has a point of order 2
+    fn tiny_curve_with_a() -> WeierstrassCurve {
+        WeierstrassCurve::new(NativeBigInt::from(97u32), NativeBigInt::from(2u32), NativeBigInt::from(3u32)).unwrap()
+    }
+
+    #[test]
+    fn test_curve_parameters() {
+        // y^2 = x^3 - 3x + 2 is singular
+        assert!(WeierstrassCurve::new(NativeBigInt::from(97u32), NativeBigInt::from(-3i32), NativeBigInt::from(2u32)).is_none());
+        // F_91 is not a field
+        assert!(WeierstrassCurve::new(NativeBigInt::from(91u32), NativeBigInt::from(0u32), NativeBigInt::from(7u32)).is_none());
+
+        let curve = tiny_secp();
+        assert!(curve.point(&NativeBigInt::from(1u32), &NativeBigInt::from(28u32)).is_some());
+        assert!(curve.point(&NativeBigInt::from(1u32), &NativeBigInt::from(29u32)).is_none());
+    }
+
+    #[test]
+    fn test_group_law_exhaustively() {
+        for curve in [tiny_secp(), tiny_curve_with_a()] {
+            let points = curve.points();
+            let order = NativeBigInt::from(points.len() as u64);
+            // Hasse bound, |#E - (p + 1)| <= 2 sqrt(p) < 20 for p = 97
+            assert!(points.len().abs_diff(98) <= 19);
+
+            for p in &points {
+                assert!(curve.is_on_curve(p));
+                assert_eq!(curve.add(p, &AffinePoint::Identity), *p);
+                assert_eq!(curve.add(p, &curve.negate(p)), AffinePoint::Identity);
+                assert_eq!(curve.double(p), curve.add(p, p));
+                assert_eq!(curve.scalar_mul(&order, p), AffinePoint::Identity);
+                assert!(order.clone() % curve.order_of(p) == NativeBigInt::zero());
+
+                for q in &points {
+                    let sum = curve.add(p, q);
+                    assert!(curve.is_on_curve(&sum));
+                    assert_eq!(sum, curve.add(q, p));
+                }
+            }
+
+            // associativity on a slice of the points
+            for p in points.iter().take(10) {
+                for q in points.iter().skip(10).take(10) {
+                    for r in points.iter().skip(20).take(10) {
+                        assert_eq!(
+                            curve.add(&curve.add(p, q), r),
+                            curve.add(p, &curve.add(q, r))
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_low_order_points() {
+        let curve = tiny_curve_with_a();
+        let order_two: Vec<_> = curve.points().into_iter()
+            .filter(|p| matches!(p, AffinePoint::Point { y, .. } if y.is_zero()))
+            .collect();
+        assert!(!order_two.is_empty());
+        for p in &order_two {
+            assert_eq!(curve.order_of(p), NativeBigInt::from(2u32));
+            assert_eq!(curve.double(p), AffinePoint::Identity);
+            assert_eq!(curve.negate(p), *p);
+            let projective = curve.to_projective(p);
+            assert_eq!(curve.to_affine(&curve.double_projective(&projective)), AffinePoint::Identity);
+        }
+    }
+
+    #[test]
+    fn test_projective_matches_affine() {
+        for curve in [tiny_secp(), tiny_curve_with_a()] {
+            let points = curve.points();
+            for p in &points {
+                let p_proj = curve.to_projective(p);
+                assert!(curve.is_on_curve_projective(&p_proj));
+                assert_eq!(curve.to_affine(&curve.double_projective(&p_proj)), curve.double(p));
+
+                for q in &points {
+                    let q_proj = curve.to_projective(q);
+                    let sum = curve.add_projective(&p_proj, &q_proj);
+                    assert!(curve.is_on_curve_projective(&sum));
+                    assert_eq!(curve.to_affine(&sum), curve.add(p, q));
+                }
+
+                for k in -5i32..25 {
+                    let k = NativeBigInt::from(k);
+                    let affine = curve.scalar_mul(&k, p);
+                    let projective = curve.scalar_mul_projective(&k, &p_proj);
+                    assert!(curve.eq_projective(&projective, &curve.to_projective(&affine)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_diffie_hellman_on_tiny_curve() {
+        let curve = tiny_secp();
+        let generator = curve.point(&NativeBigInt::from(1u32), &NativeBigInt::from(28u32)).unwrap();
+        let order = curve.order_of(&generator);
+        assert_eq!(order, NativeBigInt::from(79u32));
+
+        for a in 1u32..20 {
+            for b in 1u32..20 {
+                let a = NativeBigInt::from(a);
+                let b = NativeBigInt::from(b);
+                let a_pub = curve.scalar_mul(&a, &generator);
+                let b_pub = curve.scalar_mul(&b, &generator);
+                assert_eq!(curve.scalar_mul(&a, &b_pub), curve.scalar_mul(&b, &a_pub));
+            }
+        }
+    }
+
+    #[test]
+    fn test_prime_order_group() {
+        let curve = tiny_curve_with_a();
+        let generator = curve.point(&NativeBigInt::from(3u32), &NativeBigInt::from(6u32)).unwrap();
+        let group = PrimeOrderGroup::new(curve.clone(), generator.clone(), NativeBigInt::from(5u32)).unwrap();
+        // #E = 100, so the subgroup of order 5 holds 5 of the 100 points
+        let members: Vec<_> = curve.points().into_iter().filter(|p| group.contains(p)).collect();
+        assert_eq!(members.len(), 5);
+        assert!(members.contains(&AffinePoint::Identity));
+
+        assert!(PrimeOrderGroup::new(curve.clone(), generator.clone(), NativeBigInt::from(25u32)).is_none());
+        assert!(PrimeOrderGroup::new(curve.clone(), generator, NativeBigInt::from(7u32)).is_none());
+        assert!(PrimeOrderGroup::new(curve, AffinePoint::Identity, NativeBigInt::from(5u32)).is_none());
+
+        let curve = tiny_secp();
+        let negative = AffinePoint::Point { x: NativeBigInt::from(1u32), y: NativeBigInt::from(28i32 - 97) };
+        assert!(!curve.is_on_curve(&negative));
+        assert_eq!(curve.encode_point(&AffinePoint::Identity), vec![0]);
+        assert_eq!(curve.encode_point(&curve.point(&NativeBigInt::from(1u32), &NativeBigInt::from(28u32)).unwrap()), vec![4, 1, 28]);
+    }
+}
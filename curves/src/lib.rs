@@ -1,4 +1,5 @@
 // #![feature(return_position_impl_trait_in_trait)]
 
 pub mod arithmetic;
-pub mod cryptology_primitives;
\ No newline at end of file
+pub mod cryptology_primitives;
+pub mod elliptic;
\ No newline at end of file